@@ -1,12 +1,18 @@
 //! Agent kind enum identifying supported AI coding agents.
 
+use semver::Version;
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
 use strum::IntoEnumIterator;
+use thiserror::Error;
 
 use crate::install::info::{
-    claude_code_install_info, codex_install_info, gemini_install_info, opencode_install_info,
+    claude_code_install_info, claude_code_uninstall_method, codex_install_info,
+    codex_uninstall_method, gemini_install_info, gemini_uninstall_method, opencode_install_info,
+    opencode_uninstall_method,
 };
-use crate::InstallInfo;
+use crate::{InstallInfo, Runtime, RuntimeRequirement, UninstallMethod};
 
 /// The type of AI coding agent.
 ///
@@ -100,6 +106,133 @@ impl AgentKind {
         }
     }
 
+    /// The oldest version of this agent that is usable with ACP.
+    ///
+    /// Detection reports an older install as
+    /// [`AgentStatus::VersionMismatch`](crate::AgentStatus::VersionMismatch)
+    /// unless `DetectOptions::min_version` overrides this floor. For Gemini
+    /// CLI and OpenCode this is the release that added their ACP mode; for
+    /// Claude Code and Codex it is the oldest release their ACP adapters
+    /// support. [`health_check`](crate::health_check) and [`detect_best`](crate::detect_best)
+    /// use the same floor when judging ACP support.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rig_acp_discovery::AgentKind;
+    /// use semver::Version;
+    ///
+    /// assert_eq!(
+    ///     AgentKind::ClaudeCode.min_supported_version(),
+    ///     Some(Version::new(2, 0, 0))
+    /// );
+    /// ```
+    pub fn min_supported_version(&self) -> Option<Version> {
+        match self {
+            Self::ClaudeCode => Some(Version::new(2, 0, 0)),
+            Self::Codex => Some(Version::new(0, 80, 0)),
+            Self::OpenCode => Some(Version::new(0, 15, 0)),
+            Self::Gemini => Some(Version::new(0, 2, 0)),
+        }
+    }
+
+    /// The winget package id for this agent, if it is distributed via winget.
+    ///
+    /// Used on Windows to locate agents installed through the Windows
+    /// Package Manager when they aren't on PATH.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rig_acp_discovery::AgentKind;
+    ///
+    /// assert_eq!(AgentKind::ClaudeCode.winget_id(), Some("Anthropic.ClaudeCode"));
+    /// assert_eq!(AgentKind::Codex.winget_id(), None);
+    /// ```
+    pub fn winget_id(&self) -> Option<&'static str> {
+        match self {
+            Self::ClaudeCode => Some("Anthropic.ClaudeCode"),
+            Self::Codex | Self::OpenCode | Self::Gemini => None,
+        }
+    }
+
+    /// Environment variable that overrides the executable location.
+    ///
+    /// When this variable is set to the path of an existing file, detection
+    /// uses it instead of searching PATH. If
+    /// [`bin_env_var`](Self::bin_env_var) is also set and names an existing
+    /// binary, that one wins and this variable is ignored.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rig_acp_discovery::AgentKind;
+    ///
+    /// assert_eq!(AgentKind::ClaudeCode.path_env_var(), "CLAUDE_PATH");
+    /// assert_eq!(AgentKind::Gemini.path_env_var(), "GEMINI_PATH");
+    /// ```
+    pub fn path_env_var(&self) -> &'static str {
+        match self {
+            Self::ClaudeCode => "CLAUDE_PATH",
+            Self::Codex => "CODEX_PATH",
+            Self::OpenCode => "OPENCODE_PATH",
+            Self::Gemini => "GEMINI_PATH",
+        }
+    }
+
+    /// Environment variable naming the binary to use for this agent.
+    ///
+    /// Follows the `RIG_ACP_<AGENT>_BIN` convention. It may be either a path
+    /// to an existing file, used directly, or a bare executable name such
+    /// as `claude-code`, which is looked up in place of the default name.
+    /// Detection is unchanged while it is unset.
+    ///
+    /// It takes precedence over [`path_env_var`](Self::path_env_var): when
+    /// both are set, this one is used. The `<AGENT>_PATH` variable is only
+    /// consulted if this one is unset or names a binary that can't be
+    /// found.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rig_acp_discovery::AgentKind;
+    ///
+    /// assert_eq!(AgentKind::ClaudeCode.bin_env_var(), "RIG_ACP_CLAUDE_BIN");
+    /// assert_eq!(AgentKind::OpenCode.bin_env_var(), "RIG_ACP_OPENCODE_BIN");
+    /// ```
+    pub fn bin_env_var(&self) -> &'static str {
+        match self {
+            Self::ClaudeCode => "RIG_ACP_CLAUDE_BIN",
+            Self::Codex => "RIG_ACP_CODEX_BIN",
+            Self::OpenCode => "RIG_ACP_OPENCODE_BIN",
+            Self::Gemini => "RIG_ACP_GEMINI_BIN",
+        }
+    }
+
+    /// GitHub repository of the agent's source code, for open-source agents.
+    ///
+    /// Useful for linking to the agent's issue tracker or contribution
+    /// guide. Returns `None` for agents whose source is not published
+    /// (Claude Code). This is separate from `InstallInfo::docs_url`, which
+    /// points at installation documentation.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rig_acp_discovery::AgentKind;
+    ///
+    /// assert_eq!(AgentKind::Codex.source_repo(), Some("https://github.com/openai/codex"));
+    /// assert_eq!(AgentKind::ClaudeCode.source_repo(), None);
+    /// ```
+    pub fn source_repo(&self) -> Option<&'static str> {
+        match self {
+            Self::ClaudeCode => None,
+            Self::Codex => Some("https://github.com/openai/codex"),
+            Self::OpenCode => Some("https://github.com/anomalyco/opencode"),
+            Self::Gemini => Some("https://github.com/google-gemini/gemini-cli"),
+        }
+    }
+
     /// Iterator over all known agent kinds.
     ///
     /// This is useful for detecting all agents or building selection UIs.
@@ -116,6 +249,151 @@ impl AgentKind {
         <Self as IntoEnumIterator>::iter()
     }
 
+    /// All agents in default order of preference.
+    ///
+    /// This is the order [`detect_default`](crate::detect_default) uses to
+    /// pick an agent when several are installed. Agents are ranked by the
+    /// maturity of their ACP integration, since that is what rig-acp
+    /// drives:
+    ///
+    /// 1. Claude Code: ACP adapter with full tool use and file edit support
+    /// 2. Gemini CLI: first agent to ship native ACP support
+    /// 3. Codex: ACP support via adapter, still evolving
+    /// 4. OpenCode: newest ACP integration
+    ///
+    /// Callers with different preferences can pass their own order to
+    /// [`detect_default_with_order`](crate::detect_default_with_order).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rig_acp_discovery::AgentKind;
+    ///
+    /// let order = AgentKind::preferred_order();
+    /// assert_eq!(order[0], AgentKind::ClaudeCode);
+    /// assert_eq!(order.len(), AgentKind::all().count());
+    /// ```
+    pub fn preferred_order() -> Vec<Self> {
+        vec![Self::ClaudeCode, Self::Gemini, Self::Codex, Self::OpenCode]
+    }
+
+    /// Check whether this agent supports the current OS and architecture.
+    ///
+    /// This is derived from `std::env::consts` alone, without spawning
+    /// processes or touching the filesystem, so it can pre-filter agents
+    /// before the heavier [`can_install`](crate::can_install). Agents that
+    /// ship native binaries support Linux, macOS and Windows on x86_64 and
+    /// aarch64; Gemini CLI is a pure Node.js package and runs on any
+    /// architecture of those systems.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rig_acp_discovery::AgentKind;
+    ///
+    /// let supported: Vec<_> = AgentKind::all().filter(|k| k.is_supported_here()).collect();
+    /// println!("Installable here: {:?}", supported);
+    /// ```
+    pub fn is_supported_here(&self) -> bool {
+        self.is_supported_on(std::env::consts::OS, std::env::consts::ARCH)
+    }
+
+    /// Whether this agent supports the given `std::env::consts` OS and arch.
+    fn is_supported_on(&self, os: &str, arch: &str) -> bool {
+        if !matches!(os, "linux" | "macos" | "windows") {
+            return false;
+        }
+        match self {
+            Self::Gemini => true,
+            Self::ClaudeCode | Self::Codex | Self::OpenCode => {
+                matches!(arch, "x86_64" | "aarch64")
+            }
+        }
+    }
+
+    /// Check whether two versions of this agent are interchangeable.
+    ///
+    /// Each agent follows its own release policy:
+    ///
+    /// - Claude Code and OpenCode (1.0 and later): compatible within the
+    ///   same major version
+    /// - Codex and Gemini CLI (still 0.x): minor releases can break
+    ///   behavior, so versions must share the same major and minor
+    ///
+    /// Pre-release and build metadata are ignored.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rig_acp_discovery::AgentKind;
+    /// use semver::Version;
+    ///
+    /// let expected = Version::new(0, 87, 0);
+    /// assert!(AgentKind::Codex.is_compatible(&expected, &Version::new(0, 87, 3)));
+    /// assert!(!AgentKind::Codex.is_compatible(&expected, &Version::new(0, 88, 0)));
+    /// ```
+    pub fn is_compatible(&self, a: &Version, b: &Version) -> bool {
+        match self {
+            Self::ClaudeCode | Self::OpenCode => a.major == b.major,
+            Self::Codex | Self::Gemini => a.major == b.major && a.minor == b.minor,
+        }
+    }
+
+    /// The language runtime this agent needs, if any.
+    ///
+    /// Codex and Gemini CLI are npm packages that run on Node.js (18+ and
+    /// 20+ respectively). Claude Code and OpenCode ship native binaries and
+    /// need no runtime.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rig_acp_discovery::{AgentKind, Runtime};
+    ///
+    /// let requirement = AgentKind::Codex.runtime_requirement().unwrap();
+    /// assert_eq!(requirement.runtime, Runtime::Node);
+    /// assert_eq!(requirement.min_version.major, 18);
+    /// assert!(AgentKind::ClaudeCode.runtime_requirement().is_none());
+    /// ```
+    pub fn runtime_requirement(&self) -> Option<RuntimeRequirement> {
+        let min_version = match self {
+            Self::Codex => Version::new(18, 0, 0),
+            Self::Gemini => Version::new(20, 0, 0),
+            Self::ClaudeCode | Self::OpenCode => return None,
+        };
+        Some(RuntimeRequirement {
+            runtime: Runtime::Node,
+            min_version,
+        })
+    }
+
+    /// Recommended reasoning level to suggest when configuring this agent.
+    ///
+    /// Returns `None` for agents without a named reasoning level setting.
+    /// The value uses the agent's own naming, so it can be written to the
+    /// agent's configuration as-is and compared with
+    /// `InstalledMetadata::reasoning_level`:
+    ///
+    /// - Codex: `"medium"` (`model_reasoning_effort`, one of `minimal`,
+    ///   `low`, `medium`, `high`), balancing quality and latency
+    /// - Claude Code, OpenCode and Gemini CLI: `None` (thinking is
+    ///   controlled per prompt or by the model provider)
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rig_acp_discovery::AgentKind;
+    ///
+    /// assert_eq!(AgentKind::Codex.default_reasoning_level(), Some("medium"));
+    /// assert_eq!(AgentKind::Gemini.default_reasoning_level(), None);
+    /// ```
+    pub fn default_reasoning_level(&self) -> Option<&'static str> {
+        match self {
+            Self::Codex => Some("medium"),
+            Self::ClaudeCode | Self::OpenCode | Self::Gemini => None,
+        }
+    }
+
     /// Get installation information for this agent.
     ///
     /// Returns platform-appropriate installation instructions including
@@ -139,12 +417,220 @@ impl AgentKind {
             Self::Gemini => gemini_install_info(),
         }
     }
+
+    /// Get the command that uninstalls this agent, if there is one.
+    ///
+    /// Returns `None` for agents installed by a native installer without
+    /// an uninstall command (Claude Code); see the agent's
+    /// `InstallInfo::docs_url` for how to remove those. This removes the
+    /// agent's primary install; `uninstall()` uses it only when it can't
+    /// tell which package manager installed the agent.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rig_acp_discovery::AgentKind;
+    ///
+    /// let method = AgentKind::Codex.uninstall_method().unwrap();
+    /// assert_eq!(method.raw_command, "npm uninstall -g @openai/codex");
+    /// assert!(AgentKind::ClaudeCode.uninstall_method().is_none());
+    /// ```
+    pub fn uninstall_method(&self) -> Option<UninstallMethod> {
+        match self {
+            Self::ClaudeCode => claude_code_uninstall_method(),
+            Self::Codex => codex_uninstall_method(),
+            Self::OpenCode => opencode_uninstall_method(),
+            Self::Gemini => gemini_uninstall_method(),
+        }
+    }
+}
+
+/// Names accepted by [`AgentKind::from_str`], with the agent each maps to.
+///
+/// Matching is case-insensitive.
+const AGENT_NAMES: &[(&str, AgentKind)] = &[
+    ("claude", AgentKind::ClaudeCode),
+    ("claude-code", AgentKind::ClaudeCode),
+    ("codex", AgentKind::Codex),
+    ("opencode", AgentKind::OpenCode),
+    ("gemini", AgentKind::Gemini),
+    ("gemini-cli", AgentKind::Gemini),
+];
+
+/// Check that no two agents share an executable name or alias.
+///
+/// Every agent's [`executable_name`](AgentKind::executable_name) and the
+/// aliases accepted when parsing an [`AgentKind`] are compared,
+/// ignoring case. A shared name would make PATH lookups and parsing pick
+/// one agent for the other, so applications that extend the registry can
+/// call this at startup to catch collisions early.
+///
+/// # Returns
+///
+/// - `Ok(())` if every name belongs to one agent
+/// - `Err(pairs)` listing each pair of agents that share a name, in
+///   [`AgentKind::all`] order
+///
+/// # Example
+///
+/// ```rust
+/// use rig_acp_discovery::validate_executable_names;
+///
+/// assert!(validate_executable_names().is_ok());
+/// ```
+pub fn validate_executable_names() -> Result<(), Vec<(AgentKind, AgentKind)>> {
+    let names: Vec<(&str, AgentKind)> = AgentKind::all()
+        .map(|kind| (kind.executable_name(), kind))
+        .chain(AGENT_NAMES.iter().copied())
+        .collect();
+    name_collisions(&names)
+}
+
+/// Pairs of different agents sharing a name in `names`.
+fn name_collisions(names: &[(&str, AgentKind)]) -> Result<(), Vec<(AgentKind, AgentKind)>> {
+    let names_of = |kind: AgentKind| names.iter().filter(move |(_, k)| *k == kind);
+    let kinds: Vec<AgentKind> = AgentKind::all().collect();
+    let mut pairs = Vec::new();
+    for (i, &kind) in kinds.iter().enumerate() {
+        for &other in &kinds[i + 1..] {
+            let shared = names_of(kind).any(|(name, _)| {
+                names_of(other).any(|(other_name, _)| name.eq_ignore_ascii_case(other_name))
+            });
+            if shared {
+                pairs.push((kind, other));
+            }
+        }
+    }
+    if pairs.is_empty() {
+        Ok(())
+    } else {
+        Err(pairs)
+    }
+}
+
+/// Formats the agent's [`display_name`](AgentKind::display_name).
+impl fmt::Display for AgentKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.display_name())
+    }
+}
+
+/// Parses an agent from its executable name or a common alias.
+///
+/// Accepts `claude`, `claude-code`, `codex`, `opencode`, `gemini` and
+/// `gemini-cli`, ignoring case.
+///
+/// # Example
+///
+/// ```rust
+/// use rig_acp_discovery::AgentKind;
+///
+/// let kind: AgentKind = "Claude-Code".parse().unwrap();
+/// assert_eq!(kind, AgentKind::ClaudeCode);
+/// assert_eq!(kind.to_string(), "Claude Code");
+///
+/// assert!("cursor".parse::<AgentKind>().is_err());
+/// ```
+impl FromStr for AgentKind {
+    type Err = ParseAgentKindError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        AGENT_NAMES
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(s))
+            .map(|(_, kind)| *kind)
+            .ok_or_else(|| ParseAgentKindError {
+                input: s.to_string(),
+            })
+    }
+}
+
+/// Error returned when parsing an unknown agent name into an [`AgentKind`].
+///
+/// Its message lists the accepted names.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("unknown agent '{input}' (expected one of: {})", accepted_names())]
+pub struct ParseAgentKindError {
+    input: String,
+}
+
+impl ParseAgentKindError {
+    /// The name that failed to parse.
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+}
+
+fn accepted_names() -> String {
+    let names: Vec<&str> = AGENT_NAMES.iter().map(|(name, _)| *name).collect();
+    names.join(", ")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_display_from_str_round_trip() {
+        for kind in AgentKind::all() {
+            assert_eq!(kind.to_string(), kind.display_name());
+            assert_eq!(kind.executable_name().parse::<AgentKind>(), Ok(kind));
+        }
+    }
+
+    #[test]
+    fn test_from_str_aliases_and_case() {
+        assert_eq!("claude-code".parse(), Ok(AgentKind::ClaudeCode));
+        assert_eq!("CLAUDE".parse(), Ok(AgentKind::ClaudeCode));
+        assert_eq!("Gemini-CLI".parse(), Ok(AgentKind::Gemini));
+        assert_eq!("OpenCode".parse(), Ok(AgentKind::OpenCode));
+    }
+
+    #[test]
+    fn test_source_repo() {
+        for kind in [AgentKind::Codex, AgentKind::OpenCode, AgentKind::Gemini] {
+            let repo = kind.source_repo().unwrap();
+            assert!(repo.starts_with("https://github.com/"), "{}", repo);
+        }
+        assert_eq!(AgentKind::ClaudeCode.source_repo(), None);
+    }
+
+    #[test]
+    fn test_executable_names_are_distinct() {
+        assert_eq!(validate_executable_names(), Ok(()));
+    }
+
+    #[test]
+    fn test_name_collisions_reported() {
+        let names = [
+            ("claude", AgentKind::ClaudeCode),
+            ("codex", AgentKind::Codex),
+            ("Codex", AgentKind::OpenCode),
+            ("opencode", AgentKind::OpenCode),
+            ("codex", AgentKind::Gemini),
+            ("codex", AgentKind::Codex),
+        ];
+        assert_eq!(
+            name_collisions(&names),
+            Err(vec![
+                (AgentKind::Codex, AgentKind::OpenCode),
+                (AgentKind::Codex, AgentKind::Gemini),
+                (AgentKind::OpenCode, AgentKind::Gemini),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_from_str_unknown() {
+        let error = "cursor".parse::<AgentKind>().unwrap_err();
+        assert_eq!(error.input(), "cursor");
+        let message = error.to_string();
+        assert!(message.contains("'cursor'"), "{}", message);
+        for (name, _) in AGENT_NAMES {
+            assert!(message.contains(name), "{}", message);
+        }
+    }
+
     #[test]
     fn test_executable_names() {
         assert_eq!(AgentKind::ClaudeCode.executable_name(), "claude");
@@ -161,6 +647,65 @@ mod tests {
         assert_eq!(AgentKind::Gemini.display_name(), "Gemini CLI");
     }
 
+    #[test]
+    fn test_min_supported_versions() {
+        for kind in AgentKind::all() {
+            let version = kind
+                .min_supported_version()
+                .unwrap_or_else(|| panic!("{:?} has no minimum version", kind));
+            assert_eq!(Version::parse(&version.to_string()).unwrap(), version);
+        }
+        assert_eq!(
+            AgentKind::Codex.min_supported_version(),
+            Some(Version::new(0, 80, 0))
+        );
+    }
+
+    #[test]
+    fn test_path_env_vars() {
+        assert_eq!(AgentKind::ClaudeCode.path_env_var(), "CLAUDE_PATH");
+        assert_eq!(AgentKind::Codex.path_env_var(), "CODEX_PATH");
+        assert_eq!(AgentKind::OpenCode.path_env_var(), "OPENCODE_PATH");
+        assert_eq!(AgentKind::Gemini.path_env_var(), "GEMINI_PATH");
+    }
+
+    #[test]
+    fn test_bin_env_vars() {
+        assert_eq!(AgentKind::ClaudeCode.bin_env_var(), "RIG_ACP_CLAUDE_BIN");
+        assert_eq!(AgentKind::Codex.bin_env_var(), "RIG_ACP_CODEX_BIN");
+        assert_eq!(AgentKind::OpenCode.bin_env_var(), "RIG_ACP_OPENCODE_BIN");
+        assert_eq!(AgentKind::Gemini.bin_env_var(), "RIG_ACP_GEMINI_BIN");
+    }
+
+    #[test]
+    fn test_winget_ids() {
+        assert_eq!(
+            AgentKind::ClaudeCode.winget_id(),
+            Some("Anthropic.ClaudeCode")
+        );
+        assert_eq!(AgentKind::Codex.winget_id(), None);
+        assert_eq!(AgentKind::OpenCode.winget_id(), None);
+        assert_eq!(AgentKind::Gemini.winget_id(), None);
+    }
+
+    #[test]
+    fn test_preferred_order_is_stable_and_complete() {
+        let order = AgentKind::preferred_order();
+        assert_eq!(
+            order,
+            [
+                AgentKind::ClaudeCode,
+                AgentKind::Gemini,
+                AgentKind::Codex,
+                AgentKind::OpenCode
+            ]
+        );
+        assert_eq!(order.len(), AgentKind::all().count());
+        for kind in AgentKind::all() {
+            assert!(order.contains(&kind), "{:?} missing", kind);
+        }
+    }
+
     #[test]
     fn test_all_iterator() {
         let all: Vec<_> = AgentKind::all().collect();
@@ -190,4 +735,63 @@ mod tests {
         let deserialized: AgentKind = serde_json::from_str(&json).unwrap();
         assert_eq!(deserialized, AgentKind::ClaudeCode);
     }
+
+    #[test]
+    fn test_is_supported_here() {
+        // Pure check: every agent answers without spawning anything
+        for kind in AgentKind::all() {
+            let _ = kind.is_supported_here();
+        }
+        if cfg!(all(
+            any(
+                target_os = "linux",
+                target_os = "macos",
+                target_os = "windows"
+            ),
+            any(target_arch = "x86_64", target_arch = "aarch64")
+        )) {
+            assert!(AgentKind::all().all(|k| k.is_supported_here()));
+        }
+    }
+
+    #[test]
+    fn test_is_supported_on() {
+        assert!(AgentKind::ClaudeCode.is_supported_on("macos", "aarch64"));
+        assert!(AgentKind::Codex.is_supported_on("windows", "x86_64"));
+        assert!(!AgentKind::OpenCode.is_supported_on("linux", "riscv64"));
+        assert!(AgentKind::Gemini.is_supported_on("linux", "riscv64"));
+        assert!(!AgentKind::Gemini.is_supported_on("freebsd", "x86_64"));
+    }
+
+    #[test]
+    fn test_default_reasoning_level() {
+        // Agents with a reasoning setting suggest a non-empty level
+        assert_eq!(AgentKind::Codex.default_reasoning_level(), Some("medium"));
+        for kind in AgentKind::all() {
+            if let Some(level) = kind.default_reasoning_level() {
+                assert!(!level.is_empty(), "{:?} suggests an empty level", kind);
+            }
+        }
+        assert!(AgentKind::ClaudeCode.default_reasoning_level().is_none());
+    }
+
+    #[test]
+    fn test_is_compatible_same_major_agents() {
+        let v = |s: &str| Version::parse(s).unwrap();
+        for kind in [AgentKind::ClaudeCode, AgentKind::OpenCode] {
+            assert!(kind.is_compatible(&v("2.1.12"), &v("2.4.0")));
+            assert!(kind.is_compatible(&v("2.1.12"), &v("2.1.12-beta.1")));
+            assert!(!kind.is_compatible(&v("2.1.12"), &v("3.0.0")));
+        }
+    }
+
+    #[test]
+    fn test_is_compatible_same_minor_agents() {
+        let v = |s: &str| Version::parse(s).unwrap();
+        for kind in [AgentKind::Codex, AgentKind::Gemini] {
+            assert!(kind.is_compatible(&v("0.24.1"), &v("0.24.4")));
+            assert!(!kind.is_compatible(&v("0.24.1"), &v("0.25.0")));
+            assert!(!kind.is_compatible(&v("0.24.1"), &v("1.24.1")));
+        }
+    }
 }
@@ -1,7 +1,12 @@
 //! Agent status types representing detection results.
 
+use crate::{BinaryType, Capabilities};
 use semver::Version;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::convert::Infallible;
+use std::fmt;
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use std::time::SystemTime;
 
 /// Metadata for an installed agent.
@@ -17,7 +22,9 @@ use std::time::SystemTime;
 ///
 /// Both fields are `Option` to support graceful degradation when version
 /// parsing fails. An agent can be usable even without a parsed version.
-#[derive(Debug, Clone)]
+/// Use `version_skipped` to tell a skipped version check apart from a
+/// failed parse.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InstalledMetadata {
     /// Path to the executable.
     pub path: PathBuf,
@@ -35,10 +42,10 @@ pub struct InstalledMetadata {
     /// parse failure, unless version detection was skipped entirely.
     pub raw_version: Option<String>,
 
-    /// How the agent was installed (e.g., "npm", "cargo", "manual").
+    /// How the agent was installed, guessed from its path.
     ///
     /// This is `None` if the installation method couldn't be determined.
-    pub install_method: Option<String>,
+    pub install_method: Option<InstallMethodKind>,
 
     /// When detection was last verified.
     ///
@@ -52,6 +59,164 @@ pub struct InstalledMetadata {
     /// stores the raw string from the agent. `None` indicates the agent
     /// doesn't support reasoning levels.
     pub reasoning_level: Option<String>,
+
+    /// Agent's configured default model (e.g., "claude-sonnet-4").
+    ///
+    /// Read from the agent's configuration file when
+    /// `DetectOptions::probe_default_model` is enabled. `None` if probing
+    /// was disabled or no model is configured.
+    pub default_model: Option<String>,
+
+    /// Whether the version check was skipped during detection.
+    ///
+    /// This is `true` only when detection ran with `DetectOptions::skip_version`,
+    /// distinguishing "version skipped" from "version could not be parsed"
+    /// when `version` is `None`.
+    pub version_skipped: bool,
+
+    /// CPU architecture the executable was built for (e.g., "x86_64", "aarch64").
+    ///
+    /// Read from the executable header when `DetectOptions::probe_arch` is
+    /// enabled. `None` if probing was disabled or the executable is not a
+    /// native binary (such as a Node.js shim script).
+    pub binary_arch: Option<String>,
+
+    /// Whether the executable is a native binary or a script wrapper.
+    ///
+    /// Read from the executable header on every detection; unlike
+    /// `binary_arch`, no option is needed. A Node.js or shell wrapper
+    /// explains behavior such as a slow start-up. `None` if the file
+    /// couldn't be read.
+    pub binary_type: Option<BinaryType>,
+
+    /// ACP capabilities the agent advertises (e.g., "loadSession",
+    /// "promptCapabilities.image").
+    ///
+    /// Read from the agent's ACP `initialize` response when
+    /// `DetectOptions::probe_acp_capabilities` is enabled. Empty if probing
+    /// was disabled, the agent has no native ACP mode, or the handshake
+    /// failed.
+    pub acp_capabilities: Vec<String>,
+
+    /// Schema version of the agent's configuration file (e.g., "2" or a
+    /// `$schema` URL).
+    ///
+    /// Read from the agent's config file when
+    /// `DetectOptions::probe_config_schema` is enabled. `None` if probing
+    /// was disabled, the config doesn't exist, or it has no version.
+    pub config_schema_version: Option<String>,
+
+    /// Capabilities the agent reports about itself (streaming, tools,
+    /// context size).
+    ///
+    /// Read from `<agent> capabilities --json` when
+    /// `DetectOptions::probe_capabilities` is enabled. `None` if probing
+    /// was disabled or the agent has no capabilities subcommand.
+    pub capabilities: Option<Capabilities>,
+
+    /// The agent's edition or license tier (e.g., "Pro", "Enterprise").
+    ///
+    /// Read from the `--version` output or the agent's config file when
+    /// `DetectOptions::probe_edition` is enabled. `None` if probing was
+    /// disabled or no edition is indicated.
+    pub edition: Option<String>,
+}
+
+/// How an installed agent was installed.
+///
+/// Detection infers this from where the executable lives, such as a
+/// `node_modules` or `.cargo` directory. The `Display` form is the
+/// lowercase name (e.g., "npm", "brew"), and it serializes as that string,
+/// with `Unknown` methods serialized as their bare name. `FromStr` parses
+/// it back and never fails: an unrecognized name becomes `Unknown`.
+///
+/// This enum is marked `#[non_exhaustive]` to allow recognizing more
+/// package managers in future versions.
+///
+/// # Example
+///
+/// ```rust
+/// use rig_acp_discovery::InstallMethodKind;
+///
+/// assert_eq!(InstallMethodKind::Npm.to_string(), "npm");
+/// assert_eq!(InstallMethodKind::Unknown("nix".to_string()).to_string(), "nix");
+///
+/// let method: InstallMethodKind = "nix".parse().unwrap();
+/// assert_eq!(method, InstallMethodKind::Unknown("nix".to_string()));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum InstallMethodKind {
+    /// Installed with npm (a global `node_modules` or npm prefix).
+    Npm,
+    /// Installed with `cargo install`.
+    Cargo,
+    /// Installed with Homebrew or Linuxbrew.
+    Brew,
+    /// Installed with mise.
+    Mise,
+    /// Installed with `go install` into a Go bin directory.
+    Go,
+    /// Installed with Scoop (Windows).
+    Scoop,
+    /// Installed with Chocolatey (Windows).
+    Chocolatey,
+    /// Installed with winget or from the Microsoft Store (Windows).
+    Winget,
+    /// Installed by the agent's own installer into its home directory.
+    Native,
+    /// Any other method, by name.
+    Unknown(String),
+}
+
+impl fmt::Display for InstallMethodKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Npm => "npm",
+            Self::Cargo => "cargo",
+            Self::Brew => "brew",
+            Self::Mise => "mise",
+            Self::Go => "go",
+            Self::Scoop => "scoop",
+            Self::Chocolatey => "chocolatey",
+            Self::Winget => "winget",
+            Self::Native => "native",
+            Self::Unknown(name) => name,
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for InstallMethodKind {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "npm" => Self::Npm,
+            "cargo" => Self::Cargo,
+            "brew" => Self::Brew,
+            "mise" => Self::Mise,
+            "go" => Self::Go,
+            "scoop" => Self::Scoop,
+            "chocolatey" => Self::Chocolatey,
+            "winget" => Self::Winget,
+            "native" => Self::Native,
+            other => Self::Unknown(other.to_string()),
+        })
+    }
+}
+
+impl Serialize for InstallMethodKind {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for InstallMethodKind {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Ok(name.parse().unwrap_or_else(|never| match never {}))
+    }
 }
 
 /// Typed error variants for detection failures.
@@ -61,7 +226,7 @@ pub struct InstalledMetadata {
 ///
 /// This enum is marked `#[non_exhaustive]` to allow adding new error types
 /// in future versions.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[non_exhaustive]
 pub enum DetectionError {
     /// Timed out while detecting the agent.
@@ -75,6 +240,48 @@ pub enum DetectionError {
 
     /// I/O error during detection (e.g., failed to execute command).
     IoError,
+
+    /// A blocking detection function was called from within an async runtime.
+    ///
+    /// Use the async detection functions when a runtime is available.
+    NestedRuntime,
+
+    /// The executable exists but is empty (zero bytes).
+    ///
+    /// This usually means a download was interrupted; reinstalling the
+    /// agent fixes it.
+    CorruptInstall,
+
+    /// The executable is quarantined by macOS Gatekeeper and cannot run.
+    ///
+    /// Removing the `com.apple.quarantine` attribute with
+    /// `xattr -d com.apple.quarantine <path>` fixes it.
+    Quarantined,
+
+    /// The agent's version is below the required minimum.
+    ///
+    /// Only returned by `detect_all` and its variants, in place of an
+    /// `Ok(AgentStatus::VersionMismatch)`, when
+    /// `DetectOptions::mismatch_is_error` is set.
+    VersionUnsupported {
+        /// The version that was found.
+        found: Version,
+        /// The required minimum version.
+        required: Version,
+    },
+
+    /// Detection was cancelled through the token passed to `detect_with_cancel()`.
+    Cancelled,
+
+    /// The version check kept failing after `DetectOptions::retries` retries.
+    ///
+    /// Only returned when more than one attempt was made.
+    Retried {
+        /// Total number of attempts made, including the first.
+        attempts: u8,
+        /// The error from the final attempt.
+        last: Box<DetectionError>,
+    },
 }
 
 impl DetectionError {
@@ -96,6 +303,12 @@ impl DetectionError {
             Self::PermissionDenied => "Permission denied",
             Self::VersionParseFailed => "Failed to parse version",
             Self::IoError => "I/O error during detection",
+            Self::NestedRuntime => "Blocking detection called from within an async runtime",
+            Self::CorruptInstall => "Executable is empty or corrupted",
+            Self::Quarantined => "Executable is quarantined by Gatekeeper",
+            Self::VersionUnsupported { .. } => "Installed version is below the required minimum",
+            Self::Cancelled => "Detection was cancelled",
+            Self::Retried { last, .. } => last.description(),
         }
     }
 }
@@ -117,6 +330,12 @@ impl DetectionError {
 /// This enum is marked `#[non_exhaustive]` to allow adding new status types
 /// in future versions.
 ///
+/// # Serialization
+///
+/// `AgentStatus`, [`InstalledMetadata`] and [`DetectionError`] implement
+/// serde's `Serialize` and `Deserialize`, so detection results can be sent
+/// to another process or persisted and read back unchanged.
+///
 /// # Example
 ///
 /// ```rust
@@ -132,7 +351,7 @@ impl DetectionError {
 ///     }
 /// }
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[non_exhaustive]
 pub enum AgentStatus {
     /// Agent is installed and usable.
@@ -160,6 +379,18 @@ pub enum AgentStatus {
     },
 }
 
+/// Status predicates.
+///
+/// | Variant           | `is_usable` | `is_installed` / `is_present` |
+/// |-------------------|-------------|-------------------------------|
+/// | `Installed`       | `true`      | `true`                        |
+/// | `VersionMismatch` | `false`     | `true`                        |
+/// | `NotInstalled`    | `false`     | `false`                       |
+/// | `Unknown`         | `false`     | `false`                       |
+///
+/// Use `is_usable` to gate launching an agent, and `is_present` for
+/// display-only flows that show a mismatched agent as "present (needs
+/// update)" and let the user proceed.
 impl AgentStatus {
     /// Check if the agent is usable (installed and correct version).
     ///
@@ -195,6 +426,32 @@ impl AgentStatus {
         matches!(self, Self::Installed(_) | Self::VersionMismatch { .. })
     }
 
+    /// Check if the agent is present on the system, even if it needs an update.
+    ///
+    /// This is the same as [`is_installed`](Self::is_installed), named for
+    /// display flows: a `VersionMismatch` agent is present but not usable,
+    /// so it can be shown as "present (needs update)". Use
+    /// [`is_usable`](Self::is_usable) to decide whether the agent can be
+    /// launched as-is.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rig_acp_discovery::AgentStatus;
+    /// use semver::Version;
+    ///
+    /// let status = AgentStatus::VersionMismatch {
+    ///     found: Version::new(0, 1, 0),
+    ///     required: Version::new(1, 0, 0),
+    ///     path: "/usr/bin/claude".into(),
+    /// };
+    /// assert!(status.is_present());
+    /// assert!(!status.is_usable());
+    /// ```
+    pub fn is_present(&self) -> bool {
+        self.is_installed()
+    }
+
     /// Get the path to the agent executable if available.
     ///
     /// Returns `Some(&Path)` for `Installed` and `VersionMismatch` variants,
@@ -237,6 +494,59 @@ impl AgentStatus {
             _ => None,
         }
     }
+
+    /// Check whether two statuses describe the same installation.
+    ///
+    /// Compares install identity only: the executable path and, when both
+    /// statuses carry metadata, the install method. Version information is
+    /// ignored entirely, so a `skip_version` result and a full result for
+    /// the same executable are the same install. Returns `false` if either
+    /// status has no path (`NotInstalled` or `Unknown`).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rig_acp_discovery::AgentStatus;
+    ///
+    /// assert!(!AgentStatus::NotInstalled.same_install(&AgentStatus::NotInstalled));
+    /// ```
+    pub fn same_install(&self, other: &AgentStatus) -> bool {
+        match (self.path(), other.path()) {
+            (Some(a), Some(b)) if a == b => match (self, other) {
+                (Self::Installed(a), Self::Installed(b)) => a.install_method == b.install_method,
+                _ => true,
+            },
+            _ => false,
+        }
+    }
+
+    /// Map the status to a process exit code for scripting.
+    ///
+    /// | Variant           | Exit code |
+    /// |-------------------|-----------|
+    /// | `Installed`       | `0`       |
+    /// | `NotInstalled`    | `1`       |
+    /// | `VersionMismatch` | `2`       |
+    /// | `Unknown`         | `3`       |
+    ///
+    /// This lets a `check` command exit 0 only when the agent is usable,
+    /// while scripts can still distinguish why it is not.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rig_acp_discovery::AgentStatus;
+    ///
+    /// assert_eq!(AgentStatus::NotInstalled.to_exit_code(), 1);
+    /// ```
+    pub fn to_exit_code(&self) -> i32 {
+        match self {
+            Self::Installed(_) => 0,
+            Self::NotInstalled => 1,
+            Self::VersionMismatch { .. } => 2,
+            Self::Unknown { .. } => 3,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -245,23 +555,25 @@ mod tests {
 
     fn make_installed_metadata() -> InstalledMetadata {
         InstalledMetadata {
-            path: PathBuf::from("/usr/bin/claude"),
-            version: Some(Version::parse("1.2.3").unwrap()),
             raw_version: Some("v1.2.3".to_string()),
-            install_method: Some("npm".to_string()),
-            last_verified: SystemTime::now(),
+            install_method: Some(InstallMethodKind::Npm),
             reasoning_level: Some("high".to_string()),
+            default_model: Some("claude-sonnet-4".to_string()),
+            binary_arch: Some("aarch64".to_string()),
+            binary_type: Some(BinaryType::NativeExecutable),
+            acp_capabilities: vec!["loadSession".to_string()],
+            ..crate::test_util::installed_meta(
+                "/usr/bin/claude",
+                Some(Version::parse("1.2.3").unwrap()),
+            )
         }
     }
 
     fn make_installed_metadata_no_version() -> InstalledMetadata {
         InstalledMetadata {
-            path: PathBuf::from("/usr/bin/claude"),
-            version: None,
             raw_version: Some("unknown-version-format".to_string()),
-            install_method: Some("npm".to_string()),
-            last_verified: SystemTime::now(),
-            reasoning_level: None,
+            install_method: Some(InstallMethodKind::Npm),
+            ..crate::test_util::installed_meta("/usr/bin/claude", None)
         }
     }
 
@@ -328,6 +640,26 @@ mod tests {
             DetectionError::IoError.description(),
             "I/O error during detection"
         );
+        assert_eq!(
+            DetectionError::NestedRuntime.description(),
+            "Blocking detection called from within an async runtime"
+        );
+        assert_eq!(
+            DetectionError::CorruptInstall.description(),
+            "Executable is empty or corrupted"
+        );
+        assert_eq!(
+            DetectionError::Quarantined.description(),
+            "Executable is quarantined by Gatekeeper"
+        );
+        assert_eq!(
+            DetectionError::Retried {
+                attempts: 3,
+                last: Box::new(DetectionError::Timeout)
+            }
+            .description(),
+            "Detection timed out"
+        );
     }
 
     #[test]
@@ -336,6 +668,95 @@ mod tests {
         assert_ne!(DetectionError::Timeout, DetectionError::IoError);
     }
 
+    #[test]
+    fn test_install_method_kind_json_round_trip() {
+        let methods = [
+            InstallMethodKind::Npm,
+            InstallMethodKind::Native,
+            InstallMethodKind::Unknown("nix".to_string()),
+        ];
+        for method in methods {
+            let json = serde_json::to_string(&method).unwrap();
+            let back: InstallMethodKind = serde_json::from_str(&json).unwrap();
+            assert_eq!(back, method);
+        }
+        assert_eq!(
+            serde_json::to_string(&InstallMethodKind::Brew).unwrap(),
+            "\"brew\""
+        );
+        // Serialized as the Display string, not a tagged variant
+        assert_eq!(
+            serde_json::to_string(&InstallMethodKind::Unknown("nix".to_string())).unwrap(),
+            "\"nix\""
+        );
+    }
+
+    #[test]
+    fn test_install_method_kind_from_str() {
+        let methods = [
+            InstallMethodKind::Npm,
+            InstallMethodKind::Chocolatey,
+            InstallMethodKind::Native,
+            InstallMethodKind::Unknown("nix".to_string()),
+        ];
+        for method in methods {
+            assert_eq!(method.to_string().parse(), Ok(method));
+        }
+    }
+
+    #[test]
+    fn test_installed_status_json_round_trip() {
+        let meta = InstalledMetadata {
+            version_skipped: true,
+            config_schema_version: Some("3".to_string()),
+            edition: Some("Pro".to_string()),
+            ..make_installed_metadata()
+        };
+        let json = serde_json::to_string(&AgentStatus::Installed(meta.clone())).unwrap();
+
+        let AgentStatus::Installed(back) = serde_json::from_str(&json).unwrap() else {
+            panic!("Expected Installed from {}", json);
+        };
+        assert_eq!(back.path, meta.path);
+        assert_eq!(back.version, meta.version);
+        assert_eq!(back.raw_version, meta.raw_version);
+        assert_eq!(back.install_method, meta.install_method);
+        assert_eq!(back.last_verified, meta.last_verified);
+        assert_eq!(back.reasoning_level, meta.reasoning_level);
+        assert_eq!(back.default_model, meta.default_model);
+        assert_eq!(back.version_skipped, meta.version_skipped);
+        assert_eq!(back.binary_arch, meta.binary_arch);
+        assert_eq!(back.binary_type, meta.binary_type);
+        assert_eq!(back.acp_capabilities, meta.acp_capabilities);
+        assert_eq!(back.config_schema_version, meta.config_schema_version);
+        assert_eq!(back.capabilities, meta.capabilities);
+        assert_eq!(back.edition, meta.edition);
+    }
+
+    #[test]
+    fn test_unknown_status_json_round_trip() {
+        let error = DetectionError::Retried {
+            attempts: 3,
+            last: Box::new(DetectionError::Timeout),
+        };
+        let status = AgentStatus::Unknown {
+            error: error.clone(),
+            message: "timed out".to_string(),
+        };
+        let json = serde_json::to_string(&status).unwrap();
+
+        match serde_json::from_str(&json).unwrap() {
+            AgentStatus::Unknown {
+                error: back,
+                message,
+            } => {
+                assert_eq!(back, error);
+                assert_eq!(message, "timed out");
+            }
+            other => panic!("Expected Unknown, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_installed_metadata_clone() {
         let meta = make_installed_metadata();
@@ -346,6 +767,7 @@ mod tests {
         assert_eq!(meta.raw_version, cloned.raw_version);
         assert_eq!(meta.install_method, cloned.install_method);
         assert_eq!(meta.reasoning_level, cloned.reasoning_level);
+        assert_eq!(meta.default_model, cloned.default_model);
     }
 
     #[test]
@@ -360,4 +782,96 @@ mod tests {
         // version() returns None when version is None
         assert!(status.version().is_none());
     }
+
+    #[test]
+    fn test_same_install_ignores_version() {
+        let full = AgentStatus::Installed(make_installed_metadata());
+        let skipped = AgentStatus::Installed(InstalledMetadata {
+            version: None,
+            raw_version: None,
+            version_skipped: true,
+            ..make_installed_metadata()
+        });
+        assert!(full.same_install(&skipped));
+        assert!(skipped.same_install(&full));
+
+        let mismatch = AgentStatus::VersionMismatch {
+            found: Version::parse("0.1.0").unwrap(),
+            required: Version::parse("1.0.0").unwrap(),
+            path: PathBuf::from("/usr/bin/claude"),
+        };
+        assert!(full.same_install(&mismatch));
+    }
+
+    #[test]
+    fn test_same_install_differs_by_path_or_method() {
+        let full = AgentStatus::Installed(make_installed_metadata());
+        let other_path = AgentStatus::Installed(InstalledMetadata {
+            path: PathBuf::from("/opt/claude/bin/claude"),
+            ..make_installed_metadata()
+        });
+        let other_method = AgentStatus::Installed(InstalledMetadata {
+            install_method: Some(InstallMethodKind::Brew),
+            ..make_installed_metadata()
+        });
+        assert!(!full.same_install(&other_path));
+        assert!(!full.same_install(&other_method));
+        assert!(!full.same_install(&AgentStatus::NotInstalled));
+    }
+
+    #[test]
+    fn test_predicates_across_variants() {
+        let mismatch = AgentStatus::VersionMismatch {
+            found: Version::parse("0.1.0").unwrap(),
+            required: Version::parse("1.0.0").unwrap(),
+            path: PathBuf::from("/usr/bin/claude"),
+        };
+        let unknown = AgentStatus::Unknown {
+            error: DetectionError::Timeout,
+            message: "timed out".to_string(),
+        };
+        // (status, usable, installed/present)
+        let cases = [
+            (
+                AgentStatus::Installed(make_installed_metadata()),
+                true,
+                true,
+            ),
+            (mismatch, false, true),
+            (AgentStatus::NotInstalled, false, false),
+            (unknown, false, false),
+        ];
+
+        for (status, usable, present) in cases {
+            assert_eq!(status.is_usable(), usable, "{:?}", status);
+            assert_eq!(status.is_installed(), present, "{:?}", status);
+            assert_eq!(status.is_present(), present, "{:?}", status);
+        }
+    }
+
+    #[test]
+    fn test_to_exit_code() {
+        assert_eq!(
+            AgentStatus::Installed(make_installed_metadata()).to_exit_code(),
+            0
+        );
+        assert_eq!(AgentStatus::NotInstalled.to_exit_code(), 1);
+        assert_eq!(
+            AgentStatus::VersionMismatch {
+                found: Version::new(0, 1, 0),
+                required: Version::new(1, 0, 0),
+                path: PathBuf::from("/usr/bin/claude"),
+            }
+            .to_exit_code(),
+            2
+        );
+        assert_eq!(
+            AgentStatus::Unknown {
+                error: DetectionError::IoError,
+                message: "failed".to_string(),
+            }
+            .to_exit_code(),
+            3
+        );
+    }
 }
@@ -0,0 +1,129 @@
+//! Blocking wrappers around the async detection API.
+//!
+//! These functions let synchronous code run detection without setting up
+//! an async runtime. They create a private single-threaded Tokio runtime
+//! for the duration of the call.
+
+use crate::detection::find_agent_executable;
+use crate::{
+    detect_with_options, AgentKind, AgentStatus, DetectOptions, DetectionError, PathResolver,
+};
+use std::collections::HashMap;
+
+/// Check whether a Tokio runtime is available on the current thread.
+///
+/// The async detection functions must be awaited from within a Tokio
+/// runtime. The blocking wrappers are the opposite: they create their own
+/// runtime and therefore cannot be called from within one.
+///
+/// # Example
+///
+/// ```rust
+/// use rig_acp_discovery::runtime_available;
+///
+/// // Plain synchronous code has no runtime
+/// assert!(!runtime_available());
+/// ```
+pub fn runtime_available() -> bool {
+    tokio::runtime::Handle::try_current().is_ok()
+}
+
+/// Detect a single agent from synchronous code using default options.
+///
+/// See [`detect_blocking_with_options`] for details.
+///
+/// # Example
+///
+/// ```rust
+/// use rig_acp_discovery::{AgentKind, detect_blocking};
+///
+/// let status = detect_blocking(AgentKind::ClaudeCode).unwrap();
+/// println!("Claude Code usable: {}", status.is_usable());
+/// ```
+pub fn detect_blocking(kind: AgentKind) -> Result<AgentStatus, DetectionError> {
+    detect_blocking_with_options(kind, DetectOptions::default())
+}
+
+/// Detect a single agent from synchronous code with custom options.
+///
+/// Runs [`detect_with_options`] on a private single-threaded runtime.
+///
+/// # Errors
+///
+/// - `DetectionError::NestedRuntime` if called from within an async
+///   runtime (use the async [`detect_with_options`] there instead)
+/// - `DetectionError::IoError` if the runtime could not be created
+pub fn detect_blocking_with_options(
+    kind: AgentKind,
+    options: DetectOptions,
+) -> Result<AgentStatus, DetectionError> {
+    if runtime_available() {
+        return Err(DetectionError::NestedRuntime);
+    }
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|_| DetectionError::IoError)?;
+
+    Ok(runtime.block_on(detect_with_options(kind, options)))
+}
+
+/// Check which agents have an executable present, without spawning anything.
+///
+/// This is the fastest possible check: it only searches PATH and the known
+/// install locations for each agent's executable. No process is run, no
+/// version is checked, and no async runtime is needed, so it is safe to call
+/// from startup paths and from within an existing runtime.
+///
+/// A `true` value means an executable was found, not that it works. Use
+/// [`detect`](crate::detect) to verify the agent is usable.
+///
+/// # Example
+///
+/// ```rust
+/// use rig_acp_discovery::{AgentKind, presence_sync};
+///
+/// let presence = presence_sync();
+/// if presence[&AgentKind::ClaudeCode] {
+///     println!("Claude Code executable found");
+/// }
+/// ```
+pub fn presence_sync() -> HashMap<AgentKind, bool> {
+    AgentKind::all()
+        .map(|kind| {
+            (
+                kind,
+                find_agent_executable(kind, &PathResolver, None).is_some(),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_blocking_without_runtime() {
+        assert!(!runtime_available());
+        let status = detect_blocking(AgentKind::ClaudeCode);
+        assert!(status.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_detect_blocking_inside_runtime_errors() {
+        assert!(runtime_available());
+        let result = detect_blocking(AgentKind::ClaudeCode);
+        assert_eq!(result.unwrap_err(), DetectionError::NestedRuntime);
+    }
+
+    #[test]
+    fn test_presence_sync_has_all_agents() {
+        let presence = presence_sync();
+        assert_eq!(presence.len(), 4);
+        for kind in AgentKind::all() {
+            assert!(presence.contains_key(&kind));
+        }
+    }
+}
@@ -0,0 +1,128 @@
+//! Shared timeout budget for detecting several agents at once.
+//!
+//! See [`DetectOptions::shared_budget`](crate::DetectOptions::shared_budget).
+
+use futures::future::{select, Either};
+use std::future::Future;
+use std::pin::pin;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::{sleep_until, Instant};
+
+/// A total amount of agent time shared by concurrent detections.
+///
+/// Every pending agent consumes the budget at the same rate while it runs,
+/// so with `n` agents still pending, the budget left is shared equally
+/// between them. When an agent finishes early, it stops consuming, and
+/// the agents still running get its unused share.
+///
+/// For a budget `B` where agents finished after `d1, d2, ...` and `p`
+/// agents are pending, the pending agents' deadline is
+/// `(B - (d1 + d2 + ...)) / p` after the start.
+pub(crate) struct SharedBudget {
+    start: Instant,
+    total: Duration,
+    state: Mutex<BudgetState>,
+}
+
+struct BudgetState {
+    /// Time used by agents that have finished.
+    spent: Duration,
+    /// Agents still running.
+    pending: u32,
+}
+
+impl SharedBudget {
+    /// Start a budget of `total` shared by `agents` concurrent detections.
+    pub(crate) fn new(total: Duration, agents: usize) -> Self {
+        Self {
+            start: Instant::now(),
+            total,
+            state: Mutex::new(BudgetState {
+                spent: Duration::ZERO,
+                pending: agents as u32,
+            }),
+        }
+    }
+
+    /// The current deadline for agents that are still running.
+    fn deadline(&self) -> Instant {
+        let state = self.state.lock().unwrap();
+        let left = self.total.saturating_sub(state.spent);
+        self.start + left / state.pending.max(1)
+    }
+
+    /// Record that an agent stopped consuming the budget.
+    fn finish(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.spent += self.start.elapsed();
+        state.pending = state.pending.saturating_sub(1);
+    }
+
+    /// Run `future` within this budget.
+    ///
+    /// Returns `None` if the budget ran out first. The deadline is
+    /// rechecked when it is reached, so time left over by agents that
+    /// finished in the meantime extends it.
+    pub(crate) async fn run<F: Future>(&self, future: F) -> Option<F::Output> {
+        let mut future = pin!(future);
+        let result = loop {
+            let deadline = self.deadline();
+            if Instant::now() >= deadline {
+                break None;
+            }
+            match select(future.as_mut(), pin!(sleep_until(deadline))).await {
+                Either::Left((output, _)) => break Some(output),
+                Either::Right(_) => {}
+            }
+        };
+        self.finish();
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::future::join_all;
+    use tokio::time::sleep;
+
+    #[tokio::test]
+    async fn test_slow_agent_uses_time_left_by_fast_agents() {
+        // An even split would give each agent 100ms
+        let budget = SharedBudget::new(Duration::from_millis(400), 4);
+        let delays = [10, 10, 10, 250];
+
+        let results = join_all(delays.iter().map(|&ms| {
+            let budget = &budget;
+            async move {
+                budget
+                    .run(async move {
+                        sleep(Duration::from_millis(ms)).await;
+                        ms
+                    })
+                    .await
+            }
+        }))
+        .await;
+
+        assert_eq!(results, [Some(10), Some(10), Some(10), Some(250)]);
+    }
+
+    #[tokio::test]
+    async fn test_budget_runs_out_when_every_agent_is_slow() {
+        let budget = SharedBudget::new(Duration::from_millis(200), 2);
+        let start = std::time::Instant::now();
+
+        let results = join_all((0..2).map(|_| {
+            budget.run(async {
+                sleep(Duration::from_secs(5)).await;
+            })
+        }))
+        .await;
+
+        assert_eq!(results, [None, None]);
+        // Each gets half of the budget, so both stop after about 100ms
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,224 @@
+//! Caching of recent detection results.
+//!
+//! This module provides [`DetectionCache`], which reuses recent results so
+//! applications that detect agents repeatedly (such as on every screen
+//! refresh) don't re-run `--version` each time.
+
+use crate::{detect_with_options, AgentKind, AgentStatus, DetectOptions};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// A shared cache of detection results.
+///
+/// [`get_or_detect`](Self::get_or_detect) returns the cached status of an
+/// agent if it is recent enough, and detects it again otherwise. Every
+/// status is cached, including `NotInstalled` and `Unknown`.
+///
+/// Clones share the same entries, so one cache can be handed to several
+/// tasks. Concurrent misses for the same agent may each run detection;
+/// the last result is kept.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use rig_acp_discovery::{AgentKind, DetectOptions, DetectionCache};
+/// use std::time::Duration;
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() {
+///     let cache = DetectionCache::new(DetectOptions::default());
+///
+///     // Detects Claude Code
+///     let status = cache.get_or_detect(AgentKind::ClaudeCode, Duration::from_secs(30)).await;
+///     // Reuses the result from the first call
+///     let again = cache.get_or_detect(AgentKind::ClaudeCode, Duration::from_secs(30)).await;
+///     assert_eq!(status.is_usable(), again.is_usable());
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DetectionCache {
+    options: DetectOptions,
+    entries: Arc<Mutex<HashMap<AgentKind, CachedStatus>>>,
+}
+
+#[derive(Debug)]
+struct CachedStatus {
+    status: AgentStatus,
+    /// When the status was stored, for statuses without `last_verified`.
+    stored: Instant,
+}
+
+impl CachedStatus {
+    /// How long ago the status was verified.
+    fn age(&self) -> Duration {
+        match &self.status {
+            AgentStatus::Installed(meta) => meta.last_verified.elapsed().unwrap_or_default(),
+            _ => self.stored.elapsed(),
+        }
+    }
+}
+
+impl DetectionCache {
+    /// Create an empty cache that detects with `options`.
+    pub fn new(options: DetectOptions) -> Self {
+        Self {
+            options,
+            entries: Arc::default(),
+        }
+    }
+
+    /// Get an agent's status, detecting it if the cached one is missing or stale.
+    ///
+    /// A cached status is reused if it was verified within `max_age`: for
+    /// `Installed` agents this is `InstalledMetadata::last_verified`, and
+    /// for other statuses the time they were cached. Otherwise the agent
+    /// is detected with [`detect_with_options`] and the result is cached.
+    pub async fn get_or_detect(&self, kind: AgentKind, max_age: Duration) -> AgentStatus {
+        self.get_or_detect_with(kind, max_age, || {
+            detect_with_options(kind, self.options.clone())
+        })
+        .await
+    }
+
+    /// [`get_or_detect`](Self::get_or_detect) with an injectable detection.
+    async fn get_or_detect_with<D, Fut>(
+        &self,
+        kind: AgentKind,
+        max_age: Duration,
+        detect: D,
+    ) -> AgentStatus
+    where
+        D: FnOnce() -> Fut,
+        Fut: Future<Output = AgentStatus>,
+    {
+        if let Some(cached) = self.entries.lock().unwrap().get(&kind) {
+            if cached.age() <= max_age {
+                return cached.status.clone();
+            }
+        }
+
+        let status = detect().await;
+        self.entries.lock().unwrap().insert(
+            kind,
+            CachedStatus {
+                status: status.clone(),
+                stored: Instant::now(),
+            },
+        );
+        status
+    }
+
+    /// Drop the cached status of one agent, so the next lookup detects it.
+    pub fn invalidate(&self, kind: AgentKind) {
+        self.entries.lock().unwrap().remove(&kind);
+    }
+
+    /// Drop every cached status.
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    const MAX_AGE: Duration = Duration::from_secs(60);
+
+    fn installed() -> AgentStatus {
+        AgentStatus::Installed(crate::test_util::installed_meta("/usr/bin/codex", None))
+    }
+
+    /// Look up `kind`, counting how often detection runs.
+    async fn lookup(
+        cache: &DetectionCache,
+        kind: AgentKind,
+        max_age: Duration,
+        calls: &AtomicU32,
+        status: AgentStatus,
+    ) -> AgentStatus {
+        cache
+            .get_or_detect_with(kind, max_age, || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                status
+            })
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_second_call_within_window_is_cached() {
+        let cache = DetectionCache::default();
+        let calls = AtomicU32::new(0);
+
+        let first = lookup(&cache, AgentKind::Codex, MAX_AGE, &calls, installed()).await;
+        let second = lookup(&cache, AgentKind::Codex, MAX_AGE, &calls, installed()).await;
+
+        assert!(first.is_usable() && second.is_usable());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_not_installed_is_cached() {
+        let cache = DetectionCache::default();
+        let calls = AtomicU32::new(0);
+
+        for _ in 0..3 {
+            let status = lookup(
+                &cache,
+                AgentKind::Gemini,
+                MAX_AGE,
+                &calls,
+                AgentStatus::NotInstalled,
+            )
+            .await;
+            assert!(matches!(status, AgentStatus::NotInstalled));
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_stale_entry_is_redetected() {
+        let cache = DetectionCache::default();
+        let calls = AtomicU32::new(0);
+
+        let status = AgentStatus::NotInstalled;
+        lookup(&cache, AgentKind::Codex, MAX_AGE, &calls, status).await;
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let status = AgentStatus::NotInstalled;
+        lookup(
+            &cache,
+            AgentKind::Codex,
+            Duration::from_millis(10),
+            &calls,
+            status,
+        )
+        .await;
+
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_invalidate_and_clear() {
+        let cache = DetectionCache::default();
+        let shared = cache.clone();
+        let calls = AtomicU32::new(0);
+
+        lookup(&cache, AgentKind::Codex, MAX_AGE, &calls, installed()).await;
+        lookup(&cache, AgentKind::Gemini, MAX_AGE, &calls, installed()).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // Clones share entries
+        shared.invalidate(AgentKind::Codex);
+        lookup(&cache, AgentKind::Codex, MAX_AGE, &calls, installed()).await;
+        lookup(&cache, AgentKind::Gemini, MAX_AGE, &calls, installed()).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        cache.clear();
+        lookup(&cache, AgentKind::Gemini, MAX_AGE, &calls, installed()).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+    }
+}
@@ -4,14 +4,27 @@
 //! on the system. Detection can be performed for a single agent or
 //! all known agents in parallel.
 
-use crate::detection::{check_version, find_executable, parse_version};
+use crate::budget::SharedBudget;
+use crate::detect_progress::DetectProgress;
+use crate::detection::{
+    acp_args, acp_handshake, binary_arch, binary_type, check_version, find_agent_executable,
+    find_all_executables_in, go_bin_dirs, home_dir, is_quarantined, parse_major_version,
+    parse_version, probe_acp_capabilities, probe_capabilities, probe_config_schema_version,
+    probe_default_model, probe_edition, read_header, read_version_file, scan_locations,
+};
+use crate::fixes;
 use crate::options::DetectOptions;
-use crate::{AgentKind, AgentStatus, DetectionError, InstalledMetadata};
-use futures::future::join_all;
+use crate::{AgentKind, AgentStatus, DetectionError, InstallMethodKind, InstalledMetadata};
+use futures::future::{join_all, select, Either};
+use futures::stream::{FuturesUnordered, StreamExt};
+use semver::Version;
 use std::collections::HashMap;
-use std::path::Path;
-use std::time::SystemTime;
-use tracing::warn;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::pin;
+use std::time::{Duration, Instant, SystemTime};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, warn};
 
 /// Detect a single agent by kind using default options.
 ///
@@ -93,28 +106,178 @@ pub async fn detect(kind: AgentKind) -> AgentStatus {
 /// }
 /// ```
 pub async fn detect_with_options(kind: AgentKind, options: DetectOptions) -> AgentStatus {
-    // Step 1: Find executable in PATH or fallback locations
-    let path = match find_executable(kind.executable_name()) {
-        Some(p) => p,
-        None => return AgentStatus::NotInstalled,
-    };
+    detect_with_progress(kind, options, |_| {}).await
+}
+
+/// Detect a single agent, reporting progress as detection runs.
+///
+/// This behaves exactly like [`detect_with_options`], but calls
+/// `on_progress` as each detection stage begins or completes. See
+/// [`DetectProgress`] for the stages reported.
+///
+/// # Example
+///
+/// ```rust
+/// use rig_acp_discovery::{AgentKind, DetectOptions, DetectProgress, detect_with_progress};
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() {
+///     let status = detect_with_progress(
+///         AgentKind::ClaudeCode,
+///         DetectOptions::default(),
+///         |progress| println!("{:?}", progress),
+///     )
+///     .await;
+///     println!("Claude Code usable: {}", status.is_usable());
+/// }
+/// ```
+pub async fn detect_with_progress<F>(
+    kind: AgentKind,
+    options: DetectOptions,
+    on_progress: F,
+) -> AgentStatus
+where
+    F: Fn(DetectProgress),
+{
+    // Step 1: Find executable via override variable, PATH or fallback locations
+    on_progress(DetectProgress::Searching { agent: kind });
+    let path =
+        match find_agent_executable(kind, options.resolver.as_ref(), options.path_env.as_deref()) {
+            Some(p) => p,
+            None => match find_via_winget(kind, options.effective_timeout(kind)).await {
+                Some(p) => p,
+                None => return AgentStatus::NotInstalled,
+            },
+        };
+    on_progress(DetectProgress::Found {
+        agent: kind,
+        path: path.clone(),
+    });
+
+    inspect_executable_with_progress(kind, path, &options, &on_progress).await
+}
+
+/// Detect a single agent, stopping early if `token` is cancelled.
+///
+/// Behaves like [`detect_with_options`] until `token` is cancelled, at
+/// which point detection is abandoned and `DetectionError::Cancelled` is
+/// returned. A running version check is killed and reaped. If the token is
+/// already cancelled, nothing runs.
+///
+/// # Example
+///
+/// ```rust
+/// use rig_acp_discovery::{detect_with_cancel, AgentKind, DetectOptions, DetectionError};
+/// use tokio_util::sync::CancellationToken;
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() {
+///     let token = CancellationToken::new();
+///     token.cancel();
+///
+///     let result = detect_with_cancel(AgentKind::Codex, DetectOptions::default(), token).await;
+///     assert_eq!(result.unwrap_err(), DetectionError::Cancelled);
+/// }
+/// ```
+pub async fn detect_with_cancel(
+    kind: AgentKind,
+    options: DetectOptions,
+    token: CancellationToken,
+) -> Result<AgentStatus, DetectionError> {
+    if token.is_cancelled() {
+        return Err(DetectionError::Cancelled);
+    }
+
+    let detect = detect_with_options(kind, options);
+    // Dropping the losing detection kills its subprocess (kill_on_drop)
+    match select(pin!(token.cancelled()), pin!(detect)).await {
+        Either::Left(_) => Err(DetectionError::Cancelled),
+        Either::Right((status, _)) => Ok(status),
+    }
+}
+
+/// Inspect a located executable and build its detection status.
+///
+/// This runs every detection step after the executable lookup: the
+/// optional version check, version parsing, and metadata probes, reporting
+/// version check progress to `on_progress`.
+pub(crate) async fn inspect_executable_with_progress(
+    kind: AgentKind,
+    path: PathBuf,
+    options: &DetectOptions,
+    on_progress: &impl Fn(DetectProgress),
+) -> AgentStatus {
+    // A zero-byte file is a leftover from a failed download, not an agent
+    if std::fs::metadata(&path).is_ok_and(|m| m.is_file() && m.len() == 0) {
+        return AgentStatus::Unknown {
+            error: DetectionError::CorruptInstall,
+            message: format!(
+                "{} at {} is empty (0 bytes), likely from an interrupted download. {}",
+                kind.display_name(),
+                path.display(),
+                fixes::render(
+                    fixes::CORRUPT_INSTALL,
+                    &[("command", &kind.install_info().primary.raw_command)],
+                )
+            ),
+        };
+    }
 
     // Step 2: If skip_version is true, return Installed immediately without version info
     if options.skip_version {
-        return AgentStatus::Installed(InstalledMetadata {
-            path: path.clone(),
-            version: None,
-            raw_version: None,
-            install_method: detect_install_method(&path),
-            last_verified: SystemTime::now(),
-            reasoning_level: None,
-        });
+        let mut meta = installed_metadata(kind, path, None, None, None, options).await;
+        meta.version_skipped = true;
+        return AgentStatus::Installed(meta);
+    }
+
+    // A version file next to the binary avoids spawning the agent
+    if options.prefer_version_file {
+        if let Some((version, raw)) = read_version_file(&path) {
+            on_progress(DetectProgress::Parsed {
+                agent: kind,
+                version: Some(version.clone()),
+            });
+            if let Some(mismatch) = min_version_mismatch(kind, Some(&version), &path, options) {
+                return mismatch;
+            }
+            return AgentStatus::Installed(
+                installed_metadata(kind, path, Some(version), Some(raw), None, options).await,
+            );
+        }
     }
 
     // Step 3: Check version with configured timeout
-    let version_output = match check_version(&path, options.timeout).await {
+    on_progress(DetectProgress::CheckingVersion { agent: kind });
+    let version_check = check_version_with_retries(kind, options.retries, || {
+        check_version(&path, options.effective_timeout(kind), options.minimal_env)
+    });
+    let version_output = match version_check.await {
         Ok(output) => output,
+        // Gatekeeper blocks quarantined binaries, which looks like a generic failure
+        Err(_) if is_quarantined(&path) => {
+            return AgentStatus::Unknown {
+                error: DetectionError::Quarantined,
+                message: quarantined_message(kind, &path),
+            }
+        }
         Err(DetectionError::Timeout) => return AgentStatus::NotInstalled,
+        Err(DetectionError::PermissionDenied) => {
+            return AgentStatus::Unknown {
+                error: DetectionError::PermissionDenied,
+                message: permission_denied_message(kind, &path),
+            }
+        }
+        Err(e @ DetectionError::Retried { attempts, .. }) => {
+            return AgentStatus::Unknown {
+                message: format!(
+                    "Failed to verify {} after {} attempts: {}",
+                    kind.display_name(),
+                    attempts,
+                    e.description()
+                ),
+                error: e,
+            }
+        }
         Err(e) => {
             return AgentStatus::Unknown {
                 error: e.clone(),
@@ -128,7 +291,14 @@ pub async fn detect_with_options(kind: AgentKind, options: DetectOptions) -> Age
     };
 
     // Step 4: Parse version from output with graceful degradation
-    let (version, raw_version) = match parse_version(&version_output) {
+    let parsed = parse_version(&version_output).or_else(|| {
+        if options.allow_major_only {
+            parse_major_version(&version_output)
+        } else {
+            None
+        }
+    });
+    let (version, raw_version) = match parsed {
         Some((v, raw)) => (Some(v), Some(raw)),
         None => {
             // Graceful degradation: log warning but still return Installed
@@ -141,15 +311,237 @@ pub async fn detect_with_options(kind: AgentKind, options: DetectOptions) -> Age
         }
     };
 
+    on_progress(DetectProgress::Parsed {
+        agent: kind,
+        version: version.clone(),
+    });
+
+    if let Some(mismatch) = min_version_mismatch(kind, version.as_ref(), &path, options) {
+        return mismatch;
+    }
+
     // Step 5: Build metadata and return Installed
-    AgentStatus::Installed(InstalledMetadata {
-        path: path.clone(),
+    AgentStatus::Installed(
+        installed_metadata(
+            kind,
+            path,
+            version,
+            raw_version,
+            Some(&version_output),
+            options,
+        )
+        .await,
+    )
+}
+
+/// Check a detected version against `options.min_version`, or the
+/// agent's minimum supported version if that is unset.
+///
+/// Returns `VersionMismatch` if the version is below the requirement. An
+/// unknown version can't be checked, so it is accepted with a warning.
+fn min_version_mismatch(
+    kind: AgentKind,
+    version: Option<&Version>,
+    path: &Path,
+    options: &DetectOptions,
+) -> Option<AgentStatus> {
+    let required = options
+        .min_version
+        .clone()
+        .or_else(|| kind.min_supported_version())?;
+    match version {
+        Some(found) if *found < required => Some(AgentStatus::VersionMismatch {
+            found: found.clone(),
+            required,
+            path: path.to_path_buf(),
+        }),
+        Some(_) => None,
+        None => {
+            warn!(
+                "Cannot check {} against minimum version {}: version unknown",
+                kind.display_name(),
+                required
+            );
+            None
+        }
+    }
+}
+
+/// Run a version check, retrying timeouts and I/O errors up to `retries` times.
+///
+/// Each failed attempt is logged. If a retry was made and every attempt
+/// failed, the last error is wrapped in [`DetectionError::Retried`],
+/// except that a timeout on every attempt is returned as a plain
+/// `Timeout`, so retrying never changes how a hung agent is reported.
+async fn check_version_with_retries<F, Fut>(
+    kind: AgentKind,
+    retries: u8,
+    mut check: F,
+) -> Result<String, DetectionError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<String, DetectionError>>,
+{
+    let mut attempts: u8 = 0;
+    let mut all_timed_out = true;
+    loop {
+        attempts = attempts.saturating_add(1);
+        let error = match check().await {
+            Ok(output) => return Ok(output),
+            Err(e) => e,
+        };
+        all_timed_out &= error == DetectionError::Timeout;
+
+        let transient = matches!(error, DetectionError::Timeout | DetectionError::IoError);
+        if transient && attempts <= retries {
+            warn!(
+                "Version check attempt {} for {} failed: {}",
+                attempts,
+                kind.display_name(),
+                error.description()
+            );
+            continue;
+        }
+
+        return Err(if attempts > 1 && !all_timed_out {
+            DetectionError::Retried {
+                attempts,
+                last: Box::new(error),
+            }
+        } else {
+            error
+        });
+    }
+}
+
+/// Explain a permission failure running an agent's version check.
+///
+/// This usually means the agent was installed as root (e.g., `sudo npm
+/// install -g`) and is not executable by the current user. On Unix the
+/// message includes the file's owner and mode.
+fn permission_denied_message(kind: AgentKind, path: &Path) -> String {
+    #[cfg(unix)]
+    let details = {
+        use std::os::unix::fs::MetadataExt;
+        match std::fs::metadata(path) {
+            Ok(meta) => format!(
+                " (owner uid {}, mode {:04o})",
+                meta.uid(),
+                meta.mode() & 0o7777
+            ),
+            Err(_) => String::new(),
+        }
+    };
+    #[cfg(not(unix))]
+    let details = String::new();
+
+    #[cfg(unix)]
+    let fix = fixes::render(
+        fixes::BINARY_PERMISSION_DENIED_UNIX,
+        &[("agent", &kind.display_name()), ("path", &path.display())],
+    );
+    #[cfg(not(unix))]
+    let fix = fixes::render(
+        fixes::BINARY_PERMISSION_DENIED,
+        &[("agent", &kind.display_name())],
+    );
+
+    format!(
+        "Permission denied running {} at {}{}. The binary may be root-owned and not executable by your user. Fix: {}",
+        kind.display_name(),
+        path.display(),
+        details,
+        fix
+    )
+}
+
+/// Explain a version check failure caused by macOS Gatekeeper quarantine.
+fn quarantined_message(kind: AgentKind, path: &Path) -> String {
+    format!(
+        "{} at {} is quarantined by macOS Gatekeeper and cannot run. Fix: {}",
+        kind.display_name(),
+        path.display(),
+        fixes::render(fixes::QUARANTINED, &[("path", &path.display())])
+    )
+}
+
+/// Windows fallback: locate an agent installed via winget but not on PATH.
+#[cfg(windows)]
+async fn find_via_winget(kind: AgentKind, timeout: std::time::Duration) -> Option<PathBuf> {
+    let id = kind.winget_id()?;
+    crate::detection::find_winget_executable(id, kind.executable_name(), timeout).await
+}
+
+/// winget is only available on Windows.
+#[cfg(not(windows))]
+async fn find_via_winget(_kind: AgentKind, _timeout: std::time::Duration) -> Option<PathBuf> {
+    None
+}
+
+/// Build the metadata for an installed agent, running any enabled probes.
+async fn installed_metadata(
+    kind: AgentKind,
+    path: PathBuf,
+    version: Option<Version>,
+    raw_version: Option<String>,
+    version_output: Option<&str>,
+    options: &DetectOptions,
+) -> InstalledMetadata {
+    let default_model = if options.probe_default_model {
+        home_dir().and_then(|home| probe_default_model(kind, &home))
+    } else {
+        None
+    };
+
+    let config_schema_version = if options.probe_config_schema {
+        home_dir().and_then(|home| probe_config_schema_version(kind, &home))
+    } else {
+        None
+    };
+
+    let edition = if options.probe_edition {
+        probe_edition(kind, version_output, home_dir().as_deref())
+    } else {
+        None
+    };
+
+    // The header is small, so the binary type is always reported
+    let header = read_header(&path);
+    let binary_arch = if options.probe_arch {
+        header.as_deref().and_then(binary_arch)
+    } else {
+        None
+    };
+    let binary_type = header.map(|header| binary_type(&path, &header));
+
+    let acp_capabilities = if options.probe_acp_capabilities {
+        probe_acp_capabilities(kind, &path, options.effective_timeout(kind)).await
+    } else {
+        Vec::new()
+    };
+
+    let capabilities = if options.probe_capabilities {
+        probe_capabilities(&path, kind, options.effective_timeout(kind)).await
+    } else {
+        None
+    };
+
+    InstalledMetadata {
+        install_method: detect_install_method(&path),
+        binary_type,
+        path,
         version,
         raw_version,
-        install_method: detect_install_method(&path),
         last_verified: SystemTime::now(),
         reasoning_level: None,
-    })
+        default_model,
+        version_skipped: false,
+        binary_arch,
+        acp_capabilities,
+        config_schema_version,
+        capabilities,
+        edition,
+    }
 }
 
 /// Internal helper for parallel detection that returns Result per agent.
@@ -157,7 +549,8 @@ pub async fn detect_with_options(kind: AgentKind, options: DetectOptions) -> Age
 /// This function wraps the detection logic to return a Result, enabling
 /// error isolation in parallel detection. NotInstalled is considered
 /// a successful detection (not an error), while Unknown errors are
-/// propagated as Err.
+/// propagated as Err. VersionMismatch is Ok unless
+/// `options.mismatch_is_error` is set.
 async fn detect_one(
     kind: AgentKind,
     options: &DetectOptions,
@@ -168,6 +561,12 @@ async fn detect_one(
         // Successful detection states - return Ok
         AgentStatus::Installed(_) => Ok(status),
         AgentStatus::NotInstalled => Ok(status),
+        AgentStatus::VersionMismatch {
+            found, required, ..
+        } if options.mismatch_is_error => Err(DetectionError::VersionUnsupported {
+            found: found.clone(),
+            required: required.clone(),
+        }),
         AgentStatus::VersionMismatch { .. } => Ok(status),
         // Detection errors - propagate as Err
         AgentStatus::Unknown { error, .. } => Err(error.clone()),
@@ -275,91 +674,727 @@ pub async fn detect_all() -> HashMap<AgentKind, Result<AgentStatus, DetectionErr
 pub async fn detect_all_with_options(
     options: DetectOptions,
 ) -> HashMap<AgentKind, Result<AgentStatus, DetectionError>> {
-    let futures: Vec<_> = AgentKind::all()
-        .map(|kind| detect_one(kind, &options))
-        .collect();
-
-    join_all(futures).await.into_iter().collect()
+    detect_where(|_| true, options).await
 }
 
-/// Detect the installation method from the executable path.
+/// Detect, in parallel, only the agents for which `predicate` returns `true`.
 ///
-/// This heuristic checks the path for common patterns that indicate
-/// how the tool was installed. On Windows, path matching is case-insensitive
-/// to account for filesystem behavior.
-fn detect_install_method(path: &Path) -> Option<String> {
-    let path_str = path.to_string_lossy();
+/// This behaves like [`detect_all_with_options`] restricted to the kinds
+/// passing `predicate`, such as agents supported on this platform or
+/// agents a plugin knows how to drive. Agents that are filtered out are
+/// never probed and have no entry in the result.
+///
+/// With `DetectOptions::shared_budget`, the budget is shared among the
+/// selected agents only.
+///
+/// # Example
+///
+/// ```rust
+/// use rig_acp_discovery::{detect_where, DetectOptions};
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() {
+///     let results = detect_where(|kind| kind.is_supported_here(), DetectOptions::default()).await;
+///     for (kind, result) in &results {
+///         println!("{}: usable = {}", kind.display_name(), matches!(result, Ok(s) if s.is_usable()));
+///     }
+/// }
+/// ```
+pub async fn detect_where(
+    predicate: impl Fn(AgentKind) -> bool,
+    options: DetectOptions,
+) -> HashMap<AgentKind, Result<AgentStatus, DetectionError>> {
+    let kinds: Vec<AgentKind> = AgentKind::all().filter(|&kind| predicate(kind)).collect();
+    let (options, budget) = with_shared_budget(with_path_snapshot(options), kinds.len());
+    let futures: Vec<_> = kinds
+        .into_iter()
+        .map(|kind| detect_one_within(kind, &options, budget.as_ref()))
+        .collect();
 
-    // Normalize case for Windows (case-insensitive filesystem)
-    #[cfg(windows)]
-    let path_str = path_str.to_lowercase();
-    #[cfg(not(windows))]
-    let path_str = path_str.to_string();
+    join_all(futures).await.into_iter().collect()
+}
 
-    // npm patterns (cross-platform)
-    if path_str.contains(".npm") || path_str.contains("node_modules") {
-        return Some("npm".to_string());
+/// Snapshot the process PATH so every agent is detected against the same one.
+fn with_path_snapshot(options: DetectOptions) -> DetectOptions {
+    match options.path_env {
+        Some(_) => options,
+        None => DetectOptions {
+            path_env: Some(std::env::var_os("PATH").unwrap_or_default()),
+            ..options
+        },
     }
+}
 
-    // Windows-specific npm location: %APPDATA%\npm
-    #[cfg(windows)]
-    if path_str.contains("appdata") && path_str.contains("npm") {
-        return Some("npm".to_string());
+/// Set up the shared budget for detecting `agents` agents, if one is configured.
+///
+/// The budget replaces the per-agent version check timeout, so a slow
+/// agent can use the time left over by fast ones.
+fn with_shared_budget(
+    options: DetectOptions,
+    agents: usize,
+) -> (DetectOptions, Option<SharedBudget>) {
+    match options.shared_budget {
+        Some(total) => {
+            let budget = SharedBudget::new(total, agents);
+            let options = DetectOptions {
+                timeout: total,
+                agent_timeouts: HashMap::new(),
+                ..options
+            };
+            (options, Some(budget))
+        }
+        None => (options, None),
     }
+}
 
-    // Cargo (cross-platform)
-    if path_str.contains(".cargo") {
-        return Some("cargo".to_string());
+/// [`detect_one`], bounded by the shared budget if there is one.
+async fn detect_one_within(
+    kind: AgentKind,
+    options: &DetectOptions,
+    budget: Option<&SharedBudget>,
+) -> (AgentKind, Result<AgentStatus, DetectionError>) {
+    match budget {
+        Some(budget) => budget
+            .run(detect_one(kind, options))
+            .await
+            .unwrap_or((kind, Err(DetectionError::Timeout))),
+        None => detect_one(kind, options).await,
     }
+}
 
-    // Unix package managers
-    #[cfg(not(windows))]
-    {
-        if path_str.contains("homebrew") || path_str.contains("linuxbrew") {
-            return Some("brew".to_string());
-        }
-        if path_str.contains("mise") {
-            return Some("mise".to_string());
-        }
-    }
+/// Detect all known agents in parallel, invoking a hook as each completes.
+///
+/// This behaves like [`detect_all_with_options`], but calls `hook` with the
+/// agent kind and its result as soon as that agent's detection finishes,
+/// rather than waiting for all agents. This is useful for instrumentation
+/// or for driving a progress bar while detection is running.
+///
+/// The hook is called exactly once per agent, in completion order.
+///
+/// # Arguments
+///
+/// * `options` - Configuration options including timeout
+/// * `hook` - Callback invoked with each agent's detection result
+///
+/// # Example
+///
+/// ```rust
+/// use rig_acp_discovery::{DetectOptions, detect_all_with_hook};
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() {
+///     let all = detect_all_with_hook(DetectOptions::default(), |kind, result| {
+///         println!("{} done (ok: {})", kind.display_name(), result.is_ok());
+///     })
+///     .await;
+///     assert_eq!(all.len(), 4);
+/// }
+/// ```
+pub async fn detect_all_with_hook<F>(
+    options: DetectOptions,
+    hook: F,
+) -> HashMap<AgentKind, Result<AgentStatus, DetectionError>>
+where
+    F: Fn(AgentKind, &Result<AgentStatus, DetectionError>),
+{
+    let (options, budget) =
+        with_shared_budget(with_path_snapshot(options), AgentKind::all().count());
+    let mut pending: FuturesUnordered<_> = AgentKind::all()
+        .map(|kind| detect_one_within(kind, &options, budget.as_ref()))
+        .collect();
 
-    // Windows package managers
-    #[cfg(windows)]
-    {
-        if path_str.contains("scoop") {
-            return Some("scoop".to_string());
-        }
-        if path_str.contains("chocolatey") {
-            return Some("chocolatey".to_string());
-        }
+    let mut results = HashMap::new();
+    while let Some((kind, result)) = pending.next().await {
+        hook(kind, &result);
+        results.insert(kind, result);
     }
-
-    None
+    results
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::time::Duration;
+/// Detect all known agents in parallel, measuring how long each took.
+///
+/// This behaves like [`detect_all_with_options`], and also returns the
+/// wall-clock time each agent's detection took, for finding which agent
+/// slows detection down. Each timing is also logged at debug level.
+///
+/// # Returns
+///
+/// A tuple of the detection results and a `HashMap` with one `Duration`
+/// per agent.
+///
+/// # Example
+///
+/// ```rust
+/// use rig_acp_discovery::{detect_all_timed, DetectOptions};
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() {
+///     let (results, timings) = detect_all_timed(DetectOptions::default()).await;
+///     assert_eq!(results.len(), timings.len());
+///
+///     if let Some((kind, took)) = timings.iter().max_by_key(|(_, took)| **took) {
+///         println!("Slowest: {} ({:?})", kind.display_name(), took);
+///     }
+/// }
+/// ```
+pub async fn detect_all_timed(
+    options: DetectOptions,
+) -> (
+    HashMap<AgentKind, Result<AgentStatus, DetectionError>>,
+    HashMap<AgentKind, Duration>,
+) {
+    let (options, budget) =
+        with_shared_budget(with_path_snapshot(options), AgentKind::all().count());
+    let futures: Vec<_> = AgentKind::all()
+        .map(|kind| {
+            let (options, budget) = (&options, budget.as_ref());
+            async move {
+                let start = Instant::now();
+                let (kind, result) = detect_one_within(kind, options, budget).await;
+                let took = start.elapsed();
+                debug!("Detected {} in {:?}", kind.display_name(), took);
+                (kind, result, took)
+            }
+        })
+        .collect();
 
-    #[tokio::test]
-    async fn test_detect_all_returns_all_agents() {
-        let all = detect_all().await;
+    let mut results = HashMap::new();
+    let mut timings = HashMap::new();
+    for (kind, result, took) in join_all(futures).await {
+        results.insert(kind, result);
+        timings.insert(kind, took);
+    }
+    (results, timings)
+}
 
-        // Should have an entry for each agent kind
-        assert_eq!(all.len(), 4);
-        assert!(all.contains_key(&AgentKind::ClaudeCode));
-        assert!(all.contains_key(&AgentKind::Codex));
-        assert!(all.contains_key(&AgentKind::OpenCode));
-        assert!(all.contains_key(&AgentKind::Gemini));
+/// Detect the preferred installed agent.
+///
+/// Detects all agents in parallel and returns the first usable one in
+/// [`AgentKind::preferred_order`], or `None` if no agent is usable.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use rig_acp_discovery::detect_default;
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() {
+///     match detect_default().await {
+///         Some((kind, meta)) => println!("Using {} at {:?}", kind.display_name(), meta.path),
+///         None => println!("No agent installed"),
+///     }
+/// }
+/// ```
+pub async fn detect_default() -> Option<(AgentKind, InstalledMetadata)> {
+    detect_default_with_order(&AgentKind::preferred_order(), DetectOptions::default()).await
+}
 
-        // Each entry should be a Result (Ok or Err)
-        for (_, result) in &all {
-            assert!(result.is_ok() || result.is_err());
-        }
-    }
+/// Detect the first usable agent in a custom order of preference.
+///
+/// Like [`detect_default`], but with a caller-supplied `order` and options.
+/// Only agents listed in `order` are considered.
+pub async fn detect_default_with_order(
+    order: &[AgentKind],
+    options: DetectOptions,
+) -> Option<(AgentKind, InstalledMetadata)> {
+    let futures: Vec<_> = order
+        .iter()
+        .map(|&kind| {
+            let options = options.clone();
+            async move { (kind, detect_with_options(kind, options).await) }
+        })
+        .collect();
 
-    #[tokio::test]
+    first_usable(join_all(futures).await)
+}
+
+/// Detect every agent that is usable right now.
+///
+/// Detects all agents in parallel with default options and keeps only the
+/// `Installed` ones, sorted by [`AgentKind::display_name`] so the output is
+/// stable. Agents that are missing, too old or failed detection are left
+/// out; use [`detect_all`] to see why.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use rig_acp_discovery::detect_installed;
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() {
+///     for (kind, meta) in detect_installed().await {
+///         println!("{} at {:?}", kind.display_name(), meta.path);
+///     }
+/// }
+/// ```
+pub async fn detect_installed() -> Vec<(AgentKind, InstalledMetadata)> {
+    detect_installed_with_options(DetectOptions::default()).await
+}
+
+/// Detect every usable agent with custom options.
+///
+/// Like [`detect_installed`], but runs [`detect_all_with_options`] with
+/// `options`.
+pub async fn detect_installed_with_options(
+    options: DetectOptions,
+) -> Vec<(AgentKind, InstalledMetadata)> {
+    installed_only(detect_all_with_options(options).await)
+}
+
+/// The `Installed` results, sorted by display name.
+fn installed_only(
+    results: HashMap<AgentKind, Result<AgentStatus, DetectionError>>,
+) -> Vec<(AgentKind, InstalledMetadata)> {
+    let mut installed: Vec<_> = results
+        .into_iter()
+        .filter_map(|(kind, result)| match result {
+            Ok(AgentStatus::Installed(meta)) => Some((kind, meta)),
+            _ => None,
+        })
+        .collect();
+    installed.sort_by_key(|(kind, _)| kind.display_name());
+    installed
+}
+
+/// The first `Installed` result, in order.
+fn first_usable(
+    results: impl IntoIterator<Item = (AgentKind, AgentStatus)>,
+) -> Option<(AgentKind, InstalledMetadata)> {
+    results.into_iter().find_map(|(kind, status)| match status {
+        AgentStatus::Installed(meta) => Some((kind, meta)),
+        _ => None,
+    })
+}
+
+/// Detect the best installation of an agent for use over ACP.
+///
+/// When several copies of an agent are installed (e.g., an npm global and
+/// a Homebrew install), [`detect_with_options`] reports whichever comes
+/// first on PATH. This inspects every copy and picks the one rig-acp
+/// should launch, preferring in order:
+///
+/// 1. Usable installs (`Installed`) over ones that failed inspection
+/// 2. ACP-capable installs: the version meets
+///    [`AgentKind::min_supported_version`] and,
+///    for agents with a native ACP mode, the install answers an ACP
+///    `initialize` handshake
+/// 3. The newest version
+///
+/// Ties keep PATH search order. Returns `NotInstalled` if no copy is found.
+/// This spawns an extra process per copy for the ACP handshake.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use rig_acp_discovery::{AgentKind, DetectOptions, detect_best};
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() {
+///     let status = detect_best(AgentKind::Gemini, DetectOptions::default()).await;
+///     println!("Launch: {:?}", status.path());
+/// }
+/// ```
+pub async fn detect_best(kind: AgentKind, options: DetectOptions) -> AgentStatus {
+    let candidates = all_candidates(kind, &options);
+    detect_best_among(kind, candidates, &options).await
+}
+
+/// Detect every installed copy of an agent.
+///
+/// Uses default detection options; see [`detect_all_paths_with_options`].
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use rig_acp_discovery::{detect_all_paths, AgentKind};
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() {
+///     let copies = detect_all_paths(AgentKind::ClaudeCode).await;
+///     if let [used, shadowed @ ..] = copies.as_slice() {
+///         for other in shadowed {
+///             println!("{:?} is shadowed by {:?}", other.path, used.path);
+///         }
+///     }
+/// }
+/// ```
+pub async fn detect_all_paths(kind: AgentKind) -> Vec<InstalledMetadata> {
+    detect_all_paths_with_options(kind, DetectOptions::default()).await
+}
+
+/// Detect every installed copy of an agent with custom options.
+///
+/// Walks every PATH entry (or `options.path_env`), then the fallback and
+/// home locations, and version-checks each distinct executable found.
+/// The result is in search order, so the first entry is the copy
+/// [`detect_with_options`] reports and later entries are copies it
+/// shadows. Comparing their versions reveals an old install shadowing a
+/// newer one.
+///
+/// Minimum versions (`options.min_version` and the agent's own minimum
+/// supported version) are ignored so that old copies are listed too.
+/// Copies that fail inspection (e.g., time out) are left out.
+pub async fn detect_all_paths_with_options(
+    kind: AgentKind,
+    options: DetectOptions,
+) -> Vec<InstalledMetadata> {
+    // A zero floor also overrides the agent's own minimum supported version
+    let options = DetectOptions {
+        min_version: Some(Version::new(0, 0, 0)),
+        ..options
+    };
+    let candidates = all_candidates(kind, &options);
+    let inspected = join_all(
+        candidates
+            .into_iter()
+            .map(|path| inspect_executable_with_progress(kind, path, &options, &|_| {})),
+    )
+    .await;
+
+    inspected
+        .into_iter()
+        .filter_map(|status| match status {
+            AgentStatus::Installed(meta) => Some(meta),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Every executable that could be `kind`, with the one detection picks first.
+fn all_candidates(kind: AgentKind, options: &DetectOptions) -> Vec<PathBuf> {
+    let mut candidates: Vec<PathBuf> =
+        find_agent_executable(kind, options.resolver.as_ref(), options.path_env.as_deref())
+            .into_iter()
+            .collect();
+    for path in find_all_executables_in(kind.executable_name(), options.path_env.as_deref()) {
+        if !candidates.contains(&path) {
+            candidates.push(path);
+        }
+    }
+    candidates
+}
+
+/// Inspect each candidate executable and pick the best for ACP.
+async fn detect_best_among(
+    kind: AgentKind,
+    candidates: Vec<PathBuf>,
+    options: &DetectOptions,
+) -> AgentStatus {
+    // The handshake below also provides the capabilities, so don't probe twice
+    let inspect_options = DetectOptions {
+        probe_acp_capabilities: false,
+        ..options.clone()
+    };
+
+    let inspected = join_all(candidates.into_iter().map(|path| {
+        let inspect_options = &inspect_options;
+        async move {
+            let handshake = acp_handshake(kind, &path, options.effective_timeout(kind)).await;
+            let mut status =
+                inspect_executable_with_progress(kind, path, inspect_options, &|_| {}).await;
+            if let AgentStatus::Installed(meta) = &mut status {
+                if options.probe_acp_capabilities {
+                    meta.acp_capabilities = handshake.clone().unwrap_or_default();
+                }
+            }
+            let answered = acp_args(kind).is_none() || handshake.is_some();
+            let acp_capable = answered && meets_acp_floor(kind, status.version());
+            (status, acp_capable)
+        }
+    }))
+    .await;
+
+    best_candidate(inspected).unwrap_or(AgentStatus::NotInstalled)
+}
+
+/// Whether `version` is at least [`AgentKind::min_supported_version`].
+fn meets_acp_floor(kind: AgentKind, version: Option<&Version>) -> bool {
+    match (kind.min_supported_version(), version) {
+        (None, _) => true,
+        (Some(floor), Some(version)) => *version >= floor,
+        (Some(_), None) => false,
+    }
+}
+
+/// The best `(status, acp_capable)` candidate, keeping the earliest on ties.
+fn best_candidate(candidates: Vec<(AgentStatus, bool)>) -> Option<AgentStatus> {
+    let rank = |(status, acp_capable): &(AgentStatus, bool)| {
+        (status.is_usable(), *acp_capable, status.version().cloned())
+    };
+
+    let mut best: Option<(AgentStatus, bool)> = None;
+    for candidate in candidates {
+        if best.as_ref().map_or(true, |b| rank(&candidate) > rank(b)) {
+            best = Some(candidate);
+        }
+    }
+    best.map(|(status, _)| status)
+}
+
+/// Find every copy of every agent in all known install locations.
+///
+/// This is an audit tool: instead of the single executable detection would
+/// pick, it reports every matching file per agent across PATH, the system
+/// fallback directories, user home locations, and package-manager bin
+/// directories (Homebrew, nvm, Volta, Bun, pnpm, Scoop and agent
+/// installers), in search order. Files reachable through several
+/// directories (e.g., via symlinks) are reported once.
+///
+/// Only the filesystem is checked; no processes are spawned. Every agent
+/// appears in the map, with an empty list if no copy was found.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use rig_acp_discovery::scan_all_locations;
+///
+/// for (kind, paths) in scan_all_locations() {
+///     for path in paths {
+///         println!("{}: {}", kind.display_name(), path.display());
+///     }
+/// }
+/// ```
+pub fn scan_all_locations() -> HashMap<AgentKind, Vec<PathBuf>> {
+    AgentKind::all()
+        .map(|kind| (kind, scan_locations(kind.executable_name())))
+        .collect()
+}
+
+/// Estimated work performed by [`detect_all_with_options`].
+///
+/// See [`detection_cost`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DetectionCost {
+    /// Number of child processes spawned (e.g., `--version` checks).
+    pub process_spawns: usize,
+
+    /// Number of executable lookups (PATH and fallback location searches).
+    pub path_lookups: usize,
+}
+
+/// Estimate how much work `detect_all_with_options(options)` will do.
+///
+/// This is computed from the options alone, without touching the system,
+/// so constrained environments can decide whether to run full detection.
+/// Each agent costs one executable lookup and, unless `skip_version` is
+/// set, up to two `--version` processes (a version-manager shim that
+/// prints no version is run again with `--`) for the first attempt and
+/// for each allowed retry. The capabilities probe adds one process for
+/// each agent, and the ACP capabilities probe one for each agent with a
+/// native ACP mode. Config, edition and architecture probes read files or
+/// the version output and do not spawn processes.
+///
+/// The estimate is an upper bound for agents that are found; a typical
+/// install answers `--version` on the first try and spawns less. Agents
+/// that are not installed spawn nothing, except for the winget fallback
+/// on Windows, which is not counted.
+///
+/// # Example
+///
+/// ```rust
+/// use rig_acp_discovery::{DetectOptions, detection_cost};
+///
+/// let cost = detection_cost(&DetectOptions::default());
+/// assert_eq!(cost.process_spawns, 8);
+///
+/// let fast = DetectOptions {
+///     skip_version: true,
+///     ..Default::default()
+/// };
+/// assert_eq!(detection_cost(&fast).process_spawns, 0);
+/// ```
+pub fn detection_cost(options: &DetectOptions) -> DetectionCost {
+    let agents = AgentKind::all().count();
+    // Each attempt may run a shim a second time with `--`
+    let version_spawns = if options.skip_version {
+        0
+    } else {
+        2 * (1 + usize::from(options.retries))
+    };
+    let capabilities_spawns = usize::from(options.probe_capabilities);
+    let acp_spawns = if options.probe_acp_capabilities {
+        AgentKind::all()
+            .filter(|&kind| acp_args(kind).is_some())
+            .count()
+    } else {
+        0
+    };
+
+    DetectionCost {
+        process_spawns: agents * (version_spawns + capabilities_spawns) + acp_spawns,
+        path_lookups: agents,
+    }
+}
+
+/// Recheck previously detected agents from their stored metadata.
+///
+/// This is the reload path for persistent caches. Each stored path is
+/// rechecked in parallel, skipping the PATH search: a path that no longer
+/// exists yields `NotInstalled`, otherwise the executable is inspected
+/// again using `options` (version check, parsing and probes).
+///
+/// Results are returned in the same order as `metas`.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use rig_acp_discovery::{AgentKind, DetectOptions, InstalledMetadata, revalidate};
+///
+/// async fn reload(cached: Vec<(AgentKind, InstalledMetadata)>) {
+///     for (kind, status) in revalidate(cached, DetectOptions::default()).await {
+///         println!("{}: usable = {}", kind.display_name(), status.is_usable());
+///     }
+/// }
+/// ```
+pub async fn revalidate(
+    metas: Vec<(AgentKind, InstalledMetadata)>,
+    options: DetectOptions,
+) -> Vec<(AgentKind, AgentStatus)> {
+    let futures: Vec<_> = metas
+        .into_iter()
+        .map(|(kind, meta)| {
+            let options = &options;
+            async move {
+                if !meta.path.is_file() {
+                    return (kind, AgentStatus::NotInstalled);
+                }
+                let status =
+                    inspect_executable_with_progress(kind, meta.path, options, &|_| {}).await;
+                (kind, status)
+            }
+        })
+        .collect();
+
+    join_all(futures).await
+}
+
+/// Detect the installation method from the executable path.
+///
+/// This heuristic checks the path for common patterns that indicate
+/// how the tool was installed. On Windows, path matching is case-insensitive
+/// to account for filesystem behavior. Executables in a Go bin directory
+/// (`$GOBIN`, `$GOPATH/bin` or any `go/bin`) were placed by `go install`.
+/// Executables under an agent's own
+/// home directory (`~/.claude`, `~/.opencode`) were placed there by its
+/// native installer.
+pub(crate) fn detect_install_method(path: &Path) -> Option<InstallMethodKind> {
+    let path_str = path.to_string_lossy();
+
+    // Normalize case for Windows (case-insensitive filesystem)
+    #[cfg(windows)]
+    let path_str = path_str.to_lowercase();
+    #[cfg(not(windows))]
+    let path_str = path_str.to_string();
+
+    // npm patterns (cross-platform)
+    if path_str.contains(".npm") || path_str.contains("node_modules") {
+        return Some(InstallMethodKind::Npm);
+    }
+
+    // Windows-specific npm location: %APPDATA%\npm
+    #[cfg(windows)]
+    if path_str.contains("appdata") && path_str.contains("npm") {
+        return Some(InstallMethodKind::Npm);
+    }
+
+    // Cargo (cross-platform)
+    if path_str.contains(".cargo") {
+        return Some(InstallMethodKind::Cargo);
+    }
+
+    // Unix package managers
+    #[cfg(not(windows))]
+    {
+        if path_str.contains("homebrew") || path_str.contains("linuxbrew") {
+            return Some(InstallMethodKind::Brew);
+        }
+        if path_str.contains("mise") {
+            return Some(InstallMethodKind::Mise);
+        }
+    }
+
+    // Windows package managers
+    #[cfg(windows)]
+    {
+        if path_str.contains("scoop") {
+            return Some(InstallMethodKind::Scoop);
+        }
+        if path_str.contains("chocolatey") {
+            return Some(InstallMethodKind::Chocolatey);
+        }
+        if path_str.contains("winget") || path_str.contains("windowsapps") {
+            return Some(InstallMethodKind::Winget);
+        }
+    }
+
+    // Go (cross-platform)
+    if let Some(dir) = path.parent() {
+        if dir.ends_with("go/bin") || go_bin_dirs().iter().any(|go_bin| go_bin == dir) {
+            return Some(InstallMethodKind::Go);
+        }
+    }
+
+    // Native installers (cross-platform)
+    let native = path
+        .components()
+        .any(|c| matches!(c.as_os_str().to_str(), Some(".claude" | ".opencode")));
+    if native {
+        return Some(InstallMethodKind::Native);
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_detect_all_returns_all_agents() {
+        let all = detect_all().await;
+
+        // Should have an entry for each agent kind
+        assert_eq!(all.len(), 4);
+        assert!(all.contains_key(&AgentKind::ClaudeCode));
+        assert!(all.contains_key(&AgentKind::Codex));
+        assert!(all.contains_key(&AgentKind::OpenCode));
+        assert!(all.contains_key(&AgentKind::Gemini));
+
+        // Each entry should be a Result (Ok or Err)
+        for result in all.values() {
+            assert!(result.is_ok() || result.is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_detect_all_timed_has_timing_per_agent() {
+        let (results, timings) = detect_all_timed(DetectOptions::default()).await;
+
+        assert_eq!(results.len(), 4);
+        assert_eq!(timings.len(), 4);
+        for kind in AgentKind::all() {
+            assert!(results.contains_key(&kind));
+            let took = timings[&kind];
+            assert!(took >= Duration::ZERO && took < Duration::from_secs(30));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_detect_where_filters_kinds() {
+        let results = detect_where(|kind| kind.is_supported_here(), DetectOptions::default()).await;
+
+        let supported: Vec<_> = AgentKind::all().filter(|k| k.is_supported_here()).collect();
+        assert_eq!(results.len(), supported.len());
+        for kind in supported {
+            assert!(results.contains_key(&kind));
+        }
+
+        let none = detect_where(|_| false, DetectOptions::default()).await;
+        assert!(none.is_empty());
+    }
+
+    #[tokio::test]
     async fn test_detect_all_parallel_execution() {
         // This test verifies the function completes (parallel execution works)
         // Actual parallel timing would require real I/O
@@ -399,7 +1434,7 @@ mod tests {
         assert_eq!(all.len(), 4);
 
         // Each result should be valid
-        for (_, result) in &all {
+        for result in all.values() {
             match result {
                 Ok(status) => {
                     assert!(matches!(
@@ -418,6 +1453,24 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_detect_all_with_hook_fires_once_per_agent() {
+        use std::sync::Mutex;
+
+        let seen = Mutex::new(Vec::new());
+        let all = detect_all_with_hook(DetectOptions::default(), |kind, _| {
+            seen.lock().unwrap().push(kind);
+        })
+        .await;
+
+        let seen = seen.into_inner().unwrap();
+        assert_eq!(seen.len(), AgentKind::all().count());
+        for kind in AgentKind::all() {
+            assert_eq!(seen.iter().filter(|k| **k == kind).count(), 1);
+            assert!(all.contains_key(&kind));
+        }
+    }
+
     // Compile-time verification that detect functions return impl Future
     #[test]
     fn test_detect_returns_future() {
@@ -476,17 +1529,17 @@ mod tests {
     #[test]
     fn test_detect_install_method_npm_cross_platform() {
         let path = std::path::PathBuf::from("/home/user/.npm-global/bin/opencode");
-        assert_eq!(detect_install_method(&path), Some("npm".to_string()));
+        assert_eq!(detect_install_method(&path), Some(InstallMethodKind::Npm));
 
         let path = std::path::PathBuf::from("/usr/local/lib/node_modules/.bin/tool");
-        assert_eq!(detect_install_method(&path), Some("npm".to_string()));
+        assert_eq!(detect_install_method(&path), Some(InstallMethodKind::Npm));
     }
 
     // Cross-platform cargo test
     #[test]
     fn test_detect_install_method_cargo() {
         let path = std::path::PathBuf::from("/home/user/.cargo/bin/tool");
-        assert_eq!(detect_install_method(&path), Some("cargo".to_string()));
+        assert_eq!(detect_install_method(&path), Some(InstallMethodKind::Cargo));
     }
 
     // Unix-only tests (brew, mise)
@@ -494,10 +1547,10 @@ mod tests {
     #[cfg(not(windows))]
     fn test_detect_install_method_brew() {
         let path = std::path::PathBuf::from("/home/linuxbrew/.linuxbrew/bin/tool");
-        assert_eq!(detect_install_method(&path), Some("brew".to_string()));
+        assert_eq!(detect_install_method(&path), Some(InstallMethodKind::Brew));
 
         let path = std::path::PathBuf::from("/opt/homebrew/bin/tool");
-        assert_eq!(detect_install_method(&path), Some("brew".to_string()));
+        assert_eq!(detect_install_method(&path), Some(InstallMethodKind::Brew));
     }
 
     #[test]
@@ -505,7 +1558,28 @@ mod tests {
     fn test_detect_install_method_mise() {
         let path =
             std::path::PathBuf::from("/home/user/.local/share/mise/installs/tool/bin/binary");
-        assert_eq!(detect_install_method(&path), Some("mise".to_string()));
+        assert_eq!(detect_install_method(&path), Some(InstallMethodKind::Mise));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_detect_install_method_go() {
+        let path = std::path::PathBuf::from("/home/user/go/bin/opencode");
+        assert_eq!(detect_install_method(&path), Some(InstallMethodKind::Go));
+    }
+
+    #[test]
+    fn test_detect_install_method_native() {
+        let path = std::path::PathBuf::from("/home/user/.opencode/bin/opencode");
+        assert_eq!(
+            detect_install_method(&path),
+            Some(InstallMethodKind::Native)
+        );
+        let path = std::path::PathBuf::from("/home/user/.claude/local/claude");
+        assert_eq!(
+            detect_install_method(&path),
+            Some(InstallMethodKind::Native)
+        );
     }
 
     #[test]
@@ -520,7 +1594,7 @@ mod tests {
     fn test_detect_install_method_npm_appdata() {
         // Test npm detection from AppData\Roaming\npm
         let path = std::path::PathBuf::from(r"C:\Users\User\AppData\Roaming\npm\claude.cmd");
-        assert_eq!(detect_install_method(&path), Some("npm".to_string()));
+        assert_eq!(detect_install_method(&path), Some(InstallMethodKind::Npm));
     }
 
     #[test]
@@ -528,7 +1602,7 @@ mod tests {
     fn test_detect_install_method_npm_appdata_case_insensitive() {
         // Test case-insensitivity (AppData vs appdata)
         let path = std::path::PathBuf::from(r"C:\Users\User\APPDATA\Roaming\NPM\tool.cmd");
-        assert_eq!(detect_install_method(&path), Some("npm".to_string()));
+        assert_eq!(detect_install_method(&path), Some(InstallMethodKind::Npm));
     }
 
     #[test]
@@ -536,7 +1610,7 @@ mod tests {
     fn test_detect_install_method_scoop() {
         // Test scoop detection
         let path = std::path::PathBuf::from(r"C:\Users\User\scoop\shims\tool.exe");
-        assert_eq!(detect_install_method(&path), Some("scoop".to_string()));
+        assert_eq!(detect_install_method(&path), Some(InstallMethodKind::Scoop));
     }
 
     #[test]
@@ -544,7 +1618,29 @@ mod tests {
     fn test_detect_install_method_chocolatey() {
         // Test chocolatey detection
         let path = std::path::PathBuf::from(r"C:\ProgramData\chocolatey\bin\tool.exe");
-        assert_eq!(detect_install_method(&path), Some("chocolatey".to_string()));
+        assert_eq!(
+            detect_install_method(&path),
+            Some(InstallMethodKind::Chocolatey)
+        );
+    }
+
+    #[test]
+    #[cfg(windows)]
+    fn test_detect_install_method_winget() {
+        let path = std::path::PathBuf::from(
+            r"C:\Users\User\AppData\Local\Microsoft\WinGet\Links\claude.exe",
+        );
+        assert_eq!(
+            detect_install_method(&path),
+            Some(InstallMethodKind::Winget)
+        );
+
+        let path =
+            std::path::PathBuf::from(r"C:\Users\User\AppData\Local\Microsoft\WindowsApps\tool.exe");
+        assert_eq!(
+            detect_install_method(&path),
+            Some(InstallMethodKind::Winget)
+        );
     }
 
     #[test]
@@ -552,7 +1648,7 @@ mod tests {
     fn test_detect_install_method_cargo_windows() {
         // Test cargo on Windows (cross-platform pattern)
         let path = std::path::PathBuf::from(r"C:\Users\User\.cargo\bin\tool.exe");
-        assert_eq!(detect_install_method(&path), Some("cargo".to_string()));
+        assert_eq!(detect_install_method(&path), Some(InstallMethodKind::Cargo));
     }
 }
 
@@ -621,18 +1717,696 @@ mod mock_tests {
     #[tokio::test(flavor = "current_thread")]
     async fn test_check_version_io_error_for_nonexistent() {
         let exec_path = std::path::PathBuf::from("/nonexistent/path/to/agent");
-        let result = check_version(&exec_path, Duration::from_secs(2)).await;
+        let result = check_version(&exec_path, Duration::from_secs(2), false).await;
         assert!(matches!(result, Err(DetectionError::IoError)));
     }
 
+    async fn inspect_executable(
+        kind: AgentKind,
+        path: PathBuf,
+        options: &DetectOptions,
+    ) -> AgentStatus {
+        inspect_executable_with_progress(kind, path, options, &|_| {}).await
+    }
+
+    #[test]
+    fn test_first_usable_respects_order() {
+        let installed =
+            |path: &str| AgentStatus::Installed(crate::test_util::installed_meta(path, None));
+        let results = vec![
+            (AgentKind::ClaudeCode, AgentStatus::NotInstalled),
+            (AgentKind::Gemini, installed("/usr/bin/gemini")),
+            (AgentKind::Codex, installed("/usr/bin/codex")),
+        ];
+
+        let (kind, meta) = first_usable(results).unwrap();
+        assert_eq!(kind, AgentKind::Gemini);
+        assert_eq!(meta.path, PathBuf::from("/usr/bin/gemini"));
+
+        assert!(first_usable(vec![(AgentKind::Codex, AgentStatus::NotInstalled)]).is_none());
+    }
+
+    #[test]
+    fn test_installed_only_keeps_usable_sorted() {
+        let installed =
+            |path: &str| AgentStatus::Installed(crate::test_util::installed_meta(path, None));
+        let results = HashMap::from([
+            (AgentKind::OpenCode, Ok(installed("/usr/bin/opencode"))),
+            (AgentKind::ClaudeCode, Ok(installed("/usr/bin/claude"))),
+            (AgentKind::Codex, Ok(AgentStatus::NotInstalled)),
+            (AgentKind::Gemini, Err(DetectionError::Timeout)),
+        ]);
+
+        let kinds: Vec<_> = installed_only(results)
+            .into_iter()
+            .map(|(kind, _)| kind)
+            .collect();
+        assert_eq!(kinds, [AgentKind::ClaudeCode, AgentKind::OpenCode]);
+    }
+
     #[tokio::test]
-    async fn test_detect_with_skip_version() {
-        // Test that skip_version returns Installed with None version
-        let options = DetectOptions {
-            skip_version: true,
-            ..Default::default()
-        };
-        let status = detect_with_options(AgentKind::ClaudeCode, options).await;
+    async fn test_detect_installed_returns_usable_agents() {
+        let installed = detect_installed().await;
+
+        let mut names: Vec<_> = installed.iter().map(|(k, _)| k.display_name()).collect();
+        names.sort();
+        assert_eq!(
+            names,
+            installed
+                .iter()
+                .map(|(k, _)| k.display_name())
+                .collect::<Vec<_>>()
+        );
+        for (kind, meta) in &installed {
+            assert!(AgentKind::all().any(|k| k == *kind));
+            assert!(meta.path.exists(), "{:?}", meta.path);
+        }
+    }
+
+    #[test]
+    fn test_detection_cost() {
+        // One `--version` per agent, and a `-- --version` rerun for shims
+        let cost = detection_cost(&DetectOptions::default());
+        assert_eq!(cost.process_spawns, 8);
+        assert_eq!(cost.path_lookups, 4);
+
+        let options = DetectOptions {
+            skip_version: true,
+            probe_default_model: true,
+            probe_arch: true,
+            probe_edition: true,
+            ..Default::default()
+        };
+        let cost = detection_cost(&options);
+        assert_eq!(cost.process_spawns, 0);
+        assert_eq!(cost.path_lookups, 4);
+
+        // Only Gemini CLI and OpenCode can be started in ACP mode directly
+        let options = DetectOptions {
+            probe_acp_capabilities: true,
+            ..Default::default()
+        };
+        assert_eq!(detection_cost(&options).process_spawns, 10);
+
+        // `capabilities --json` runs once per agent, even without a version check
+        let options = DetectOptions {
+            skip_version: true,
+            probe_capabilities: true,
+            ..Default::default()
+        };
+        assert_eq!(detection_cost(&options).process_spawns, 4);
+
+        let options = DetectOptions {
+            retries: 2,
+            ..Default::default()
+        };
+        assert_eq!(detection_cost(&options).process_spawns, 24);
+    }
+
+    #[tokio::test]
+    async fn test_check_version_retries_record_attempts() {
+        let calls = std::cell::Cell::new(0);
+        let result = check_version_with_retries(AgentKind::Codex, 2, || {
+            calls.set(calls.get() + 1);
+            let attempt = calls.get();
+            async move {
+                if attempt == 1 {
+                    Err(DetectionError::IoError)
+                } else {
+                    Err(DetectionError::Timeout)
+                }
+            }
+        })
+        .await;
+        assert_eq!(calls.get(), 3);
+        assert_eq!(
+            result,
+            Err(DetectionError::Retried {
+                attempts: 3,
+                last: Box::new(DetectionError::Timeout)
+            })
+        );
+
+        // A timeout on every attempt stays a timeout, as without retries
+        let calls = std::cell::Cell::new(0);
+        let result = check_version_with_retries(AgentKind::Codex, 2, || {
+            calls.set(calls.get() + 1);
+            async { Err(DetectionError::Timeout) }
+        })
+        .await;
+        assert_eq!(calls.get(), 3);
+        assert_eq!(result, Err(DetectionError::Timeout));
+
+        // A later success is returned as-is
+        let calls = std::cell::Cell::new(0);
+        let result = check_version_with_retries(AgentKind::Codex, 2, || {
+            calls.set(calls.get() + 1);
+            let attempt = calls.get();
+            async move {
+                if attempt < 2 {
+                    Err(DetectionError::IoError)
+                } else {
+                    Ok("1.0.0".to_string())
+                }
+            }
+        })
+        .await;
+        assert_eq!(result, Ok("1.0.0".to_string()));
+
+        // Without retries, and for non-transient errors, the error is unwrapped
+        let result = check_version_with_retries(AgentKind::Codex, 0, || async {
+            Err(DetectionError::IoError)
+        })
+        .await;
+        assert_eq!(result, Err(DetectionError::IoError));
+        let result = check_version_with_retries(AgentKind::Codex, 2, || async {
+            Err(DetectionError::PermissionDenied)
+        })
+        .await;
+        assert_eq!(result, Err(DetectionError::PermissionDenied));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_probe_acp_capabilities_fills_metadata() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = crate::test_util::fake_executable(
+            dir.path(),
+            "opencode",
+            r#"if [ "$1" = "acp" ]; then
+  read request
+  echo '{"jsonrpc":"2.0","id":1,"result":{"agentCapabilities":{"loadSession":true,"mcpCapabilities":{"http":true}}}}'
+else
+  echo "1.1.25"
+fi"#,
+        );
+
+        let options = DetectOptions {
+            probe_acp_capabilities: true,
+            ..Default::default()
+        };
+        match inspect_executable(AgentKind::OpenCode, path, &options).await {
+            AgentStatus::Installed(meta) => {
+                assert_eq!(meta.version, Some(Version::new(1, 1, 25)));
+                assert_eq!(
+                    meta.acp_capabilities,
+                    ["loadSession", "mcpCapabilities.http"]
+                );
+            }
+            other => panic!("Unexpected status: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_revalidate_detects_deleted_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let kept = dir.path().join("claude");
+        let deleted = dir.path().join("codex");
+        std::fs::write(&kept, "fake").unwrap();
+        std::fs::write(&deleted, "fake").unwrap();
+
+        let meta = |path: &Path| InstalledMetadata {
+            version_skipped: true,
+            ..crate::test_util::installed_meta(path, None)
+        };
+        let cached = vec![
+            (AgentKind::ClaudeCode, meta(&kept)),
+            (AgentKind::Codex, meta(&deleted)),
+        ];
+        std::fs::remove_file(&deleted).unwrap();
+
+        let options = DetectOptions {
+            skip_version: true,
+            ..Default::default()
+        };
+        let results = revalidate(cached, options).await;
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].0, AgentKind::ClaudeCode);
+        assert_eq!(results[0].1.path(), Some(kept.as_path()));
+        assert_eq!(results[1].0, AgentKind::Codex);
+        assert!(matches!(results[1].1, AgentStatus::NotInstalled));
+    }
+
+    #[tokio::test]
+    async fn test_detect_with_progress_events() {
+        let events = std::sync::Mutex::new(Vec::new());
+        let status = detect_with_progress(AgentKind::ClaudeCode, DetectOptions::default(), |p| {
+            events.lock().unwrap().push(p)
+        })
+        .await;
+
+        let events = events.into_inner().unwrap();
+        assert!(matches!(
+            events[0],
+            DetectProgress::Searching {
+                agent: AgentKind::ClaudeCode
+            }
+        ));
+        if let Some(path) = status.path() {
+            assert!(events
+                .iter()
+                .any(|e| matches!(e, DetectProgress::Found { path: p, .. } if p == path)));
+        }
+    }
+
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn test_inspect_reports_version_progress() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = crate::test_util::fake_executable(dir.path(), "codex", "echo codex-cli 0.87.0");
+
+        let events = std::sync::Mutex::new(Vec::new());
+        let on_progress = |p| events.lock().unwrap().push(p);
+        let options = DetectOptions::default();
+        inspect_executable_with_progress(AgentKind::Codex, path, &options, &on_progress).await;
+
+        let events = events.into_inner().unwrap();
+        assert_eq!(events.len(), 2);
+        assert!(matches!(events[0], DetectProgress::CheckingVersion { .. }));
+        match &events[1] {
+            DetectProgress::Parsed { version, .. } => {
+                assert_eq!(version, &Some(Version::new(0, 87, 0)));
+            }
+            other => panic!("Unexpected event: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn test_permission_denied_message_includes_owner_and_mode() {
+        use std::os::unix::fs::PermissionsExt;
+
+        // Not executable by anyone, like a root-owned 0700 binary is for other users
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("claude");
+        std::fs::write(&path, "#!/bin/sh\necho 1.0.0\n").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600)).unwrap();
+
+        match inspect_executable(
+            AgentKind::ClaudeCode,
+            path.clone(),
+            &DetectOptions::default(),
+        )
+        .await
+        {
+            AgentStatus::Unknown { error, message } => {
+                assert_eq!(error, DetectionError::PermissionDenied);
+                assert!(message.contains("mode 0600"), "{}", message);
+                assert!(message.contains("owner uid"), "{}", message);
+                assert!(message.contains("root-owned"), "{}", message);
+                assert!(message.contains("chmod a+rx"), "{}", message);
+            }
+            other => panic!("Unexpected status: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn test_allow_major_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = crate::test_util::fake_executable(dir.path(), "gemini", "echo version 3");
+
+        // Off by default: the bare number is kept only as raw output
+        let options = DetectOptions::default();
+        match inspect_executable(AgentKind::Gemini, path.clone(), &options).await {
+            AgentStatus::Installed(meta) => {
+                assert!(meta.version.is_none());
+                assert_eq!(meta.raw_version.as_deref(), Some("version 3"));
+            }
+            other => panic!("Unexpected status: {:?}", other),
+        }
+
+        let options = DetectOptions {
+            allow_major_only: true,
+            ..Default::default()
+        };
+        match inspect_executable(AgentKind::Gemini, path, &options).await {
+            AgentStatus::Installed(meta) => {
+                assert_eq!(meta.version, Some(Version::new(3, 0, 0)));
+                assert_eq!(meta.raw_version.as_deref(), Some("3"));
+            }
+            other => panic!("Unexpected status: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn test_detect_all_against_path_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        for kind in AgentKind::all() {
+            crate::test_util::fake_executable(dir.path(), kind.executable_name(), "echo 99.0.0");
+        }
+
+        let options = DetectOptions {
+            path_env: Some(dir.path().as_os_str().to_owned()),
+            ..Default::default()
+        };
+        let results = detect_all_with_options(options).await;
+
+        assert_eq!(results.len(), AgentKind::all().count());
+        for (kind, result) in results {
+            // An override variable in the environment takes precedence
+            if crate::test_util::has_path_override(kind) {
+                continue;
+            }
+            match result {
+                Ok(AgentStatus::Installed(meta)) => {
+                    assert_eq!(meta.path, dir.path().join(kind.executable_name()));
+                    assert_eq!(meta.version, Some(Version::new(99, 0, 0)));
+                }
+                other => panic!("Unexpected result for {:?}: {:?}", kind, other),
+            }
+        }
+    }
+
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn test_mismatch_is_error_flips_detect_one_result() {
+        if crate::test_util::has_path_override(AgentKind::Codex) {
+            return;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        crate::test_util::fake_executable(dir.path(), "codex", "echo 0.1.0");
+        let mut options = DetectOptions {
+            path_env: Some(dir.path().as_os_str().to_owned()),
+            min_version: Some(Version::new(1, 0, 0)),
+            ..Default::default()
+        };
+
+        let (_, result) = detect_one(AgentKind::Codex, &options).await;
+        assert!(matches!(result, Ok(AgentStatus::VersionMismatch { .. })));
+
+        options.mismatch_is_error = true;
+        let (_, result) = detect_one(AgentKind::Codex, &options).await;
+        assert_eq!(
+            result.unwrap_err(),
+            DetectionError::VersionUnsupported {
+                found: Version::new(0, 1, 0),
+                required: Version::new(1, 0, 0),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_detect_with_cancel_before_start() {
+        let token = CancellationToken::new();
+        token.cancel();
+
+        let result = detect_with_cancel(AgentKind::Codex, DetectOptions::default(), token).await;
+        assert_eq!(result.unwrap_err(), DetectionError::Cancelled);
+    }
+
+    #[tokio::test]
+    #[cfg(target_os = "linux")]
+    async fn test_detect_with_cancel_reaps_version_check() {
+        let dir = tempfile::tempdir().unwrap();
+        let pid_file = dir.path().join("pid");
+        crate::test_util::fake_executable(
+            dir.path(),
+            "codex",
+            &crate::test_util::record_pid_and_sleep(&pid_file),
+        );
+        let options = DetectOptions {
+            path_env: Some(dir.path().as_os_str().to_owned()),
+            timeout: std::time::Duration::from_secs(30),
+            ..Default::default()
+        };
+
+        // Cancel once the version check is running
+        let token = CancellationToken::new();
+        let cancel = token.clone();
+        let watched = pid_file.clone();
+        let canceller = tokio::spawn(async move {
+            let pid = crate::test_util::wait_for_pid(&watched).await;
+            cancel.cancel();
+            pid
+        });
+
+        let result = detect_with_cancel(AgentKind::Codex, options, token).await;
+        assert_eq!(result.unwrap_err(), DetectionError::Cancelled);
+
+        let pid = canceller
+            .await
+            .unwrap()
+            .expect("version check never started");
+        assert!(
+            crate::test_util::is_reaped(pid).await,
+            "process {} lingers",
+            pid
+        );
+    }
+
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn test_probe_edition_from_version_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = crate::test_util::fake_executable(
+            dir.path(),
+            "claude",
+            "echo '2.1.0 (Claude Code, Pro edition)'",
+        );
+
+        let options = DetectOptions {
+            probe_edition: true,
+            ..Default::default()
+        };
+        match inspect_executable(AgentKind::ClaudeCode, path.clone(), &options).await {
+            AgentStatus::Installed(meta) => {
+                assert_eq!(meta.version, Some(Version::new(2, 1, 0)));
+                assert_eq!(meta.edition.as_deref(), Some("Pro"));
+            }
+            other => panic!("Unexpected status: {:?}", other),
+        }
+
+        // Off by default
+        match inspect_executable(AgentKind::ClaudeCode, path, &DetectOptions::default()).await {
+            AgentStatus::Installed(meta) => assert!(meta.edition.is_none()),
+            other => panic!("Unexpected status: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn test_min_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = crate::test_util::fake_executable(dir.path(), "codex", "echo codex-cli 0.87.0");
+
+        let below = DetectOptions {
+            min_version: Some(Version::new(0, 90, 0)),
+            ..Default::default()
+        };
+        match inspect_executable(AgentKind::Codex, path.clone(), &below).await {
+            AgentStatus::VersionMismatch {
+                found,
+                required,
+                path: found_path,
+            } => {
+                assert_eq!(found, Version::new(0, 87, 0));
+                assert_eq!(required, Version::new(0, 90, 0));
+                assert_eq!(found_path, path);
+            }
+            other => panic!("Unexpected status: {:?}", other),
+        }
+
+        let above = DetectOptions {
+            min_version: Some(Version::new(0, 80, 0)),
+            ..Default::default()
+        };
+        match inspect_executable(AgentKind::Codex, path, &above).await {
+            AgentStatus::Installed(meta) => {
+                assert_eq!(meta.version, Some(Version::new(0, 87, 0)));
+            }
+            other => panic!("Unexpected status: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn test_min_supported_version_applies_by_default() {
+        let dir = tempfile::tempdir().unwrap();
+        let path =
+            crate::test_util::fake_executable(dir.path(), "claude", "echo '1.0.9 (Claude Code)'");
+
+        match inspect_executable(
+            AgentKind::ClaudeCode,
+            path.clone(),
+            &DetectOptions::default(),
+        )
+        .await
+        {
+            AgentStatus::VersionMismatch {
+                found, required, ..
+            } => {
+                assert_eq!(found, Version::new(1, 0, 9));
+                assert_eq!(required, Version::new(2, 0, 0));
+            }
+            other => panic!("Unexpected status: {:?}", other),
+        }
+
+        // An explicit requirement replaces the agent's floor
+        let options = DetectOptions {
+            min_version: Some(Version::new(0, 0, 0)),
+            ..Default::default()
+        };
+        assert!(matches!(
+            inspect_executable(AgentKind::ClaudeCode, path, &options).await,
+            AgentStatus::Installed(_)
+        ));
+    }
+
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn test_min_version_with_unparseable_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = crate::test_util::fake_executable(dir.path(), "claude", "echo garbage");
+
+        let options = DetectOptions {
+            min_version: Some(Version::new(2, 0, 0)),
+            ..Default::default()
+        };
+        match inspect_executable(AgentKind::ClaudeCode, path, &options).await {
+            AgentStatus::Installed(meta) => assert!(meta.version.is_none()),
+            other => panic!("Unexpected status: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn test_version_skipped_flag() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = crate::test_util::fake_executable(dir.path(), "claude", "echo garbage");
+
+        // skip_version sets the flag
+        let options = DetectOptions {
+            skip_version: true,
+            ..Default::default()
+        };
+        match inspect_executable(AgentKind::ClaudeCode, path.clone(), &options).await {
+            AgentStatus::Installed(meta) => {
+                assert!(meta.version.is_none());
+                assert!(meta.version_skipped);
+            }
+            other => panic!("Unexpected status: {:?}", other),
+        }
+
+        // A genuine parse failure leaves the flag unset
+        let options = DetectOptions::default();
+        match inspect_executable(AgentKind::ClaudeCode, path, &options).await {
+            AgentStatus::Installed(meta) => {
+                assert!(meta.version.is_none());
+                assert_eq!(meta.raw_version.as_deref(), Some("garbage"));
+                assert!(!meta.version_skipped);
+            }
+            other => panic!("Unexpected status: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_zero_byte_binary_is_corrupt_install() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("codex");
+        std::fs::write(&path, b"").unwrap();
+
+        // Even the fast path reports the corruption
+        let options = DetectOptions {
+            skip_version: true,
+            ..Default::default()
+        };
+        match inspect_executable(AgentKind::Codex, path, &options).await {
+            AgentStatus::Unknown { error, message } => {
+                assert_eq!(error, DetectionError::CorruptInstall);
+                assert!(message.contains("Reinstall"));
+            }
+            other => panic!("Unexpected status: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_quarantined_message_suggests_xattr() {
+        let message = quarantined_message(AgentKind::ClaudeCode, Path::new("/opt/bin/claude"));
+        assert!(message.contains("Gatekeeper"));
+        assert!(message.contains("xattr -d com.apple.quarantine /opt/bin/claude"));
+    }
+
+    #[cfg(target_os = "macos")]
+    #[tokio::test]
+    async fn test_quarantined_binary_diagnosed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = crate::test_util::fake_executable(dir.path(), "claude", "exit 1");
+        let status = std::process::Command::new("xattr")
+            .args(["-w", "com.apple.quarantine", "0081;00000000;Safari;"])
+            .arg(&path)
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        match inspect_executable(AgentKind::ClaudeCode, path, &DetectOptions::default()).await {
+            AgentStatus::Unknown { error, message } => {
+                assert_eq!(error, DetectionError::Quarantined);
+                assert!(message.contains("xattr -d com.apple.quarantine"));
+            }
+            other => panic!("Unexpected status: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_probe_arch_fills_binary_arch() {
+        // Copy of the running test binary: a native executable for this target
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("codex");
+        std::fs::copy(std::env::current_exe().unwrap(), &path).unwrap();
+
+        let options = DetectOptions {
+            skip_version: true,
+            probe_arch: true,
+            ..Default::default()
+        };
+        match inspect_executable(AgentKind::Codex, path.clone(), &options).await {
+            AgentStatus::Installed(meta) => {
+                assert_eq!(meta.binary_arch.as_deref(), Some(std::env::consts::ARCH));
+                assert_eq!(meta.binary_type, Some(crate::BinaryType::NativeExecutable));
+            }
+            other => panic!("Unexpected status: {:?}", other),
+        }
+
+        // Disabled by default; the binary type is still reported
+        let options = DetectOptions {
+            skip_version: true,
+            ..Default::default()
+        };
+        match inspect_executable(AgentKind::Codex, path, &options).await {
+            AgentStatus::Installed(meta) => {
+                assert!(meta.binary_arch.is_none());
+                assert_eq!(meta.binary_type, Some(crate::BinaryType::NativeExecutable));
+            }
+            other => panic!("Unexpected status: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn test_binary_type_reported_by_default() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = crate::test_util::fake_executable(dir.path(), "codex", "echo codex 1.0.0");
+
+        match inspect_executable(AgentKind::Codex, path, &DetectOptions::default()).await {
+            AgentStatus::Installed(meta) => {
+                assert_eq!(meta.binary_type, Some(crate::BinaryType::ShellScript));
+                assert!(meta.binary_arch.is_none());
+            }
+            other => panic!("Unexpected status: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_detect_with_skip_version() {
+        // Test that skip_version returns Installed with None version
+        let options = DetectOptions {
+            skip_version: true,
+            ..Default::default()
+        };
+        let status = detect_with_options(AgentKind::ClaudeCode, options).await;
 
         // If Claude Code is installed, it should return Installed with version: None
         // If not installed, it should return NotInstalled
@@ -646,6 +2420,7 @@ mod mock_tests {
                     meta.raw_version.is_none(),
                     "skip_version should result in raw_version: None"
                 );
+                assert!(meta.version_skipped, "skip_version should be recorded");
             }
             AgentStatus::NotInstalled => {
                 // Expected if agent not installed
@@ -653,4 +2428,149 @@ mod mock_tests {
             _ => panic!("Unexpected status with skip_version: {:?}", status),
         }
     }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_detect_best_prefers_acp_capable_install() {
+        let newer_dir = tempfile::tempdir().unwrap();
+        let older_dir = tempfile::tempdir().unwrap();
+        // Newer release that predates the `acp` subcommand
+        let newer = crate::test_util::fake_executable(
+            newer_dir.path(),
+            "opencode",
+            r#"if [ "$1" = "acp" ]; then exit 1; fi
+echo "2.0.0""#,
+        );
+        let older = crate::test_util::fake_executable(
+            older_dir.path(),
+            "opencode",
+            r#"if [ "$1" = "acp" ]; then
+  read request
+  echo '{"jsonrpc":"2.0","id":1,"result":{"agentCapabilities":{"loadSession":true}}}'
+else
+  echo "1.1.25"
+fi"#,
+        );
+
+        let options = DetectOptions {
+            probe_acp_capabilities: true,
+            ..Default::default()
+        };
+        let status =
+            detect_best_among(AgentKind::OpenCode, vec![newer, older.clone()], &options).await;
+        match status {
+            AgentStatus::Installed(meta) => {
+                assert_eq!(meta.path, older);
+                assert_eq!(meta.version, Some(Version::new(1, 1, 25)));
+                assert_eq!(meta.acp_capabilities, ["loadSession"]);
+            }
+            other => panic!("Unexpected status: {:?}", other),
+        }
+
+        let status = detect_best_among(AgentKind::OpenCode, vec![], &options).await;
+        assert!(matches!(status, AgentStatus::NotInstalled));
+    }
+
+    #[test]
+    fn test_best_candidate_prefers_newest_on_ties() {
+        let installed = |path: &str, minor| {
+            AgentStatus::Installed(crate::test_util::installed_meta(
+                path,
+                Some(Version::new(1, minor, 0)),
+            ))
+        };
+        let best = best_candidate(vec![
+            (installed("/a/codex", 1), true),
+            (installed("/b/codex", 3), true),
+            (installed("/c/codex", 3), true),
+            (installed("/d/codex", 9), false),
+        ])
+        .unwrap();
+        assert_eq!(best.path(), Some(Path::new("/b/codex")));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_best_candidates_searched_in_path_env() {
+        let first = tempfile::tempdir().unwrap();
+        let second = tempfile::tempdir().unwrap();
+        let first_codex = crate::test_util::fake_executable(first.path(), "codex", "echo 1.0.0");
+        let second_codex = crate::test_util::fake_executable(second.path(), "codex", "echo 2.0.0");
+
+        let options = DetectOptions {
+            path_env: Some(std::env::join_paths([first.path(), second.path()]).unwrap()),
+            ..Default::default()
+        };
+        let candidates = all_candidates(AgentKind::Codex, &options);
+        let first_at = candidates.iter().position(|path| *path == first_codex);
+        let second_at = candidates.iter().position(|path| *path == second_codex);
+        assert!(
+            first_at.is_some() && first_at < second_at,
+            "{:?}",
+            candidates
+        );
+    }
+
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn test_detect_all_paths_finds_shadowed_copy() {
+        if crate::test_util::has_path_override(AgentKind::Codex) {
+            return;
+        }
+        let old = tempfile::tempdir().unwrap();
+        let new = tempfile::tempdir().unwrap();
+        let old_codex = crate::test_util::fake_executable(old.path(), "codex", "echo 0.40.0");
+        let new_codex = crate::test_util::fake_executable(new.path(), "codex", "echo 0.87.0");
+
+        let options = DetectOptions {
+            path_env: Some(std::env::join_paths([old.path(), new.path()]).unwrap()),
+            min_version: Some(Version::new(0, 50, 0)),
+            ..Default::default()
+        };
+        let copies = detect_all_paths_with_options(AgentKind::Codex, options).await;
+
+        // PATH order first; home locations on this machine may follow
+        assert!(copies.len() >= 2, "{:?}", copies);
+        assert_eq!(copies[0].path, old_codex);
+        assert_eq!(copies[0].version, Some(Version::new(0, 40, 0)));
+        assert_eq!(copies[1].path, new_codex);
+        assert_eq!(copies[1].version, Some(Version::new(0, 87, 0)));
+    }
+
+    #[test]
+    fn test_scan_all_locations_covers_every_agent() {
+        let scan = scan_all_locations();
+        assert_eq!(scan.len(), AgentKind::all().count());
+        for paths in scan.values() {
+            assert!(paths.iter().all(|path| path.is_file()));
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_prefer_version_file_skips_spawn() {
+        let dir = tempfile::tempdir().unwrap();
+        // Running this binary would report a different version
+        let path = crate::test_util::fake_executable(dir.path(), "codex", "echo 9.9.9");
+        std::fs::write(
+            dir.path().join("package.json"),
+            r#"{ "name": "@openai/codex", "version": "0.87.0" }"#,
+        )
+        .unwrap();
+
+        let options = DetectOptions {
+            prefer_version_file: true,
+            ..Default::default()
+        };
+        match inspect_executable(AgentKind::Codex, path.clone(), &options).await {
+            AgentStatus::Installed(meta) => {
+                assert_eq!(meta.version, Some(Version::new(0, 87, 0)));
+            }
+            other => panic!("Unexpected status: {:?}", other),
+        }
+
+        // Without the option the binary is run
+        let status = inspect_executable(AgentKind::Codex, path, &DetectOptions::default()).await;
+        assert_eq!(status.version(), Some(&Version::new(9, 9, 9)));
+    }
 }
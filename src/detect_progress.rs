@@ -0,0 +1,73 @@
+//! Progress reporting types for detection.
+//!
+//! This module provides the [`DetectProgress`] enum, which represents the
+//! stages of detecting a single agent. It mirrors the installation progress
+//! model so UIs can show a spinner or status line while detection runs.
+
+use crate::AgentKind;
+use semver::Version;
+use std::path::PathBuf;
+
+/// Progress stages during detection of a single agent.
+///
+/// Events are reported in order via the callback passed to
+/// [`detect_with_progress`](crate::detect_with_progress). Not every stage
+/// is reported for every detection: `Found` is skipped when the agent is
+/// not installed, `CheckingVersion`/`Parsed` are skipped when
+/// `skip_version` is set, and `CheckingVersion` is skipped when the version
+/// is read from a file (`prefer_version_file`).
+///
+/// # Example
+///
+/// ```rust
+/// use rig_acp_discovery::DetectProgress;
+///
+/// fn on_progress(progress: DetectProgress) {
+///     match &progress {
+///         DetectProgress::Searching { agent } => {
+///             println!("Looking for {}...", agent.display_name());
+///         }
+///         DetectProgress::Found { agent, path } => {
+///             println!("Found {} at {}", agent.display_name(), path.display());
+///         }
+///         DetectProgress::CheckingVersion { agent } => {
+///             println!("Checking {} version...", agent.display_name());
+///         }
+///         DetectProgress::Parsed { agent, version } => {
+///             println!("{} version: {:?}", agent.display_name(), version);
+///         }
+///         _ => {}
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum DetectProgress {
+    /// Searching for the agent's executable.
+    Searching {
+        /// The agent being detected.
+        agent: AgentKind,
+    },
+
+    /// The agent's executable was found.
+    Found {
+        /// The agent being detected.
+        agent: AgentKind,
+        /// Path to the executable.
+        path: PathBuf,
+    },
+
+    /// Running the agent's version check.
+    CheckingVersion {
+        /// The agent being detected.
+        agent: AgentKind,
+    },
+
+    /// The version check output was parsed.
+    Parsed {
+        /// The agent being detected.
+        agent: AgentKind,
+        /// The parsed version, or `None` if the output had no recognizable version.
+        version: Option<Version>,
+    },
+}
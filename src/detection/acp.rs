@@ -0,0 +1,206 @@
+//! ACP capability probing via the agent's `initialize` handshake.
+
+use crate::AgentKind;
+use serde_json::{json, Value};
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::Command;
+use tokio::time::timeout;
+use tracing::debug;
+
+/// ACP protocol version sent in the `initialize` request.
+const PROTOCOL_VERSION: u64 = 1;
+
+/// Arguments that start the agent executable as an ACP server on stdio.
+///
+/// Returns `None` for agents whose CLI does not speak ACP itself. Claude
+/// Code and Codex are served through separate adapter packages, so their
+/// executables cannot be probed directly.
+pub(crate) fn acp_args(kind: AgentKind) -> Option<&'static [&'static str]> {
+    match kind {
+        AgentKind::Gemini => Some(&["--experimental-acp"]),
+        AgentKind::OpenCode => Some(&["acp"]),
+        AgentKind::ClaudeCode | AgentKind::Codex => None,
+    }
+}
+
+/// Probe the ACP capabilities an agent advertises.
+///
+/// Starts the agent in ACP mode, sends an `initialize` request over stdin,
+/// and reads the `agentCapabilities` object from the response. The process
+/// is killed once the response arrives or `timeout_duration` elapses.
+///
+/// Returns an empty list if the agent has no ACP mode, fails to start,
+/// times out, or replies with an error.
+pub(crate) async fn probe_acp_capabilities(
+    kind: AgentKind,
+    path: &Path,
+    timeout_duration: Duration,
+) -> Vec<String> {
+    acp_handshake(kind, path, timeout_duration)
+        .await
+        .unwrap_or_default()
+}
+
+/// Run the ACP handshake, distinguishing failure from an empty capability set.
+///
+/// Returns `Some(capabilities)` if the agent answered `initialize`, and
+/// `None` if it has no ACP mode, failed to start, timed out, or replied
+/// with an error.
+pub(crate) async fn acp_handshake(
+    kind: AgentKind,
+    path: &Path,
+    timeout_duration: Duration,
+) -> Option<Vec<String>> {
+    let args = acp_args(kind)?;
+
+    match timeout(timeout_duration, initialize(path, args)).await {
+        Ok(capabilities) => capabilities.map(|c| flatten_capabilities(&c)),
+        Err(_) => {
+            debug!(
+                "ACP initialize timed out for {} after {:?}",
+                kind.display_name(),
+                timeout_duration
+            );
+            None
+        }
+    }
+}
+
+/// Run the `initialize` handshake and return the `agentCapabilities` value.
+async fn initialize(path: &Path, args: &[&str]) -> Option<Value> {
+    let mut child = Command::new(path)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .ok()?;
+
+    let request = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": PROTOCOL_VERSION,
+            "clientCapabilities": {
+                "fs": { "readTextFile": false, "writeTextFile": false },
+                "terminal": false,
+            },
+        },
+    });
+    let mut stdin = child.stdin.take()?;
+    stdin
+        .write_all(format!("{}\n", request).as_bytes())
+        .await
+        .ok()?;
+    stdin.flush().await.ok()?;
+
+    // Skip notifications and log lines until the response to our request
+    let mut lines = BufReader::new(child.stdout.take()?).lines();
+    while let Some(line) = lines.next_line().await.ok()? {
+        let Ok(message) = serde_json::from_str::<Value>(&line) else {
+            continue;
+        };
+        if message.get("id") != Some(&json!(1)) {
+            continue;
+        }
+        return message
+            .get("result")
+            .and_then(|result| result.get("agentCapabilities"))
+            .cloned();
+    }
+
+    None
+}
+
+/// Flatten an `agentCapabilities` object into capability names.
+///
+/// Every `true` leaf becomes an entry, with nested objects joined by dots
+/// (e.g., `{"promptCapabilities": {"image": true}}` becomes
+/// `promptCapabilities.image`). The result is sorted.
+pub(crate) fn flatten_capabilities(capabilities: &Value) -> Vec<String> {
+    fn walk(prefix: &str, value: &Value, out: &mut Vec<String>) {
+        match value {
+            Value::Bool(true) => out.push(prefix.to_string()),
+            Value::Object(map) => {
+                for (key, value) in map {
+                    let name = if prefix.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{}.{}", prefix, key)
+                    };
+                    walk(&name, value, out);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut out = Vec::new();
+    walk("", capabilities, &mut out);
+    out.sort();
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flatten_capabilities() {
+        let capabilities = json!({
+            "loadSession": true,
+            "promptCapabilities": { "image": true, "audio": false, "embeddedContext": true },
+            "mcpCapabilities": { "http": false, "sse": false },
+        });
+        assert_eq!(
+            flatten_capabilities(&capabilities),
+            [
+                "loadSession",
+                "promptCapabilities.embeddedContext",
+                "promptCapabilities.image"
+            ]
+        );
+        assert!(flatten_capabilities(&json!(null)).is_empty());
+    }
+
+    #[test]
+    fn test_acp_args() {
+        assert_eq!(acp_args(AgentKind::OpenCode), Some(&["acp"][..]));
+        assert!(acp_args(AgentKind::Gemini).is_some());
+        assert!(acp_args(AgentKind::ClaudeCode).is_none());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_probe_acp_capabilities_fake_agent() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = crate::test_util::fake_executable(
+            dir.path(),
+            "opencode",
+            r#"read request
+echo 'starting up'
+echo '{"jsonrpc":"2.0","method":"session/update","params":{}}'
+echo '{"jsonrpc":"2.0","id":1,"result":{"protocolVersion":1,"agentCapabilities":{"loadSession":true,"promptCapabilities":{"image":true,"audio":false}}}}'"#,
+        );
+
+        let capabilities =
+            probe_acp_capabilities(AgentKind::OpenCode, &path, Duration::from_secs(5)).await;
+        assert_eq!(capabilities, ["loadSession", "promptCapabilities.image"]);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_probe_acp_capabilities_timeout() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = crate::test_util::fake_executable(dir.path(), "opencode", "sleep 5");
+
+        let capabilities =
+            probe_acp_capabilities(AgentKind::OpenCode, &path, Duration::from_millis(100)).await;
+        assert!(capabilities.is_empty());
+    }
+}
@@ -0,0 +1,145 @@
+//! Executable architecture probing.
+//!
+//! Reads the executable header (ELF, Mach-O or PE) to determine which CPU
+//! architecture an agent binary was built for. This surfaces mismatches
+//! such as an x86_64 binary running under Rosetta on Apple Silicon.
+
+use super::header::{executable_format, ExecutableFormat};
+
+/// Determine the architecture an executable was built for from its
+/// header (see [`read_header`](super::read_header)).
+///
+/// Returns an architecture name matching `std::env::consts::ARCH`
+/// (e.g., "x86_64", "aarch64"), `"universal"` for multi-architecture
+/// Mach-O binaries, or `None` if the file is not a recognized binary
+/// (such as a shell script or Node.js shim).
+pub(crate) fn binary_arch(header: &[u8]) -> Option<String> {
+    parse_arch(header).map(str::to_string)
+}
+
+/// Parse the architecture from an executable header.
+fn parse_arch(header: &[u8]) -> Option<&'static str> {
+    match executable_format(header)? {
+        ExecutableFormat::Elf => elf_arch(header),
+        ExecutableFormat::MachO => macho_arch(read_u32_le(header, 4)?),
+        ExecutableFormat::FatMachO => Some("universal"),
+        ExecutableFormat::Pe => pe_arch(header),
+    }
+}
+
+/// Architecture from an ELF `e_machine` field.
+fn elf_arch(header: &[u8]) -> Option<&'static str> {
+    // EI_DATA: 1 = little-endian, 2 = big-endian
+    let machine = match header.get(5)? {
+        1 => u16::from_le_bytes([*header.get(18)?, *header.get(19)?]),
+        2 => u16::from_be_bytes([*header.get(18)?, *header.get(19)?]),
+        _ => return None,
+    };
+    match machine {
+        0x03 => Some("x86"),
+        0x28 => Some("arm"),
+        0x3e => Some("x86_64"),
+        0xb7 => Some("aarch64"),
+        0xf3 => Some("riscv64"),
+        _ => None,
+    }
+}
+
+/// Architecture from a Mach-O `cputype` field.
+fn macho_arch(cputype: u32) -> Option<&'static str> {
+    match cputype {
+        0x0000_0007 => Some("x86"),
+        0x0100_0007 => Some("x86_64"),
+        0x0000_000c => Some("arm"),
+        0x0100_000c => Some("aarch64"),
+        _ => None,
+    }
+}
+
+/// Architecture from a PE file header's `Machine` field.
+fn pe_arch(header: &[u8]) -> Option<&'static str> {
+    let pe_offset = read_u32_le(header, 0x3c)? as usize;
+    if header.get(pe_offset..pe_offset + 4)? != b"PE\0\0" {
+        return None;
+    }
+    let machine = u16::from_le_bytes([*header.get(pe_offset + 4)?, *header.get(pe_offset + 5)?]);
+    match machine {
+        0x014c => Some("x86"),
+        0x8664 => Some("x86_64"),
+        0xaa64 => Some("aarch64"),
+        _ => None,
+    }
+}
+
+fn read_u32_le(bytes: &[u8], offset: usize) -> Option<u32> {
+    let slice = bytes.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes([slice[0], slice[1], slice[2], slice[3]]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Minimal 64-bit little-endian ELF header for the given machine.
+    fn elf_header(machine: u16) -> Vec<u8> {
+        let mut header = vec![0u8; 64];
+        header[..4].copy_from_slice(&[0x7f, b'E', b'L', b'F']);
+        header[4] = 2; // ELFCLASS64
+        header[5] = 1; // little-endian
+        header[18..20].copy_from_slice(&machine.to_le_bytes());
+        header
+    }
+
+    #[test]
+    fn test_elf_fixture() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("agent");
+        std::fs::write(&path, elf_header(0xb7)).unwrap();
+
+        let header = crate::detection::read_header(&path).unwrap();
+        assert_eq!(binary_arch(&header).as_deref(), Some("aarch64"));
+    }
+
+    #[test]
+    fn test_macho_header() {
+        let mut header = vec![0xcf, 0xfa, 0xed, 0xfe];
+        header.extend_from_slice(&0x0100_0007u32.to_le_bytes());
+        assert_eq!(parse_arch(&header), Some("x86_64"));
+
+        let mut header = vec![0xcf, 0xfa, 0xed, 0xfe];
+        header.extend_from_slice(&0x0100_000cu32.to_le_bytes());
+        assert_eq!(parse_arch(&header), Some("aarch64"));
+
+        assert_eq!(
+            parse_arch(&[0xca, 0xfe, 0xba, 0xbe, 0, 0, 0, 2]),
+            Some("universal")
+        );
+    }
+
+    #[test]
+    fn test_pe_header() {
+        let mut header = vec![0u8; 0x90];
+        header[..2].copy_from_slice(b"MZ");
+        header[0x3c..0x40].copy_from_slice(&0x80u32.to_le_bytes());
+        header[0x80..0x84].copy_from_slice(b"PE\0\0");
+        header[0x84..0x86].copy_from_slice(&0x8664u16.to_le_bytes());
+        assert_eq!(parse_arch(&header), Some("x86_64"));
+    }
+
+    #[test]
+    fn test_script_has_no_arch() {
+        assert_eq!(parse_arch(b"#!/usr/bin/env node\n"), None);
+        assert_eq!(parse_arch(b""), None);
+        assert_eq!(parse_arch(b"MZ"), None);
+    }
+
+    #[test]
+    fn test_current_exe_matches_target_arch() {
+        let exe = std::env::current_exe().unwrap();
+        let header = crate::detection::read_header(&exe).unwrap();
+        assert_eq!(
+            binary_arch(&header).as_deref(),
+            Some(std::env::consts::ARCH)
+        );
+    }
+}
@@ -0,0 +1,147 @@
+//! Executable type probing (native binary or script).
+//!
+//! Uses the first bytes of an agent executable to tell native binaries
+//! apart from the shell, batch and Node.js wrappers that package managers
+//! install. Wrappers explain behavior such as a slow start-up.
+
+use super::header::executable_format;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// What kind of file an agent executable is.
+///
+/// Determined from the file's magic number or `#!` line, and from the
+/// extension for Windows batch files.
+///
+/// This enum is marked `#[non_exhaustive]` to allow recognizing more file
+/// types in future versions.
+///
+/// # Example
+///
+/// ```rust
+/// use rig_acp_discovery::{AgentStatus, BinaryType};
+///
+/// fn warn_about_shims(status: &AgentStatus) {
+///     if let AgentStatus::Installed(meta) = status {
+///         if meta.binary_type == Some(BinaryType::NodeScript) {
+///             println!("{:?} is a Node.js script; startup may be slow", meta.path);
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum BinaryType {
+    /// A compiled executable (ELF, Mach-O or PE).
+    NativeExecutable,
+    /// A script run by a Unix shell (`sh`, `bash`, `zsh`, ...).
+    ShellScript,
+    /// A Windows batch file (`.cmd` or `.bat`), such as an npm shim.
+    BatchFile,
+    /// A script run by Node.js, such as an npm package's entry point.
+    NodeScript,
+    /// Any other file, such as a script for another interpreter.
+    Unknown,
+}
+
+/// Determine what kind of file the executable at `path` is, from its
+/// header (see [`read_header`](super::read_header)).
+pub(crate) fn binary_type(path: &Path, header: &[u8]) -> BinaryType {
+    if executable_format(header).is_some() {
+        return BinaryType::NativeExecutable;
+    }
+
+    if let Some(shebang) = header.strip_prefix(b"#!") {
+        let line = shebang.split(|&b| b == b'\n').next().unwrap_or_default();
+        return interpreter_type(&String::from_utf8_lossy(line));
+    }
+
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase);
+    match extension.as_deref() {
+        Some("cmd" | "bat") => BinaryType::BatchFile,
+        Some("js" | "mjs" | "cjs") => BinaryType::NodeScript,
+        _ => BinaryType::Unknown,
+    }
+}
+
+/// Classify a script from its `#!` line (without the `#!`).
+///
+/// The interpreter is the last path component of the first word, or of
+/// the second word for `/usr/bin/env` lines.
+fn interpreter_type(line: &str) -> BinaryType {
+    let mut words = line.split_whitespace();
+    let mut program = words.next().unwrap_or_default();
+    if program.rsplit('/').next() == Some("env") {
+        program = words
+            .find(|word| !word.starts_with('-'))
+            .unwrap_or_default();
+    }
+
+    match program.rsplit('/').next().unwrap_or_default() {
+        "node" | "nodejs" | "bun" | "deno" => BinaryType::NodeScript,
+        "sh" | "bash" | "dash" | "zsh" | "ksh" | "ash" => BinaryType::ShellScript,
+        _ => BinaryType::Unknown,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_native_headers() {
+        let elf = [0x7f, b'E', b'L', b'F', 2, 1, 1, 0];
+        assert_eq!(
+            binary_type(Path::new("claude"), &elf),
+            BinaryType::NativeExecutable
+        );
+        assert_eq!(
+            binary_type(Path::new("claude.exe"), b"MZ\x90\x00"),
+            BinaryType::NativeExecutable
+        );
+    }
+
+    #[test]
+    fn test_classify_scripts() {
+        let node = b"#!/usr/bin/env node\nrequire('./cli.js')";
+        assert_eq!(
+            binary_type(Path::new("codex"), node),
+            BinaryType::NodeScript
+        );
+        let env_flags = b"#!/usr/bin/env -S node --no-warnings\n";
+        assert_eq!(
+            binary_type(Path::new("codex"), env_flags),
+            BinaryType::NodeScript
+        );
+        assert_eq!(
+            binary_type(Path::new("gemini"), b"#!/bin/bash\nexec node x"),
+            BinaryType::ShellScript
+        );
+        assert_eq!(
+            binary_type(Path::new("tool"), b"#!/usr/bin/python3\n"),
+            BinaryType::Unknown
+        );
+        assert_eq!(
+            binary_type(Path::new("codex.CMD"), b"@ECHO off\r\n"),
+            BinaryType::BatchFile
+        );
+        assert_eq!(binary_type(Path::new("tool"), b""), BinaryType::Unknown);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_binary_type_of_fake_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let script = crate::test_util::fake_executable(dir.path(), "claude", "echo 1.0.0");
+        let header = crate::detection::read_header(&script).unwrap();
+        assert_eq!(binary_type(&script, &header), BinaryType::ShellScript);
+
+        let binary = dir.path().join("codex");
+        std::fs::write(&binary, [0x7f, b'E', b'L', b'F', 2, 1, 1, 0, 0, 0]).unwrap();
+        let header = crate::detection::read_header(&binary).unwrap();
+        assert_eq!(binary_type(&binary, &header), BinaryType::NativeExecutable);
+    }
+}
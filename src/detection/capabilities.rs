@@ -0,0 +1,131 @@
+//! Self-reported agent capabilities via a `capabilities` subcommand.
+
+use crate::AgentKind;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::process::Command;
+use tokio::time::timeout;
+use tracing::debug;
+
+/// Arguments that ask an agent to print its capabilities as JSON.
+const CAPABILITIES_ARGS: &[&str] = &["capabilities", "--json"];
+
+/// Capabilities an agent reports about itself.
+///
+/// Read from the JSON printed by `<agent> capabilities --json` when
+/// `DetectOptions::probe_capabilities` is enabled. Missing keys take their
+/// default (`false` or `None`), and unknown keys are ignored.
+///
+/// # Example
+///
+/// ```rust
+/// use rig_acp_discovery::Capabilities;
+///
+/// let caps: Capabilities = serde_json::from_str(
+///     r#"{ "supports_streaming": true, "max_context": 200000 }"#,
+/// ).unwrap();
+/// assert!(caps.supports_streaming);
+/// assert!(!caps.supports_tools);
+/// assert_eq!(caps.max_context, Some(200_000));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Capabilities {
+    /// Whether the agent can stream partial responses.
+    pub supports_streaming: bool,
+
+    /// Whether the agent can call tools.
+    pub supports_tools: bool,
+
+    /// Largest context window the agent supports, in tokens.
+    pub max_context: Option<u64>,
+}
+
+/// Probe the capabilities an agent reports about itself.
+///
+/// Runs `<path> capabilities --json` and parses its stdout. The process is
+/// killed if it doesn't finish within `timeout_duration`.
+///
+/// Returns `None` if the agent has no such subcommand, fails, times out,
+/// or prints something other than a capabilities object.
+pub(crate) async fn probe_capabilities(
+    path: &Path,
+    kind: AgentKind,
+    timeout_duration: Duration,
+) -> Option<Capabilities> {
+    let output = Command::new(path)
+        .args(CAPABILITIES_ARGS)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .output();
+
+    let output = match timeout(timeout_duration, output).await {
+        Ok(output) => output.ok()?,
+        Err(_) => {
+            debug!(
+                "Capabilities probe timed out for {} after {:?}",
+                kind.display_name(),
+                timeout_duration
+            );
+            return None;
+        }
+    };
+    if !output.status.success() {
+        return None;
+    }
+
+    serde_json::from_slice(&output.stdout).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn test_probe_capabilities_parses_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = crate::test_util::fake_executable(
+            dir.path(),
+            "codex",
+            r#"[ "$1" = capabilities ] && echo '{"supports_streaming": true, "supports_tools": true, "max_context": 400000, "extra": 1}'"#,
+        );
+
+        let caps = probe_capabilities(&path, AgentKind::Codex, Duration::from_secs(2)).await;
+        assert_eq!(
+            caps,
+            Some(Capabilities {
+                supports_streaming: true,
+                supports_tools: true,
+                max_context: Some(400_000),
+            })
+        );
+    }
+
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn test_probe_capabilities_unsupported() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = crate::test_util::fake_executable(
+            dir.path(),
+            "claude",
+            "echo \"error: unknown command '$1'\" >&2; exit 1",
+        );
+
+        let caps = probe_capabilities(&path, AgentKind::ClaudeCode, Duration::from_secs(2)).await;
+        assert_eq!(caps, None);
+    }
+
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn test_probe_capabilities_non_json_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = crate::test_util::fake_executable(dir.path(), "gemini", "echo 0.1.5");
+
+        let caps = probe_capabilities(&path, AgentKind::Gemini, Duration::from_secs(2)).await;
+        assert_eq!(caps, None);
+    }
+}
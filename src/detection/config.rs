@@ -0,0 +1,197 @@
+//! Agent configuration file probing.
+
+use crate::AgentKind;
+use regex::Regex;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// Location of an agent's user configuration file, relative to the home directory.
+///
+/// - Claude Code: `~/.claude/settings.json`
+/// - Codex: `~/.codex/config.toml`
+/// - OpenCode: `~/.config/opencode/opencode.json`
+/// - Gemini CLI: `~/.gemini/settings.json`
+pub(crate) fn config_path(kind: AgentKind, home: &Path) -> PathBuf {
+    match kind {
+        AgentKind::ClaudeCode => home.join(".claude").join("settings.json"),
+        AgentKind::Codex => home.join(".codex").join("config.toml"),
+        AgentKind::OpenCode => home.join(".config").join("opencode").join("opencode.json"),
+        AgentKind::Gemini => home.join(".gemini").join("settings.json"),
+    }
+}
+
+/// Probe the agent's configured default model from its config file.
+///
+/// Reads the agent's configuration file under `home` and extracts the
+/// model setting. Codex uses TOML (`model = "..."`), the other agents use
+/// JSON (`"model": "..."`, or `"model": { "name": "..." }` for Gemini CLI).
+///
+/// Returns `None` if the file doesn't exist, can't be read, or has no
+/// model setting.
+pub(crate) fn probe_default_model(kind: AgentKind, home: &Path) -> Option<String> {
+    let contents = std::fs::read_to_string(config_path(kind, home)).ok()?;
+
+    let pattern = match kind {
+        AgentKind::Codex => r#"(?m)^\s*model\s*=\s*"([^"]+)""#,
+        _ => r#""model"\s*:\s*(?:\{[^}]*?"name"\s*:\s*)?"([^"]+)""#,
+    };
+    let re = Regex::new(pattern).expect("Invalid model regex");
+
+    re.captures(&contents)
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str().to_string())
+}
+
+/// Probe the schema version of the agent's config file.
+///
+/// For JSON configs this is the top-level `version` key (a string or a
+/// number), falling back to the `$schema` URL. For Codex's TOML config it
+/// is a top-level `version = "..."` line.
+///
+/// Returns `None` if the file doesn't exist, can't be parsed, or declares
+/// no version.
+pub(crate) fn probe_config_schema_version(kind: AgentKind, home: &Path) -> Option<String> {
+    let contents = std::fs::read_to_string(config_path(kind, home)).ok()?;
+
+    if kind == AgentKind::Codex {
+        // Only keys before the first table header are top-level
+        let re = Regex::new(r#"^\s*version\s*=\s*"?([^"\s]+)"?"#).expect("Invalid version regex");
+        return contents
+            .lines()
+            .take_while(|line| !line.trim_start().starts_with('['))
+            .find_map(|line| re.captures(line)?.get(1))
+            .map(|m| m.as_str().to_string());
+    }
+
+    let config: Value = serde_json::from_str(&contents).ok()?;
+    match config.get("version").or_else(|| config.get("$schema"))? {
+        Value::String(version) => Some(version.clone()),
+        Value::Number(version) => Some(version.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_config(home: &Path, kind: AgentKind, contents: &str) {
+        let path = config_path(kind, home);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn test_probe_default_model_claude_json() {
+        let home = tempfile::tempdir().unwrap();
+        write_config(
+            home.path(),
+            AgentKind::ClaudeCode,
+            r#"{ "model": "claude-sonnet-4", "permissions": {} }"#,
+        );
+        assert_eq!(
+            probe_default_model(AgentKind::ClaudeCode, home.path()),
+            Some("claude-sonnet-4".to_string())
+        );
+    }
+
+    #[test]
+    fn test_probe_default_model_codex_toml() {
+        let home = tempfile::tempdir().unwrap();
+        write_config(
+            home.path(),
+            AgentKind::Codex,
+            "approval_policy = \"on-request\"\nmodel = \"gpt-5-codex\"\n",
+        );
+        assert_eq!(
+            probe_default_model(AgentKind::Codex, home.path()),
+            Some("gpt-5-codex".to_string())
+        );
+    }
+
+    #[test]
+    fn test_probe_default_model_gemini_nested() {
+        let home = tempfile::tempdir().unwrap();
+        write_config(
+            home.path(),
+            AgentKind::Gemini,
+            r#"{ "model": { "name": "gemini-2.5-pro" } }"#,
+        );
+        assert_eq!(
+            probe_default_model(AgentKind::Gemini, home.path()),
+            Some("gemini-2.5-pro".to_string())
+        );
+    }
+
+    #[test]
+    fn test_probe_config_schema_version_json() {
+        let home = tempfile::tempdir().unwrap();
+        write_config(
+            home.path(),
+            AgentKind::Gemini,
+            r#"{ "version": 2, "model": { "name": "gemini-2.5-pro" } }"#,
+        );
+        assert_eq!(
+            probe_config_schema_version(AgentKind::Gemini, home.path()),
+            Some("2".to_string())
+        );
+
+        write_config(
+            home.path(),
+            AgentKind::OpenCode,
+            r#"{ "$schema": "https://opencode.ai/config.json" }"#,
+        );
+        assert_eq!(
+            probe_config_schema_version(AgentKind::OpenCode, home.path()),
+            Some("https://opencode.ai/config.json".to_string())
+        );
+    }
+
+    #[test]
+    fn test_probe_config_schema_version_codex_toml() {
+        let home = tempfile::tempdir().unwrap();
+        write_config(
+            home.path(),
+            AgentKind::Codex,
+            "version = \"1\"\nmodel = \"gpt-5-codex\"\n\n[profiles.fast]\nversion = \"9\"\n",
+        );
+        assert_eq!(
+            probe_config_schema_version(AgentKind::Codex, home.path()),
+            Some("1".to_string())
+        );
+
+        // A version inside a table is not the schema version
+        write_config(home.path(), AgentKind::Codex, "[tools]\nversion = \"9\"\n");
+        assert_eq!(
+            probe_config_schema_version(AgentKind::Codex, home.path()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_probe_config_schema_version_missing() {
+        let home = tempfile::tempdir().unwrap();
+        write_config(home.path(), AgentKind::ClaudeCode, r#"{ "model": "opus" }"#);
+        assert_eq!(
+            probe_config_schema_version(AgentKind::ClaudeCode, home.path()),
+            None
+        );
+        assert_eq!(
+            probe_config_schema_version(AgentKind::Gemini, home.path()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_probe_default_model_missing_config() {
+        let home = tempfile::tempdir().unwrap();
+        assert_eq!(probe_default_model(AgentKind::OpenCode, home.path()), None);
+    }
+
+    #[test]
+    fn test_probe_default_model_no_model_key() {
+        let home = tempfile::tempdir().unwrap();
+        write_config(home.path(), AgentKind::OpenCode, r#"{ "theme": "dark" }"#);
+        assert_eq!(probe_default_model(AgentKind::OpenCode, home.path()), None);
+    }
+}
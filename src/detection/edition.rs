@@ -0,0 +1,97 @@
+//! Agent edition (license tier) probing.
+
+use super::config::config_path;
+use crate::AgentKind;
+use regex::Regex;
+use serde_json::Value;
+use std::path::Path;
+
+/// Probe the agent's edition (e.g., "Pro" or "Enterprise").
+///
+/// Looks for an edition marker in the agent's `--version` output first,
+/// such as `Pro edition`, `Edition: Team` or a parenthesized `(Pro)`, then
+/// for an `edition` setting in the agent's config file under `home`.
+///
+/// Returns `None` if neither names an edition.
+pub(crate) fn probe_edition(
+    kind: AgentKind,
+    version_output: Option<&str>,
+    home: Option<&Path>,
+) -> Option<String> {
+    version_output
+        .and_then(edition_from_output)
+        .or_else(|| edition_from_config(kind, home?))
+}
+
+/// Find an edition marker in CLI output.
+fn edition_from_output(output: &str) -> Option<String> {
+    let re = Regex::new(
+        r"(?i)\b(\w+)[ \t]+edition\b|\bedition\s*[:=]?\s*(\w+)|\((free|plus|pro|max|team|business|enterprise)\)",
+    )
+    .expect("Invalid edition regex");
+
+    let caps = re.captures(output)?;
+    (1..=3)
+        .find_map(|i| caps.get(i))
+        .map(|m| m.as_str().to_string())
+}
+
+/// Read an `edition` setting from the agent's config file.
+fn edition_from_config(kind: AgentKind, home: &Path) -> Option<String> {
+    let contents = std::fs::read_to_string(config_path(kind, home)).ok()?;
+
+    if kind == AgentKind::Codex {
+        let re = Regex::new(r#"(?m)^\s*edition\s*=\s*"([^"]+)""#).expect("Invalid edition regex");
+        return re
+            .captures(&contents)
+            .and_then(|caps| caps.get(1))
+            .map(|m| m.as_str().to_string());
+    }
+
+    let config: Value = serde_json::from_str(&contents).ok()?;
+    config.get("edition")?.as_str().map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edition_from_output() {
+        assert_eq!(
+            edition_from_output("2.1.0 (Claude Code, Pro edition)"),
+            Some("Pro".to_string())
+        );
+        assert_eq!(
+            edition_from_output("agent 1.0.0\nEdition: Enterprise"),
+            Some("Enterprise".to_string())
+        );
+        assert_eq!(
+            edition_from_output("gemini 0.3.0 (Team)"),
+            Some("Team".to_string())
+        );
+        assert_eq!(edition_from_output("2.1.12 (Claude Code)"), None);
+    }
+
+    #[test]
+    fn test_edition_from_config() {
+        let home = tempfile::tempdir().unwrap();
+        let path = config_path(AgentKind::Gemini, home.path());
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, r#"{ "edition": "Business" }"#).unwrap();
+
+        assert_eq!(
+            probe_edition(AgentKind::Gemini, Some("0.3.0"), Some(home.path())),
+            Some("Business".to_string())
+        );
+        // The version output takes precedence
+        assert_eq!(
+            probe_edition(AgentKind::Gemini, Some("0.3.0 (Pro)"), Some(home.path())),
+            Some("Pro".to_string())
+        );
+        assert_eq!(
+            probe_edition(AgentKind::Codex, Some("0.3.0"), Some(home.path())),
+            None
+        );
+    }
+}
@@ -0,0 +1,84 @@
+//! Reading and recognizing executable headers.
+//!
+//! The architecture and binary type probes both look at the first bytes
+//! of an agent executable. They share one read and one table of magic
+//! numbers here.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// Number of header bytes read from the executable.
+///
+/// Large enough to reach the PE header on typical Windows executables and
+/// to hold a script's `#!` line.
+const HEADER_LEN: usize = 1024;
+
+/// A native executable format, recognized by its magic number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum ExecutableFormat {
+    /// ELF (Linux and other Unixes).
+    Elf,
+    /// A single-architecture Mach-O binary (macOS), little-endian on disk.
+    MachO,
+    /// A fat (universal) Mach-O binary.
+    FatMachO,
+    /// A PE executable (Windows), starting with the DOS `MZ` stub.
+    Pe,
+}
+
+/// Read the first bytes of the executable at `path`.
+///
+/// Symlinks are followed. Returns `None` if the file can't be read.
+pub(crate) fn read_header(path: &Path) -> Option<Vec<u8>> {
+    let mut header = Vec::with_capacity(HEADER_LEN);
+    File::open(path)
+        .ok()?
+        .take(HEADER_LEN as u64)
+        .read_to_end(&mut header)
+        .ok()?;
+    Some(header)
+}
+
+/// The native executable format `header` starts with, if any.
+pub(super) fn executable_format(header: &[u8]) -> Option<ExecutableFormat> {
+    match header.get(..4)? {
+        [0x7f, b'E', b'L', b'F'] => Some(ExecutableFormat::Elf),
+        // 64-bit and 32-bit
+        [0xcf, 0xfa, 0xed, 0xfe] | [0xce, 0xfa, 0xed, 0xfe] => Some(ExecutableFormat::MachO),
+        [0xca, 0xfe, 0xba, 0xbe] => Some(ExecutableFormat::FatMachO),
+        [b'M', b'Z', ..] => Some(ExecutableFormat::Pe),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_executable_format() {
+        assert_eq!(
+            executable_format(&[0x7f, b'E', b'L', b'F', 2]),
+            Some(ExecutableFormat::Elf)
+        );
+        assert_eq!(
+            executable_format(&[0xce, 0xfa, 0xed, 0xfe]),
+            Some(ExecutableFormat::MachO)
+        );
+        assert_eq!(executable_format(b"MZ\x90\x00"), Some(ExecutableFormat::Pe));
+        assert_eq!(executable_format(b"#!/bin/sh\n"), None);
+        assert_eq!(executable_format(b"MZ"), None);
+    }
+
+    #[test]
+    fn test_read_header() {
+        let exe = std::env::current_exe().unwrap();
+        let header = read_header(&exe).unwrap();
+        assert_eq!(header.len(), HEADER_LEN);
+        assert!(executable_format(&header).is_some());
+
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(read_header(&dir.path().join("missing")), None);
+    }
+}
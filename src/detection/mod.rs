@@ -4,13 +4,60 @@
 //! AI coding agents on the system. It provides:
 //!
 //! - `find_executable`: PATH-based executable lookup with fallbacks
+//! - `go_bin_dirs`: Directories `go install` places executables in
+//! - `find_agent_executable`: Agent lookup honoring `RIG_ACP_<AGENT>_BIN` and `<AGENT>_PATH` overrides
+//! - `find_all_executables` / `find_all_executables_in` / `path_issues`: PATH diagnostics
+//! - `scan_locations`: Every copy of an executable, including package-manager bins
+//! - `find_off_path`: A copy of an executable in a location missing from PATH
+//! - `run_probe`: Run an agent executable with a timeout and optional minimal environment
 //! - `check_version`: Async version check with 2-second timeout
+//! - `read_version_file`: Version from a `VERSION`/`package.json` next to the binary
 //! - `parse_version`: Regex-based version extraction from CLI output
+//! - `probe_default_model`: Default model lookup from agent config files
+//! - `probe_config_schema_version`: Config schema version from agent config files
+//! - `probe_edition`: Edition (license tier) from `--version` output or config files
+//! - `read_header`: The first bytes of an executable, shared by the two probes below
+//! - `binary_arch`: Executable architecture from the binary header
+//! - `binary_type`: Native executable or script, from the binary header
+//! - `is_quarantined`: macOS Gatekeeper quarantine check
+//! - `probe_acp_capabilities` / `acp_handshake`: ACP capabilities from the `initialize` handshake
+//! - `probe_capabilities`: Self-reported capabilities from a `capabilities` subcommand
+//! - `find_winget_executable`: winget package lookup (Windows only)
 
+mod acp;
+mod arch;
+mod binary_type;
+mod capabilities;
+mod config;
+mod edition;
+mod header;
 mod parser;
 mod path_finder;
+mod probe;
+mod quarantine;
 mod version;
+mod version_file;
+#[cfg(windows)]
+mod winget;
 
-pub(crate) use parser::parse_version;
-pub(crate) use path_finder::find_executable;
+pub(crate) use acp::{acp_args, acp_handshake, probe_acp_capabilities};
+pub(crate) use arch::binary_arch;
+pub(crate) use binary_type::binary_type;
+pub use binary_type::BinaryType;
+pub(crate) use capabilities::probe_capabilities;
+pub use capabilities::Capabilities;
+pub(crate) use config::{probe_config_schema_version, probe_default_model};
+pub(crate) use edition::probe_edition;
+pub(crate) use header::read_header;
+pub(crate) use parser::{parse_major_version, parse_version};
+pub(crate) use path_finder::{
+    find_agent_executable, find_all_executables, find_all_executables_in, find_executable,
+    find_executable_in, find_off_path, go_bin_dirs, home_dir, path_issues, same_path,
+    scan_locations,
+};
+pub(crate) use probe::run_probe;
+pub(crate) use quarantine::is_quarantined;
 pub(crate) use version::check_version;
+pub(crate) use version_file::read_version_file;
+#[cfg(windows)]
+pub(crate) use winget::find_winget_executable;
@@ -15,6 +15,9 @@ use semver::Version;
 /// - `1.2` -> 1.2.0 (appends .0 for 2-part versions)
 /// - `v0.24.4` -> 0.24.4 (Gemini CLI format)
 ///
+/// Versions inside URLs or paths (e.g., `https://example.com/v2.1/docs`)
+/// are only used if no standalone version is found.
+///
 /// # Arguments
 ///
 /// * `output` - The CLI output text to parse
@@ -28,45 +31,50 @@ use semver::Version;
 /// Returns `None` if no version pattern matches or the matched string
 /// cannot be parsed as valid semver.
 pub(crate) fn parse_version(output: &str) -> Option<(Version, String)> {
-    // First try: 3-part version with optional 'v' prefix
     // Pattern: v?X.Y.Z where X, Y, Z are digits
     let re_3part = Regex::new(r"[vV]?(\d+)\.(\d+)\.(\d+)").expect("Invalid regex pattern");
+    // Pattern: v?X.Y where X, Y are digits
+    let re_2part = Regex::new(r"[vV]?(\d+)\.(\d+)").expect("Invalid regex pattern");
 
-    if let Some(caps) = re_3part.captures(output) {
-        let raw_match = caps.get(0).expect("Capture group 0 should exist").as_str();
-        // Strip 'v' or 'V' prefix for parsing
-        let version_str = raw_match.trim_start_matches(['v', 'V']);
+    // Prefer standalone version tokens over numbers inside URLs and paths
+    // (e.g., "https://docs.example.com/v2.1/" or "/usr/lib/python3.11/"),
+    // falling back to embedded matches if nothing else is found.
+    find_version(output, &re_3part, true)
+        .or_else(|| find_version(output, &re_2part, true))
+        .or_else(|| find_version(output, &re_3part, false))
+        .or_else(|| find_version(output, &re_2part, false))
+}
 
-        if let Ok(version) = Version::parse(version_str) {
-            return Some((version, raw_match.to_string()));
+/// Find the first version matching `re`, optionally skipping embedded matches.
+///
+/// Two-part matches (`X.Y`) are completed with a `.0` patch component.
+fn find_version(output: &str, re: &Regex, standalone_only: bool) -> Option<(Version, String)> {
+    for m in re.find_iter(output) {
+        if standalone_only && is_embedded(output, m.start(), m.end()) {
+            continue;
         }
-    }
-
-    // Second try: 2-part version with optional 'v' prefix
-    // Pattern: v?X.Y where X, Y are digits
-    // We use a simpler pattern and check manually that it's not part of a 3-part version
-    let re_2part = Regex::new(r"[vV]?(\d+)\.(\d+)").expect("Invalid regex pattern");
 
-    if let Some(caps) = re_2part.captures(output) {
-        let raw_match = caps.get(0).expect("Capture group 0 should exist").as_str();
-        let match_end = caps.get(0).expect("Capture group 0 should exist").end();
+        // Strip 'v' or 'V' prefix for parsing
+        let raw_match = m.as_str();
+        let version_str = raw_match.trim_start_matches(['v', 'V']);
+        let two_part = version_str.matches('.').count() == 1;
 
-        // Check if this is followed by another .digit (would be part of 3-part version)
-        // If so, skip this match as it was already handled above (or should have been)
-        let remaining = &output[match_end..];
-        if remaining.starts_with('.')
+        // A 2-part match followed by another .digit is part of a longer version
+        let remaining = &output[m.end()..];
+        if two_part
+            && remaining.starts_with('.')
             && remaining.chars().nth(1).is_some_and(|c| c.is_ascii_digit())
         {
-            // This is part of a 3-part version, but we didn't match it above
-            // This shouldn't happen normally, but handle it gracefully
-            return None;
+            continue;
         }
 
-        // Strip 'v' or 'V' prefix and append .0 for semver compatibility
-        let version_str = raw_match.trim_start_matches(['v', 'V']);
-        let version_str_with_patch = format!("{}.0", version_str);
-
-        if let Ok(version) = Version::parse(&version_str_with_patch) {
+        // Append .0 to 2-part versions for semver compatibility
+        let version = if two_part {
+            Version::parse(&format!("{}.0", version_str))
+        } else {
+            Version::parse(version_str)
+        };
+        if let Ok(version) = version {
             return Some((version, raw_match.to_string()));
         }
     }
@@ -74,6 +82,44 @@ pub(crate) fn parse_version(output: &str) -> Option<(Version, String)> {
     None
 }
 
+/// Check whether a match is part of a URL or filesystem path.
+///
+/// A match is embedded if it directly follows a `/`, or if the
+/// whitespace-delimited token containing it is a URL or absolute path.
+fn is_embedded(output: &str, start: usize, end: usize) -> bool {
+    let before = &output[..start];
+    if before.ends_with('/') {
+        return true;
+    }
+
+    let token_start = before
+        .rfind(|c: char| c.is_ascii_whitespace())
+        .map_or(0, |i| i + 1);
+    let token_end = output[end..]
+        .find(|c: char| c.is_ascii_whitespace())
+        .map_or(output.len(), |i| end + i);
+    let token = &output[token_start..token_end];
+    token.contains("://") || token.starts_with('/')
+}
+
+/// Parse a bare major version (e.g., `3` or `v3`) from CLI output.
+///
+/// This is a fallback for minimal tools that print only a major version,
+/// used when [`parse_version`] finds nothing and
+/// `DetectOptions::allow_major_only` is enabled. A bare number is
+/// ambiguous, so this is never tried by default.
+///
+/// - `version 3` -> 3.0.0 (raw `3`)
+/// - `v12` -> 12.0.0 (raw `v12`)
+pub(crate) fn parse_major_version(output: &str) -> Option<(Version, String)> {
+    let re_major = Regex::new(r"\b[vV]?(\d+)\b").expect("Invalid regex pattern");
+
+    let caps = re_major.captures(output)?;
+    let raw_match = caps.get(0).expect("Capture group 0 should exist").as_str();
+    let major = caps[1].parse().ok()?;
+    Some((Version::new(major, 0, 0), raw_match.to_string()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -174,4 +220,53 @@ mod tests {
         assert_eq!(version, Version::new(1, 2, 3));
         assert_eq!(raw, "1.2.3");
     }
+
+    #[test]
+    fn test_parse_version_major_only_not_matched() {
+        assert!(parse_version("version 3").is_none());
+    }
+
+    #[test]
+    fn test_parse_major_version() {
+        let (version, raw) = parse_major_version("version 3").unwrap();
+        assert_eq!(version, Version::new(3, 0, 0));
+        assert_eq!(raw, "3");
+
+        let (version, raw) = parse_major_version("tool v12\n").unwrap();
+        assert_eq!(version, Version::new(12, 0, 0));
+        assert_eq!(raw, "v12");
+
+        assert!(parse_major_version("no version here").is_none());
+    }
+
+    #[test]
+    fn test_parse_version_ignores_url_version() {
+        let output = "See https://docs.example.com/v2.1/install for help\nmytool 1.4.7";
+        let (version, raw) = parse_version(output).unwrap();
+        assert_eq!(version, Version::new(1, 4, 7));
+        assert_eq!(raw, "1.4.7");
+    }
+
+    #[test]
+    fn test_parse_version_ignores_path_version() {
+        let output = "loaded /usr/lib/python3.11/site-packages\nagent version 0.9";
+        let (version, raw) = parse_version(output).unwrap();
+        assert_eq!(version, Version::new(0, 9, 0));
+        assert_eq!(raw, "0.9");
+    }
+
+    #[test]
+    fn test_parse_version_four_part() {
+        let (version, raw) = parse_version("tool 1.2.3.4").unwrap();
+        assert_eq!(version, Version::new(1, 2, 3));
+        assert_eq!(raw, "1.2.3");
+    }
+
+    #[test]
+    fn test_parse_version_falls_back_to_embedded() {
+        // A slash-separated version is still used when it is the only one
+        let (version, raw) = parse_version("claude-code/2.1.12").unwrap();
+        assert_eq!(version, Version::new(2, 1, 12));
+        assert_eq!(raw, "2.1.12");
+    }
 }
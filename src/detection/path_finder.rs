@@ -1,6 +1,8 @@
 //! PATH-based executable lookup with fallback locations.
 
-use std::path::PathBuf;
+use crate::{AgentKind, Resolver};
+use std::ffi::{OsStr, OsString};
+use std::path::{Path, PathBuf};
 
 /// System fallback paths to check if executable not found in PATH (Linux/Unix).
 #[cfg(not(windows))]
@@ -11,6 +13,27 @@ const FALLBACK_PATHS: &[&str] = &["/usr/local/bin", "/usr/bin"];
 #[cfg(windows)]
 const FALLBACK_PATHS: &[&str] = &[];
 
+/// Get the current user's home directory.
+///
+/// Uses `USERPROFILE` on Windows and `HOME` elsewhere. If the variable is
+/// unset or empty (common in minimal containers and system services), the
+/// home directory is looked up from the OS account database instead
+/// (`getpwuid_r` on Unix, the Profile known folder on Windows).
+pub(crate) fn home_dir() -> Option<PathBuf> {
+    let var = if cfg!(windows) { "USERPROFILE" } else { "HOME" };
+    home_dir_with(std::env::var_os(var), dirs::home_dir)
+}
+
+fn home_dir_with(
+    var: Option<OsString>,
+    fallback: impl FnOnce() -> Option<PathBuf>,
+) -> Option<PathBuf> {
+    match var.filter(|value| !value.is_empty()) {
+        Some(value) => Some(PathBuf::from(value)),
+        None => fallback().filter(|dir| !dir.as_os_str().is_empty()),
+    }
+}
+
 /// Get home directory paths to check for an executable.
 ///
 /// Returns platform-specific paths where user-installed tools are commonly found.
@@ -18,8 +41,9 @@ fn get_home_paths(name: &str) -> Vec<PathBuf> {
     let mut paths = Vec::new();
 
     if cfg!(windows) {
-        // Windows: use USERPROFILE for native installs
-        if let Ok(userprofile) = std::env::var("USERPROFILE") {
+        // Windows: use the user profile for native installs
+        if let Some(userprofile) = home_dir() {
+            let userprofile = userprofile.display();
             // With .exe extension
             paths.push(PathBuf::from(format!(
                 r"{}\.local\bin\{}.exe",
@@ -37,17 +61,106 @@ fn get_home_paths(name: &str) -> Vec<PathBuf> {
             // npm creates .cmd shims
             paths.push(PathBuf::from(format!(r"{}\npm\{}.cmd", appdata, name)));
         }
+
+        // Windows: winget links portable packages into LOCALAPPDATA
+        if let Ok(local_app_data) = std::env::var("LOCALAPPDATA") {
+            paths.push(PathBuf::from(format!(
+                r"{}\Microsoft\WinGet\Links\{}.exe",
+                local_app_data, name
+            )));
+        }
     } else {
-        // Unix: use HOME
-        if let Ok(home) = std::env::var("HOME") {
-            paths.push(PathBuf::from(format!("{}/.local/bin/{}", home, name)));
-            paths.push(PathBuf::from(format!("{}/bin/{}", home, name)));
+        // Unix: use HOME and XDG conventions
+        paths.extend(unix_home_paths(
+            name,
+            home_dir(),
+            std::env::var_os("XDG_DATA_HOME").map(PathBuf::from),
+        ));
+        for dir in go_bin_dirs() {
+            let path = dir.join(name);
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
         }
     }
 
     paths
 }
 
+/// Directories `go install` places executables in.
+///
+/// `$GOBIN` if set, otherwise `bin` under each `$GOPATH` entry, followed
+/// by `~/go/bin` (the default when `GOPATH` is unset) if not already listed.
+pub(crate) fn go_bin_dirs() -> Vec<PathBuf> {
+    go_bin_dirs_with(
+        home_dir(),
+        std::env::var_os("GOBIN"),
+        std::env::var_os("GOPATH"),
+    )
+}
+
+fn go_bin_dirs_with(
+    home: Option<PathBuf>,
+    gobin: Option<OsString>,
+    gopath: Option<OsString>,
+) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = match gobin.filter(|v| !v.is_empty()) {
+        Some(gobin) => vec![PathBuf::from(gobin)],
+        None => gopath
+            .map(|gopath| {
+                std::env::split_paths(&gopath)
+                    .filter(|dir| !dir.as_os_str().is_empty())
+                    .map(|dir| dir.join("bin"))
+                    .collect()
+            })
+            .unwrap_or_default(),
+    };
+
+    if let Some(default) = home.map(|home| home.join("go").join("bin")) {
+        if !dirs.contains(&default) {
+            dirs.push(default);
+        }
+    }
+
+    dirs.retain(|dir| dir.is_absolute());
+    dirs
+}
+
+/// Unix user-level locations for an executable.
+///
+/// Covers `~/.local/bin` and `~/bin`, the bin directory next to
+/// `$XDG_DATA_HOME` (the XDG equivalent of `~/.local/bin`), npm's common
+/// user prefix `~/.npm-global/bin`, and cargo's `~/.cargo/bin`.
+fn unix_home_paths(
+    name: &str,
+    home: Option<PathBuf>,
+    xdg_data_home: Option<PathBuf>,
+) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+
+    if let Some(home) = &home {
+        paths.push(home.join(".local/bin").join(name));
+        paths.push(home.join("bin").join(name));
+    }
+
+    // $XDG_DATA_HOME is typically ~/.local/share; its sibling bin is ~/.local/bin
+    if let Some(bin) = xdg_data_home
+        .filter(|dir| dir.is_absolute())
+        .and_then(|dir| dir.parent().map(|parent| parent.join("bin").join(name)))
+    {
+        if !paths.contains(&bin) {
+            paths.push(bin);
+        }
+    }
+
+    if let Some(home) = &home {
+        paths.push(home.join(".npm-global/bin").join(name));
+        paths.push(home.join(".cargo/bin").join(name));
+    }
+
+    paths
+}
+
 /// Find an executable by name.
 ///
 /// This function first tries to find the executable using the system PATH
@@ -62,10 +175,25 @@ fn get_home_paths(name: &str) -> Vec<PathBuf> {
 ///
 /// `Some(PathBuf)` if the executable is found, `None` otherwise.
 pub(crate) fn find_executable(name: &str) -> Option<PathBuf> {
+    find_executable_in(name, None)
+}
+
+/// [`find_executable`], searching `path_env` instead of the process PATH if set.
+///
+/// `path_env` is a PATH-style list of directories. Passing a snapshot
+/// keeps lookups consistent even if the process PATH changes meanwhile.
+pub(crate) fn find_executable_in(name: &str, path_env: Option<&OsStr>) -> Option<PathBuf> {
     // Primary: PATH lookup via which crate
     // This handles symlinks, relative paths, and platform differences
     // On Windows, which crate automatically handles PATHEXT (.exe, .cmd, etc.)
-    if let Ok(path) = which::which(name) {
+    let found = match path_env {
+        Some(path_env) => {
+            let cwd = std::env::current_dir().unwrap_or_default();
+            which::which_in(name, Some(path_env), cwd)
+        }
+        None => which::which(name),
+    };
+    if let Ok(path) = found {
         return Some(path);
     }
 
@@ -81,9 +209,267 @@ pub(crate) fn find_executable(name: &str) -> Option<PathBuf> {
     get_home_paths(name).into_iter().find(|path| path.exists())
 }
 
+/// Find an agent's executable, honoring its override variables.
+///
+/// The variable named by [`AgentKind::bin_env_var`] (e.g.,
+/// `RIG_ACP_CLAUDE_BIN`) is checked first: a path to an existing file is
+/// used as is, and a bare name is looked up with `resolver` instead of the
+/// default executable name. Next, the variable named by
+/// [`AgentKind::path_env_var`] (e.g., `CLAUDE_PATH`) is used if it points
+/// to an existing file. Otherwise the executable is looked up with
+/// `resolver`, in `path_env` if set.
+pub(crate) fn find_agent_executable(
+    kind: AgentKind,
+    resolver: &dyn Resolver,
+    path_env: Option<&OsStr>,
+) -> Option<PathBuf> {
+    find_agent_executable_with(
+        kind,
+        std::env::var_os(kind.bin_env_var()),
+        std::env::var_os(kind.path_env_var()),
+        resolver,
+        path_env,
+    )
+}
+
+fn find_agent_executable_with(
+    kind: AgentKind,
+    bin_var: Option<OsString>,
+    override_var: Option<OsString>,
+    resolver: &dyn Resolver,
+    path_env: Option<&OsStr>,
+) -> Option<PathBuf> {
+    let resolve = |name: &str| match path_env {
+        Some(path_env) => resolver.resolve_in(name, path_env),
+        None => resolver.resolve(name),
+    };
+
+    if let Some(value) = bin_var.filter(|v| !v.is_empty()) {
+        let path = PathBuf::from(&value);
+        if path.is_file() {
+            return Some(path);
+        }
+        let bare_name = path.components().count() == 1 && !path.is_absolute();
+        if let Some(found) = value.to_str().filter(|_| bare_name).and_then(resolve) {
+            return Some(found);
+        }
+        tracing::warn!(
+            "{} is set to {}, which was not found; ignoring it",
+            kind.bin_env_var(),
+            path.display()
+        );
+    }
+
+    if let Some(value) = override_var.filter(|v| !v.is_empty()) {
+        let path = PathBuf::from(value);
+        if path.is_file() {
+            return Some(path);
+        }
+        tracing::warn!(
+            "{} is set to {}, which is not a file; searching PATH instead",
+            kind.path_env_var(),
+            path.display()
+        );
+    }
+
+    resolve(kind.executable_name())
+}
+
+/// Find every location of an executable by name.
+///
+/// Unlike [`find_executable`], which returns the first match, this walks
+/// every PATH entry followed by the fallback and home locations and returns
+/// all distinct matches in search order. Entries that resolve to the same
+/// file (e.g., via symlinked directories like `/bin` -> `/usr/bin`) are
+/// reported once.
+pub(crate) fn find_all_executables(name: &str) -> Vec<PathBuf> {
+    find_all_executables_in(name, None)
+}
+
+/// [`find_all_executables`], walking `path_env` instead of the process PATH if set.
+pub(crate) fn find_all_executables_in(name: &str, path_env: Option<&OsStr>) -> Vec<PathBuf> {
+    let path_env = path_env
+        .map(OsStr::to_os_string)
+        .or_else(|| std::env::var_os("PATH"));
+    let mut dirs: Vec<PathBuf> = path_env
+        .map(|path| std::env::split_paths(&path).collect())
+        .unwrap_or_default();
+    dirs.extend(FALLBACK_PATHS.iter().map(PathBuf::from));
+
+    let mut candidates = candidates_in(name, &dirs);
+    candidates.extend(get_home_paths(name));
+    distinct_files(candidates)
+}
+
+/// Find every copy of an executable in all known install locations.
+///
+/// This extends [`find_all_executables`] with package-manager bin
+/// directories that are usually not on PATH (Homebrew, nvm, Volta, Bun,
+/// pnpm, Scoop and agent-specific installer directories), for auditing
+/// every install regardless of which one PATH would pick.
+pub(crate) fn scan_locations(name: &str) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = std::env::var_os("PATH")
+        .map(|path| std::env::split_paths(&path).collect())
+        .unwrap_or_default();
+    dirs.extend(FALLBACK_PATHS.iter().map(PathBuf::from));
+    dirs.extend(package_manager_dirs(
+        home_dir(),
+        std::env::var_os("NVM_DIR").map(PathBuf::from),
+    ));
+
+    let mut candidates = candidates_in(name, &dirs);
+    candidates.extend(get_home_paths(name));
+    distinct_files(candidates)
+}
+
+/// Find a copy of an executable in a known install location that isn't on PATH.
+///
+/// Used after a failed install verification to tell "installed somewhere
+/// the shell can't see" apart from "not installed at all".
+pub(crate) fn find_off_path(name: &str) -> Option<PathBuf> {
+    off_path_copy(scan_locations(name), std::env::var_os("PATH"))
+}
+
+/// The first candidate whose directory is not an entry of `path_var`.
+fn off_path_copy(candidates: Vec<PathBuf>, path_var: Option<OsString>) -> Option<PathBuf> {
+    let entries: Vec<PathBuf> = path_var
+        .map(|path| std::env::split_paths(&path).collect())
+        .unwrap_or_default();
+
+    candidates.into_iter().find(|candidate| {
+        candidate
+            .parent()
+            .is_some_and(|dir| !entries.iter().any(|entry| same_path(entry, dir)))
+    })
+}
+
+/// Package-manager bin directories that may hold agent executables.
+///
+/// Includes every installed nvm Node.js version's bin directory, since
+/// npm global installs are per Node.js version under nvm.
+fn package_manager_dirs(home: Option<PathBuf>, nvm_dir: Option<PathBuf>) -> Vec<PathBuf> {
+    let mut dirs = vec![
+        PathBuf::from("/opt/homebrew/bin"),
+        PathBuf::from("/home/linuxbrew/.linuxbrew/bin"),
+    ];
+
+    if let Some(home) = &home {
+        dirs.extend(
+            [
+                ".volta/bin",
+                ".bun/bin",
+                ".local/share/pnpm",
+                "scoop/shims",
+                ".opencode/bin",
+                ".claude/local",
+            ]
+            .iter()
+            .map(|dir| home.join(dir)),
+        );
+    }
+
+    if let Some(nvm_dir) = nvm_dir.or_else(|| home.map(|home| home.join(".nvm"))) {
+        if let Ok(entries) = std::fs::read_dir(nvm_dir.join("versions").join("node")) {
+            let mut versions: Vec<PathBuf> = entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path().join("bin"))
+                .collect();
+            versions.sort();
+            dirs.extend(versions);
+        }
+    }
+
+    dirs
+}
+
+/// Every platform-specific file name for `name` in each of `dirs`.
+fn candidates_in(name: &str, dirs: &[PathBuf]) -> Vec<PathBuf> {
+    dirs.iter()
+        .flat_map(|dir| candidate_names(name).map(move |n| dir.join(n)))
+        .collect()
+}
+
+/// The existing files among `candidates`, reporting each file once.
+///
+/// Entries that resolve to the same file (e.g., via symlinked directories
+/// like `/bin` -> `/usr/bin`) are reported once, in first-seen order.
+fn distinct_files(candidates: Vec<PathBuf>) -> Vec<PathBuf> {
+    let mut seen = Vec::new();
+    let mut found = Vec::new();
+    for path in candidates {
+        if !path.is_file() {
+            continue;
+        }
+        let canonical = path.canonicalize().unwrap_or_else(|_| path.clone());
+        if !seen.contains(&canonical) {
+            seen.push(canonical);
+            found.push(path);
+        }
+    }
+    found
+}
+
+/// File names an executable may have on this platform.
+fn candidate_names(name: &str) -> impl Iterator<Item = String> + '_ {
+    let extensions: &[&str] = if cfg!(windows) {
+        &[".exe", ".cmd", ".bat", ""]
+    } else {
+        &[""]
+    };
+    extensions.iter().map(move |ext| format!("{}{}", name, ext))
+}
+
+/// Find problems with the PATH that commonly break agent detection.
+///
+/// Reports PATH entries that don't exist, and the user-local bin directory
+/// (where native installers place agents) when it exists but isn't on PATH.
+pub(crate) fn path_issues() -> Vec<String> {
+    path_issues_in(std::env::var_os("PATH"), home_dir())
+}
+
+/// [`path_issues`] for an explicit PATH value and home directory.
+fn path_issues_in(path_var: Option<OsString>, home: Option<PathBuf>) -> Vec<String> {
+    let mut issues = Vec::new();
+
+    let entries: Vec<PathBuf> = match path_var {
+        Some(path) => std::env::split_paths(&path).collect(),
+        None => {
+            issues.push("PATH is not set".to_string());
+            Vec::new()
+        }
+    };
+
+    for entry in &entries {
+        if !entry.as_os_str().is_empty() && !entry.exists() {
+            issues.push(format!("PATH entry does not exist: {}", entry.display()));
+        }
+    }
+
+    if let Some(home) = home {
+        let local_bin = home.join(".local").join("bin");
+        if local_bin.is_dir() && !entries.iter().any(|e| same_path(e, &local_bin)) {
+            issues.push(format!(
+                "{} exists but is not on PATH; agents installed there will not be found by other tools",
+                local_bin.display()
+            ));
+        }
+    }
+
+    issues
+}
+
+/// Compare two paths, resolving symlinks where possible.
+pub(crate) fn same_path(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::PathResolver;
 
     #[test]
     #[cfg(not(windows))]
@@ -137,6 +523,9 @@ mod tests {
         assert!(path_strs.iter().any(|p| p.contains(".exe")));
         assert!(path_strs.iter().any(|p| p.contains(r"\npm\")));
         assert!(path_strs.iter().any(|p| p.contains(".cmd")));
+        if std::env::var("LOCALAPPDATA").is_ok() {
+            assert!(path_strs.iter().any(|p| p.contains(r"\WinGet\Links\")));
+        }
 
         // Restore env vars
         std::env::remove_var("USERPROFILE");
@@ -162,6 +551,106 @@ mod tests {
         // Restore env var (or leave as-is since HOME is typically set)
     }
 
+    #[test]
+    #[cfg(not(windows))]
+    fn test_find_all_executables_finds_common_tool() {
+        let all = find_all_executables("ls");
+        assert!(!all.is_empty());
+        // The first match should agree with find_executable's choice
+        assert_eq!(
+            all[0].canonicalize().unwrap(),
+            find_executable("ls").unwrap().canonicalize().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_scan_finds_binaries_across_dirs() {
+        let home = tempfile::tempdir().unwrap();
+        let other = tempfile::tempdir().unwrap();
+        let volta = home.path().join(".volta/bin");
+        let nvm_18 = home.path().join(".nvm/versions/node/v18.20.0/bin");
+        let nvm_20 = home.path().join(".nvm/versions/node/v20.11.0/bin");
+        for dir in [&volta, &nvm_18, &nvm_20] {
+            std::fs::create_dir_all(dir).unwrap();
+        }
+        for dir in [&volta, &nvm_20, &other.path().to_path_buf()] {
+            std::fs::write(dir.join("gemini"), "fake").unwrap();
+        }
+        // A directory listed twice is reported once
+        let mut dirs = vec![other.path().to_path_buf(), other.path().to_path_buf()];
+        dirs.extend(package_manager_dirs(Some(home.path().to_path_buf()), None));
+
+        let found = distinct_files(candidates_in("gemini", &dirs));
+        assert_eq!(
+            found,
+            [
+                other.path().join("gemini"),
+                volta.join("gemini"),
+                nvm_20.join("gemini")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_package_manager_dirs_uses_nvm_dir() {
+        let nvm = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(nvm.path().join("versions/node/v22.0.0")).unwrap();
+        let dirs = package_manager_dirs(None, Some(nvm.path().to_path_buf()));
+        assert!(dirs.contains(&nvm.path().join("versions/node/v22.0.0/bin")));
+    }
+
+    #[test]
+    fn test_find_all_executables_nonexistent() {
+        assert!(find_all_executables("definitely_not_a_real_executable_12345").is_empty());
+    }
+
+    #[test]
+    fn test_path_issues_reports_missing_entries() {
+        let existing = tempfile::tempdir().unwrap();
+        let missing = existing.path().join("does-not-exist");
+        let path_var = std::env::join_paths([existing.path(), missing.as_path()]).unwrap();
+
+        let issues = path_issues_in(Some(path_var), None);
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].contains("does-not-exist"));
+    }
+
+    #[test]
+    fn test_path_issues_reports_local_bin_off_path() {
+        let home = tempfile::tempdir().unwrap();
+        let local_bin = home.path().join(".local").join("bin");
+        std::fs::create_dir_all(&local_bin).unwrap();
+
+        let off_path = path_issues_in(Some(OsString::new()), Some(home.path().to_path_buf()));
+        assert!(off_path.iter().any(|issue| issue.contains("not on PATH")));
+
+        let path_var = std::env::join_paths([&local_bin]).unwrap();
+        let on_path = path_issues_in(Some(path_var), Some(home.path().to_path_buf()));
+        assert!(on_path.is_empty());
+    }
+
+    #[test]
+    fn test_off_path_copy() {
+        let on_path = tempfile::tempdir().unwrap();
+        let off_path = tempfile::tempdir().unwrap();
+        let visible = on_path.path().join("claude");
+        let hidden = off_path.path().join("claude");
+        let path_var = std::env::join_paths([on_path.path()]).unwrap();
+
+        let found = off_path_copy(
+            vec![visible.clone(), hidden.clone()],
+            Some(path_var.clone()),
+        );
+        assert_eq!(found, Some(hidden));
+        assert_eq!(off_path_copy(vec![visible], Some(path_var)), None);
+    }
+
+    #[test]
+    fn test_path_issues_unset_path() {
+        let issues = path_issues_in(None, None);
+        assert_eq!(issues, vec!["PATH is not set".to_string()]);
+    }
+
     #[test]
     #[cfg(not(windows))]
     fn test_fallback_paths_unix() {
@@ -178,4 +667,211 @@ mod tests {
         // (PATH + npm location suffice)
         assert!(FALLBACK_PATHS.is_empty());
     }
+
+    #[test]
+    fn test_path_override_used_before_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let fake = dir.path().join("my-claude");
+        std::fs::write(&fake, "fake").unwrap();
+
+        let found = find_agent_executable_with(
+            AgentKind::ClaudeCode,
+            None,
+            Some(fake.clone().into_os_string()),
+            &PathResolver,
+            None,
+        );
+        assert_eq!(found, Some(fake));
+    }
+
+    #[test]
+    fn test_path_override_ignored_when_missing() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("not-there");
+
+        // Falls back to the normal search, which cannot find the missing file
+        let found = find_agent_executable_with(
+            AgentKind::Codex,
+            None,
+            Some(missing.clone().into()),
+            &PathResolver,
+            None,
+        );
+        assert_ne!(found, Some(missing));
+        assert_eq!(
+            find_agent_executable_with(
+                AgentKind::Codex,
+                None,
+                Some(OsString::new()),
+                &PathResolver,
+                None
+            ),
+            find_executable("codex")
+        );
+    }
+
+    #[test]
+    fn test_bin_override_used_before_path_override() {
+        let dir = tempfile::tempdir().unwrap();
+        let wrapper = dir.path().join("claude-wrapper");
+        let path_override = dir.path().join("claude");
+        std::fs::write(&wrapper, "fake").unwrap();
+        std::fs::write(&path_override, "fake").unwrap();
+
+        let found = find_agent_executable_with(
+            AgentKind::ClaudeCode,
+            Some(wrapper.clone().into_os_string()),
+            Some(path_override.clone().into_os_string()),
+            &PathResolver,
+            None,
+        );
+        assert_eq!(found, Some(wrapper));
+
+        // A missing binary falls through to the path override
+        let found = find_agent_executable_with(
+            AgentKind::ClaudeCode,
+            Some(dir.path().join("missing").into_os_string()),
+            Some(path_override.clone().into_os_string()),
+            &PathResolver,
+            None,
+        );
+        assert_eq!(found, Some(path_override));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_bin_override_wins_over_path_lookup() {
+        let on_path = tempfile::tempdir().unwrap();
+        crate::test_util::fake_executable(on_path.path(), "gemini", "echo 1.0.0");
+        let dir = tempfile::tempdir().unwrap();
+        let wrapper =
+            crate::test_util::fake_executable(dir.path(), "gemini-wrapper", "echo 77.1.0");
+
+        let found = find_agent_executable_with(
+            AgentKind::Gemini,
+            Some(wrapper.clone().into_os_string()),
+            None,
+            &PathResolver,
+            Some(on_path.path().as_os_str()),
+        );
+        assert_eq!(found, Some(wrapper));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_bin_override_bare_name_is_looked_up() {
+        let dir = tempfile::tempdir().unwrap();
+        let wrapper = crate::test_util::fake_executable(dir.path(), "claude-code", "echo 1.0.0");
+
+        let found = find_agent_executable_with(
+            AgentKind::ClaudeCode,
+            Some(OsString::from("claude-code")),
+            None,
+            &PathResolver,
+            Some(dir.path().as_os_str()),
+        );
+        assert_eq!(found, Some(wrapper));
+    }
+
+    #[test]
+    fn test_home_dir_falls_back_when_unset() {
+        let fallback = || Some(PathBuf::from("/home/fromdb"));
+
+        assert_eq!(
+            home_dir_with(Some(OsString::from("/home/env")), fallback),
+            Some(PathBuf::from("/home/env"))
+        );
+        assert_eq!(
+            home_dir_with(None, fallback),
+            Some(PathBuf::from("/home/fromdb"))
+        );
+        assert_eq!(
+            home_dir_with(Some(OsString::new()), fallback),
+            Some(PathBuf::from("/home/fromdb"))
+        );
+        assert_eq!(home_dir_with(None, || None), None);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_unix_home_paths_without_home() {
+        // No home directory at all yields no candidates rather than panicking
+        assert!(unix_home_paths("claude", None, None).is_empty());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_unix_home_paths_include_user_prefixes() {
+        let paths = unix_home_paths("codex", Some(PathBuf::from("/home/u")), None);
+        assert!(paths.contains(&PathBuf::from("/home/u/.local/bin/codex")));
+        assert!(paths.contains(&PathBuf::from("/home/u/.npm-global/bin/codex")));
+        assert!(paths.contains(&PathBuf::from("/home/u/.cargo/bin/codex")));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_go_bin_dirs() {
+        let home = Some(PathBuf::from("/home/u"));
+        assert_eq!(
+            go_bin_dirs_with(home.clone(), None, None),
+            [PathBuf::from("/home/u/go/bin")]
+        );
+        assert_eq!(
+            go_bin_dirs_with(
+                home.clone(),
+                None,
+                Some(OsString::from("/src/go:/home/u/go"))
+            ),
+            [
+                PathBuf::from("/src/go/bin"),
+                PathBuf::from("/home/u/go/bin")
+            ]
+        );
+        // GOBIN replaces the GOPATH bin directories
+        assert_eq!(
+            go_bin_dirs_with(
+                home,
+                Some(OsString::from("/opt/gobin")),
+                Some(OsString::from("/src/go"))
+            ),
+            [PathBuf::from("/opt/gobin"), PathBuf::from("/home/u/go/bin")]
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_finds_fake_binary_in_home_go_bin() {
+        let home = tempfile::tempdir().unwrap();
+        let go_bin = home.path().join("go/bin");
+        std::fs::create_dir_all(&go_bin).unwrap();
+        std::fs::write(go_bin.join("opencode"), "fake").unwrap();
+
+        let dirs = go_bin_dirs_with(Some(home.path().to_path_buf()), None, None);
+        let found = distinct_files(candidates_in("opencode", &dirs));
+        assert_eq!(found, [go_bin.join("opencode")]);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_unix_home_paths_xdg_data_home() {
+        let paths = unix_home_paths(
+            "claude",
+            Some(PathBuf::from("/home/u")),
+            Some(PathBuf::from("/data/xdg/share")),
+        );
+        assert!(paths.contains(&PathBuf::from("/data/xdg/bin/claude")));
+
+        // The default XDG location is not listed twice
+        let paths = unix_home_paths(
+            "claude",
+            Some(PathBuf::from("/home/u")),
+            Some(PathBuf::from("/home/u/.local/share")),
+        );
+        let local_bin = PathBuf::from("/home/u/.local/bin/claude");
+        assert_eq!(paths.iter().filter(|p| **p == local_bin).count(), 1);
+
+        // Relative values are invalid per the XDG spec and ignored
+        let paths = unix_home_paths("claude", None, Some(PathBuf::from("share")));
+        assert!(paths.is_empty());
+    }
 }
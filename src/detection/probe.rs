@@ -0,0 +1,76 @@
+//! Running an agent executable to probe it.
+
+use crate::DetectionError;
+use std::path::Path;
+use std::process::{Output, Stdio};
+use std::time::Duration;
+use tokio::process::Command;
+use tokio::time::timeout;
+
+/// Environment variables kept when running a probe with a minimal
+/// environment.
+///
+/// These are what executables (including Node.js shims) need to start:
+/// the search path, the user's home and temp directories, and locale.
+/// Windows additionally needs its system directories for DLL loading.
+pub(super) const MINIMAL_ENV_VARS: &[&str] = &[
+    "PATH",
+    "HOME",
+    "USER",
+    "LANG",
+    "TMPDIR",
+    "SYSTEMROOT",
+    "WINDIR",
+    "USERPROFILE",
+    "APPDATA",
+    "LOCALAPPDATA",
+    "PATHEXT",
+    "COMSPEC",
+    "TEMP",
+    "TMP",
+];
+
+/// Run the executable at `path` with `args` and capture its output.
+///
+/// stdin is closed, and the process is killed if it runs longer than
+/// `timeout_duration` or the future is dropped. With `minimal_env`, the
+/// child environment is cleared except for [`MINIMAL_ENV_VARS`].
+///
+/// The exit status is not checked; callers decide what a failure means.
+///
+/// # Errors
+///
+/// - `Timeout` if the command takes longer than `timeout_duration`
+/// - `PermissionDenied` if the executable cannot be run due to permissions
+/// - `IoError` if it could not be started for another reason
+pub(crate) async fn run_probe(
+    path: &Path,
+    args: &[&str],
+    timeout_duration: Duration,
+    minimal_env: bool,
+) -> Result<Output, DetectionError> {
+    let mut cmd = Command::new(path);
+    cmd.args(args).stdin(Stdio::null()).kill_on_drop(true);
+    if minimal_env {
+        cmd.env_clear();
+        for (key, value) in std::env::vars_os() {
+            if key
+                .to_str()
+                .is_some_and(|key| MINIMAL_ENV_VARS.iter().any(|v| v.eq_ignore_ascii_case(key)))
+            {
+                cmd.env(key, value);
+            }
+        }
+    }
+
+    timeout(timeout_duration, cmd.output())
+        .await
+        .map_err(|_| DetectionError::Timeout)?
+        .map_err(|e| {
+            if e.kind() == std::io::ErrorKind::PermissionDenied {
+                DetectionError::PermissionDenied
+            } else {
+                DetectionError::IoError
+            }
+        })
+}
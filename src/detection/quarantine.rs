@@ -0,0 +1,73 @@
+//! macOS Gatekeeper quarantine detection.
+
+use std::path::Path;
+
+/// Extended attribute Gatekeeper sets on downloaded files.
+#[cfg(target_os = "macos")]
+const QUARANTINE_XATTR: &str = "com.apple.quarantine";
+
+/// Check whether a file carries the `com.apple.quarantine` attribute.
+///
+/// Gatekeeper refuses to run quarantined binaries that are not notarized,
+/// which surfaces as an opaque I/O error from the version check.
+#[cfg(target_os = "macos")]
+pub(crate) fn is_quarantined(path: &Path) -> bool {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let Ok(c_path) = CString::new(path.as_os_str().as_bytes()) else {
+        return false;
+    };
+    let name = CString::new(QUARANTINE_XATTR).expect("Invalid xattr name");
+
+    // A null buffer queries the attribute size; -1 means it is absent
+    // SAFETY: both strings are valid and NUL-terminated, and a null value
+    // buffer with size 0 is explicitly allowed by getxattr(2).
+    let size = unsafe {
+        libc::getxattr(
+            c_path.as_ptr(),
+            name.as_ptr(),
+            std::ptr::null_mut(),
+            0,
+            0,
+            0,
+        )
+    };
+    size >= 0
+}
+
+/// Quarantine is a macOS-only mechanism.
+#[cfg(not(target_os = "macos"))]
+pub(crate) fn is_quarantined(_path: &Path) -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_file_not_quarantined() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("claude");
+        std::fs::write(&path, "fake").unwrap();
+        assert!(!is_quarantined(&path));
+        assert!(!is_quarantined(&dir.path().join("missing")));
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn test_quarantined_file_detected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("claude");
+        std::fs::write(&path, "fake").unwrap();
+
+        let status = std::process::Command::new("xattr")
+            .args(["-w", QUARANTINE_XATTR, "0081;00000000;Safari;"])
+            .arg(&path)
+            .status()
+            .unwrap();
+        assert!(status.success());
+        assert!(is_quarantined(&path));
+    }
+}
@@ -1,10 +1,9 @@
 //! Async version check with timeout.
 
+use crate::detection::{parse_version, run_probe};
 use crate::DetectionError;
 use std::path::Path;
 use std::time::Duration;
-use tokio::process::Command;
-use tokio::time::timeout;
 
 /// Check the version of an executable.
 ///
@@ -17,6 +16,9 @@ use tokio::time::timeout;
 ///
 /// * `path` - Path to the executable to check
 /// * `timeout_duration` - Maximum time to wait for the command to complete
+/// * `minimal_env` - Run with a minimal environment (see
+///   [`run_probe`]), so agent-specific configuration variables are not
+///   seen by the version check
 ///
 /// # Returns
 ///
@@ -26,23 +28,56 @@ use tokio::time::timeout;
 /// - `PermissionDenied` if the executable cannot be run due to permissions
 /// - `IoError` for other I/O failures or non-zero exit codes
 /// - `VersionParseFailed` if output is not valid UTF-8
+///
+/// # Version-manager shims
+///
+/// Some version-manager shims (in a `shims` directory, as used by asdf,
+/// mise and proto) consume flags themselves and only forward arguments
+/// after a `--` separator. For an executable in a `shims` directory, if
+/// `--version` fails or prints no version, the check is retried as
+/// `-- --version`. Timeouts are not retried.
 pub(crate) async fn check_version(
     path: &Path,
     timeout_duration: Duration,
+    minimal_env: bool,
+) -> Result<String, DetectionError> {
+    let result = run_version(path, &["--version"], timeout_duration, minimal_env).await;
+
+    let has_version = result
+        .as_ref()
+        .is_ok_and(|output| parse_version(output).is_some());
+    if !has_version
+        && !matches!(result, Err(DetectionError::Timeout))
+        && is_version_manager_shim(path)
+    {
+        let separated =
+            run_version(path, &["--", "--version"], timeout_duration, minimal_env).await;
+        if separated
+            .as_ref()
+            .is_ok_and(|output| parse_version(output).is_some())
+        {
+            return separated;
+        }
+    }
+
+    result
+}
+
+/// Whether `path` is a version-manager shim (lives in a `shims` directory).
+fn is_version_manager_shim(path: &Path) -> bool {
+    path.parent()
+        .and_then(Path::file_name)
+        .is_some_and(|dir| dir == "shims")
+}
+
+/// Run the executable with `args` and capture its output.
+async fn run_version(
+    path: &Path,
+    args: &[&str],
+    timeout_duration: Duration,
+    minimal_env: bool,
 ) -> Result<String, DetectionError> {
-    let mut cmd = Command::new(path);
-    cmd.arg("--version").kill_on_drop(true);
-
-    let output = timeout(timeout_duration, cmd.output())
-        .await
-        .map_err(|_| DetectionError::Timeout)?
-        .map_err(|e| {
-            if e.kind() == std::io::ErrorKind::PermissionDenied {
-                DetectionError::PermissionDenied
-            } else {
-                DetectionError::IoError
-            }
-        })?;
+    let output = run_probe(path, args, timeout_duration, minimal_env).await?;
 
     if !output.status.success() {
         return Err(DetectionError::IoError);
@@ -71,7 +106,7 @@ mod tests {
         // ls --version should work on Linux
         let path = PathBuf::from("/bin/ls");
         if path.exists() {
-            let result = check_version(&path, TEST_TIMEOUT).await;
+            let result = check_version(&path, TEST_TIMEOUT, false).await;
             // Should succeed or fail gracefully (ls --version behavior varies)
             // On some systems ls might not have --version
             assert!(result.is_ok() || matches!(result, Err(DetectionError::IoError)));
@@ -81,7 +116,7 @@ mod tests {
     #[tokio::test]
     async fn test_check_version_nonexistent() {
         let path = PathBuf::from("/nonexistent/path/to/executable");
-        let result = check_version(&path, TEST_TIMEOUT).await;
+        let result = check_version(&path, TEST_TIMEOUT, false).await;
         assert!(matches!(result, Err(DetectionError::IoError)));
     }
 
@@ -89,8 +124,66 @@ mod tests {
     async fn test_check_version_with_custom_timeout() {
         // Test that a very short timeout still works (though may timeout)
         let path = PathBuf::from("/nonexistent/path/to/executable");
-        let result = check_version(&path, Duration::from_millis(100)).await;
+        let result = check_version(&path, Duration::from_millis(100), false).await;
         // Should fail with IoError (not timeout, since executable doesn't exist)
         assert!(matches!(result, Err(DetectionError::IoError)));
     }
+
+    /// A shim that only forwards arguments after `--` to the real binary.
+    #[cfg(unix)]
+    const SEPARATOR_SHIM: &str = r#"if [ "$1" != "--" ]; then
+  echo "shim: unknown option $1" >&2
+  exit 2
+fi
+shift
+[ "$1" = "--version" ] && echo "codex-cli 0.87.0""#;
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_check_version_shim_separator() {
+        let dir = tempfile::tempdir().unwrap();
+        let shims = dir.path().join("shims");
+        std::fs::create_dir(&shims).unwrap();
+        let path = crate::test_util::fake_executable(&shims, "codex", SEPARATOR_SHIM);
+
+        let output = check_version(&path, TEST_TIMEOUT, false).await.unwrap();
+        assert_eq!(output.trim(), "codex-cli 0.87.0");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_check_version_separator_only_for_shims() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = crate::test_util::fake_executable(dir.path(), "codex", SEPARATOR_SHIM);
+
+        let result = check_version(&path, TEST_TIMEOUT, false).await;
+        assert!(matches!(result, Err(DetectionError::IoError)));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_check_version_minimal_env() {
+        let dir = tempfile::tempdir().unwrap();
+        // Prints the names of the variables the child can see
+        let path = crate::test_util::fake_executable(
+            dir.path(),
+            "agent",
+            "env | cut -d= -f1 | sort | tr '\\n' ' '",
+        );
+
+        let full = check_version(&path, TEST_TIMEOUT, false).await.unwrap();
+        let minimal = check_version(&path, TEST_TIMEOUT, true).await.unwrap();
+
+        // Variables the shell itself sets on startup
+        let shell_vars = ["PWD", "SHLVL", "_"];
+        let minimal_names: Vec<&str> = minimal.split_whitespace().collect();
+        assert!(minimal_names.len() < full.split_whitespace().count());
+        assert!(minimal_names.contains(&"PATH"));
+        assert!(minimal_names
+            .iter()
+            .all(
+                |name| crate::detection::probe::MINIMAL_ENV_VARS.contains(name)
+                    || shell_vars.contains(name)
+            ));
+    }
 }
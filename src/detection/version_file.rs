@@ -0,0 +1,112 @@
+//! Version lookup from files installed next to an agent executable.
+
+use crate::detection::parse_version;
+use semver::Version;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// Read an agent's version from a `VERSION` or `package.json` file.
+///
+/// Looks in the executable's directory and, if the executable is a symlink
+/// (as npm creates for global installs), in the resolved file's directory
+/// and its parent, which is the package root for npm's `bin/` layout. In
+/// each directory `VERSION` is tried before `package.json`'s `"version"`
+/// field.
+///
+/// Returns the parsed version and the raw string it came from, or `None`
+/// if no readable version file is found.
+pub(crate) fn read_version_file(path: &Path) -> Option<(Version, String)> {
+    version_file_dirs(path)
+        .iter()
+        .find_map(|dir| read_version_in(dir))
+}
+
+/// Directories to search for version files, in order.
+fn version_file_dirs(path: &Path) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = path.parent().map(Path::to_path_buf).into_iter().collect();
+
+    if let Ok(resolved) = path.canonicalize() {
+        if let Some(dir) = resolved.parent() {
+            for dir in [Some(dir), dir.parent()].into_iter().flatten() {
+                if !dirs.iter().any(|d| d == dir) {
+                    dirs.push(dir.to_path_buf());
+                }
+            }
+        }
+    }
+
+    dirs
+}
+
+fn read_version_in(dir: &Path) -> Option<(Version, String)> {
+    if let Ok(contents) = std::fs::read_to_string(dir.join("VERSION")) {
+        if let Some(found) = parse_version(contents.trim()) {
+            return Some(found);
+        }
+    }
+
+    let contents = std::fs::read_to_string(dir.join("package.json")).ok()?;
+    let package: Value = serde_json::from_str(&contents).ok()?;
+    let raw = package.get("version")?.as_str()?;
+    let version = Version::parse(raw).ok()?;
+    Some((version, raw.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sibling_package_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let binary = dir.path().join("codex");
+        std::fs::write(&binary, "fake").unwrap();
+        std::fs::write(
+            dir.path().join("package.json"),
+            r#"{ "name": "@openai/codex", "version": "0.87.0" }"#,
+        )
+        .unwrap();
+
+        let (version, raw) = read_version_file(&binary).unwrap();
+        assert_eq!(version, Version::new(0, 87, 0));
+        assert_eq!(raw, "0.87.0");
+    }
+
+    #[test]
+    fn test_version_file_preferred_over_package_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let binary = dir.path().join("claude");
+        std::fs::write(&binary, "fake").unwrap();
+        std::fs::write(dir.path().join("VERSION"), "2.1.12\n").unwrap();
+        std::fs::write(dir.path().join("package.json"), r#"{ "version": "1.0.0" }"#).unwrap();
+
+        let (version, _) = read_version_file(&binary).unwrap();
+        assert_eq!(version, Version::new(2, 1, 12));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_npm_symlink_layout() {
+        // <prefix>/bin/gemini -> <prefix>/lib/node_modules/@google/gemini-cli/dist/index.js
+        let prefix = tempfile::tempdir().unwrap();
+        let package = prefix.path().join("lib/node_modules/@google/gemini-cli");
+        std::fs::create_dir_all(package.join("dist")).unwrap();
+        std::fs::create_dir_all(prefix.path().join("bin")).unwrap();
+        std::fs::write(package.join("dist/index.js"), "fake").unwrap();
+        std::fs::write(package.join("package.json"), r#"{ "version": "0.24.4" }"#).unwrap();
+        let binary = prefix.path().join("bin/gemini");
+        std::os::unix::fs::symlink(package.join("dist/index.js"), &binary).unwrap();
+
+        let (version, _) = read_version_file(&binary).unwrap();
+        assert_eq!(version, Version::new(0, 24, 4));
+    }
+
+    #[test]
+    fn test_no_version_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let binary = dir.path().join("opencode");
+        std::fs::write(&binary, "fake").unwrap();
+        std::fs::write(dir.path().join("package.json"), r#"{ "name": "x" }"#).unwrap();
+        assert!(read_version_file(&binary).is_none());
+    }
+}
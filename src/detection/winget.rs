@@ -0,0 +1,97 @@
+//! Windows Package Manager (winget) fallback lookup.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::process::Command;
+use tokio::time::timeout;
+
+/// Find an agent executable installed through winget.
+///
+/// Runs `winget list --id <id> --exact` to confirm the package is installed,
+/// then looks for `<name>.exe` in the package's directory under
+/// `%LOCALAPPDATA%\Microsoft\WinGet\Packages`.
+///
+/// Returns `None` if winget isn't available, the package isn't listed, or
+/// the executable can't be located.
+pub(crate) async fn find_winget_executable(
+    id: &str,
+    name: &str,
+    timeout_duration: Duration,
+) -> Option<PathBuf> {
+    let mut cmd = Command::new("winget");
+    cmd.args(["list", "--id", id, "--exact", "--accept-source-agreements"])
+        .kill_on_drop(true);
+
+    let output = timeout(timeout_duration, cmd.output()).await.ok()?.ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    parse_winget_list(&stdout, id)?;
+
+    let local_app_data = PathBuf::from(std::env::var_os("LOCALAPPDATA")?);
+    let packages = local_app_data
+        .join("Microsoft")
+        .join("WinGet")
+        .join("Packages");
+    find_in_packages_dir(&packages, id, name)
+}
+
+/// Parse `winget list` output for a package id.
+///
+/// Returns the installed version (the column following the id) if the
+/// package is listed, `None` otherwise.
+pub(crate) fn parse_winget_list(output: &str, id: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        let mut tokens = line.split_whitespace();
+        tokens.position(|token| token.eq_ignore_ascii_case(id))?;
+        tokens.next().map(str::to_string)
+    })
+}
+
+/// Locate `<name>.exe` in a winget package directory (`<id>_<source hash>`).
+fn find_in_packages_dir(packages: &Path, id: &str, name: &str) -> Option<PathBuf> {
+    let prefix = format!("{}_", id);
+    std::fs::read_dir(packages)
+        .ok()?
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with(&prefix))
+        .map(|entry| entry.path().join(format!("{}.exe", name)))
+        .find(|path| path.exists())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_winget_list_installed() {
+        let output = "Name         Id                   Version Source\n\
+                      -------------------------------------------------\n\
+                      Claude Code  Anthropic.ClaudeCode 2.0.14  winget\n";
+        assert_eq!(
+            parse_winget_list(output, "Anthropic.ClaudeCode"),
+            Some("2.0.14".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_winget_list_not_installed() {
+        let output = "No installed package found matching input criteria.\n";
+        assert_eq!(parse_winget_list(output, "Anthropic.ClaudeCode"), None);
+    }
+
+    #[test]
+    fn test_find_in_packages_dir() {
+        let packages = tempfile::tempdir().unwrap();
+        let package_dir = packages
+            .path()
+            .join("Anthropic.ClaudeCode_Microsoft.Winget.Source_8wekyb3d8bbwe");
+        std::fs::create_dir_all(&package_dir).unwrap();
+        std::fs::write(package_dir.join("claude.exe"), b"MZ").unwrap();
+
+        let found = find_in_packages_dir(packages.path(), "Anthropic.ClaudeCode", "claude");
+        assert_eq!(found, Some(package_dir.join("claude.exe")));
+    }
+}
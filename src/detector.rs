@@ -0,0 +1,154 @@
+//! Reusable, stateful entry point for detection and installation.
+//!
+//! This module provides the [`Detector`] struct, which holds detection and
+//! installation options so applications that reuse the same settings don't
+//! need to pass them to every call.
+
+use crate::{
+    can_install, detect_all_with_options, detect_with_options, install, AgentKind, AgentStatus,
+    DetectOptions, DetectionError, InstallError, InstallOptions, InstallOutcome, InstallProgress,
+};
+use std::collections::HashMap;
+
+/// Detection and installation with stored configuration.
+///
+/// A `Detector` is constructed once with [`DetectOptions`] (and optionally
+/// [`InstallOptions`]) and then used for any number of calls.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use rig_acp_discovery::{AgentKind, DetectOptions, Detector};
+/// use std::time::Duration;
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() {
+///     let detector = Detector::new(DetectOptions {
+///         timeout: Duration::from_secs(10),
+///         ..Default::default()
+///     });
+///
+///     let status = detector.detect(AgentKind::ClaudeCode).await;
+///     println!("Claude Code usable: {}", status.is_usable());
+///
+///     for (kind, result) in detector.detect_all().await {
+///         println!("{}: {:?}", kind.display_name(), result);
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Detector {
+    options: DetectOptions,
+    install_options: InstallOptions,
+}
+
+impl Detector {
+    /// Create a detector with the given detection options.
+    ///
+    /// Installation uses `InstallOptions::default()` unless overridden with
+    /// [`Detector::with_install_options`].
+    pub fn new(options: DetectOptions) -> Self {
+        Self {
+            options,
+            install_options: InstallOptions::default(),
+        }
+    }
+
+    /// Set the options used by [`Detector::install`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rig_acp_discovery::{DetectOptions, Detector, InstallOptions};
+    /// use std::time::Duration;
+    ///
+    /// let detector = Detector::new(DetectOptions::default()).with_install_options(InstallOptions {
+    ///     timeout: Duration::from_secs(600),
+    ///     ..Default::default()
+    /// });
+    /// assert_eq!(detector.install_options().timeout, Duration::from_secs(600));
+    /// ```
+    pub fn with_install_options(mut self, install_options: InstallOptions) -> Self {
+        self.install_options = install_options;
+        self
+    }
+
+    /// The detection options used by this detector.
+    pub fn options(&self) -> &DetectOptions {
+        &self.options
+    }
+
+    /// The installation options used by this detector.
+    pub fn install_options(&self) -> &InstallOptions {
+        &self.install_options
+    }
+
+    /// Detect a single agent using the stored options.
+    ///
+    /// See [`detect_with_options`] for details.
+    pub async fn detect(&self, kind: AgentKind) -> AgentStatus {
+        detect_with_options(kind, self.options.clone()).await
+    }
+
+    /// Detect all agents in parallel using the stored options.
+    ///
+    /// See [`detect_all_with_options`] for details.
+    pub async fn detect_all(&self) -> HashMap<AgentKind, Result<AgentStatus, DetectionError>> {
+        detect_all_with_options(self.options.clone()).await
+    }
+
+    /// Check whether an agent can be installed.
+    ///
+    /// See [`can_install`] for details.
+    pub async fn can_install(&self, kind: AgentKind) -> Result<(), InstallError> {
+        can_install(kind).await
+    }
+
+    /// Install an agent using the stored installation options.
+    ///
+    /// See [`install`] for details.
+    pub async fn install<F>(
+        &self,
+        kind: AgentKind,
+        on_progress: F,
+    ) -> Result<InstallOutcome, InstallError>
+    where
+        F: Fn(InstallProgress) + Send + Sync,
+    {
+        install(kind, self.install_options.clone(), on_progress).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_default_detector() {
+        let detector = Detector::default();
+        assert_eq!(detector.options().timeout, Duration::from_secs(5));
+        assert_eq!(detector.install_options().timeout, Duration::from_secs(300));
+    }
+
+    #[tokio::test]
+    async fn test_detector_with_custom_timeout() {
+        let detector = Detector::new(DetectOptions {
+            timeout: Duration::from_secs(1),
+            ..Default::default()
+        });
+        assert_eq!(detector.options().timeout, Duration::from_secs(1));
+
+        let results = detector.detect_all().await;
+        assert_eq!(results.len(), 4);
+
+        let status = detector.detect(AgentKind::Codex).await;
+        assert!(matches!(
+            status,
+            AgentStatus::Installed(_)
+                | AgentStatus::NotInstalled
+                | AgentStatus::VersionMismatch { .. }
+                | AgentStatus::Unknown { .. }
+        ));
+    }
+}
@@ -0,0 +1,294 @@
+//! One-call environment health check.
+//!
+//! This module provides [`doctor`], which combines the crate's individual
+//! diagnostics (PATH checks, agent detection, shadowed installs, and
+//! installation pre-flight checks) into a single [`DoctorReport`] suitable
+//! for a CLI `doctor` subcommand.
+
+use crate::detection::{find_all_executables, path_issues, same_path};
+use crate::fixes as fix_templates;
+use crate::install::can_install_all;
+use crate::{detect_all, AgentKind, AgentStatus, DetectionError, InstallError};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::PathBuf;
+
+/// Diagnosis for a single agent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentDiagnosis {
+    /// The agent being diagnosed.
+    pub kind: AgentKind,
+
+    /// Short status summary (e.g., "installed", "not installed").
+    pub status: String,
+
+    /// Path to the executable that detection selected, if any.
+    pub path: Option<PathBuf>,
+
+    /// Detected version (parsed if possible, otherwise raw), if any.
+    pub version: Option<String>,
+
+    /// Other copies of the executable that are shadowed by `path`.
+    pub shadowed: Vec<PathBuf>,
+
+    /// Why the agent can't be installed, if the pre-flight check failed.
+    pub prerequisite_error: Option<String>,
+
+    /// Suggested fixes for any problems found.
+    pub fixes: Vec<String>,
+}
+
+/// Combined health report for the current environment.
+///
+/// The report serializes to JSON via serde, and its [`Display`](fmt::Display)
+/// implementation renders a human-readable text report.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DoctorReport {
+    /// Problems found with the PATH environment variable.
+    pub path_issues: Vec<String>,
+
+    /// One diagnosis per known agent, in `AgentKind::all()` order.
+    pub agents: Vec<AgentDiagnosis>,
+}
+
+impl DoctorReport {
+    /// Whether the report found no problems at all.
+    pub fn is_healthy(&self) -> bool {
+        self.path_issues.is_empty() && self.agents.iter().all(|a| a.fixes.is_empty())
+    }
+}
+
+impl fmt::Display for DoctorReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "PATH:")?;
+        if self.path_issues.is_empty() {
+            writeln!(f, "  ok")?;
+        }
+        for issue in &self.path_issues {
+            writeln!(f, "  ! {}", issue)?;
+        }
+
+        for agent in &self.agents {
+            writeln!(f)?;
+            write!(f, "{}: {}", agent.kind.display_name(), agent.status)?;
+            if let Some(version) = &agent.version {
+                write!(f, " {}", version)?;
+            }
+            if let Some(path) = &agent.path {
+                write!(f, " at {}", path.display())?;
+            }
+            writeln!(f)?;
+            for path in &agent.shadowed {
+                writeln!(f, "  shadowed: {}", path.display())?;
+            }
+            if let Some(error) = &agent.prerequisite_error {
+                writeln!(f, "  prerequisite: {}", error)?;
+            }
+            for fix in &agent.fixes {
+                writeln!(f, "  fix: {}", fix)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Run every diagnostic and summarize the environment's health.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use rig_acp_discovery::doctor;
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() {
+///     let report = doctor().await;
+///     println!("{}", report);
+/// }
+/// ```
+pub async fn doctor() -> DoctorReport {
+    let (detections, mut install_checks) = futures::join!(detect_all(), can_install_all());
+
+    let agents = AgentKind::all()
+        .map(|kind| {
+            let detection = detections
+                .get(&kind)
+                .cloned()
+                .unwrap_or(Ok(AgentStatus::NotInstalled));
+            let install_check = install_checks.remove(&kind).unwrap_or(Ok(()));
+            diagnose(
+                kind,
+                detection,
+                install_check,
+                find_all_executables(kind.executable_name()),
+            )
+        })
+        .collect();
+
+    DoctorReport {
+        path_issues: path_issues(),
+        agents,
+    }
+}
+
+/// Build one agent's diagnosis from the individual diagnostic results.
+fn diagnose(
+    kind: AgentKind,
+    detection: Result<AgentStatus, DetectionError>,
+    install_check: Result<(), InstallError>,
+    all_paths: Vec<PathBuf>,
+) -> AgentDiagnosis {
+    let mut fixes = Vec::new();
+
+    let status = match &detection {
+        Ok(AgentStatus::Installed(_)) => "installed",
+        Ok(AgentStatus::NotInstalled) => "not installed",
+        Ok(AgentStatus::VersionMismatch { .. }) => "version mismatch",
+        Ok(_) | Err(_) => "unknown",
+    };
+
+    let (path, version) = match &detection {
+        Ok(AgentStatus::Installed(meta)) => (
+            Some(meta.path.clone()),
+            meta.version
+                .as_ref()
+                .map(|v| v.to_string())
+                .or_else(|| meta.raw_version.clone()),
+        ),
+        Ok(status) => (
+            status.path().map(PathBuf::from),
+            status.version().map(|v| v.to_string()),
+        ),
+        Err(_) => (None, None),
+    };
+
+    match &detection {
+        Ok(AgentStatus::NotInstalled) => fixes.push(fix_templates::render(
+            fix_templates::NOT_INSTALLED,
+            &[("command", &kind.install_info().primary.raw_command)],
+        )),
+        Ok(AgentStatus::VersionMismatch { required, .. }) => fixes.push(fix_templates::render(
+            fix_templates::UPGRADE_AGENT,
+            &[("agent", &kind.display_name()), ("required", required)],
+        )),
+        Ok(AgentStatus::Unknown { message, .. }) => fixes.push(message.clone()),
+        Err(e) => fixes.push(fix_templates::render(
+            fix_templates::DETECTION_FAILED,
+            &[
+                ("error", &e.description()),
+                ("executable", &kind.executable_name()),
+            ],
+        )),
+        Ok(_) => {}
+    }
+
+    let shadowed: Vec<PathBuf> = match &path {
+        Some(selected) => all_paths
+            .into_iter()
+            .filter(|p| !same_path(p, selected))
+            .collect(),
+        None => Vec::new(),
+    };
+    if !shadowed.is_empty() {
+        fixes.push(fix_templates::render(
+            fix_templates::SHADOWED_COPIES,
+            &[("executable", &kind.executable_name())],
+        ));
+    }
+
+    let prerequisite_error = match install_check {
+        Ok(()) => None,
+        Err(e) => {
+            // Prerequisites only matter for agents that still need installing
+            if !matches!(detection, Ok(AgentStatus::Installed(_))) {
+                fixes.push(e.fix_suggestion().to_string());
+            }
+            Some(e.to_string())
+        }
+    };
+
+    AgentDiagnosis {
+        kind,
+        status: status.to_string(),
+        path,
+        version,
+        shadowed,
+        prerequisite_error,
+        fixes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_doctor_reports_every_agent() {
+        let report = doctor().await;
+
+        assert_eq!(report.agents.len(), AgentKind::all().count());
+        let text = report.to_string();
+        assert!(text.contains("PATH:"));
+        for kind in AgentKind::all() {
+            assert!(report.agents.iter().any(|a| a.kind == kind));
+            assert!(text.contains(kind.display_name()));
+        }
+
+        let json = serde_json::to_string(&report).expect("Should serialize");
+        assert!(json.contains("path_issues"));
+        assert!(json.contains("agents"));
+    }
+
+    #[test]
+    fn test_diagnose_not_installed_suggests_install() {
+        let diagnosis = diagnose(
+            AgentKind::Codex,
+            Ok(AgentStatus::NotInstalled),
+            Ok(()),
+            Vec::new(),
+        );
+        assert_eq!(diagnosis.status, "not installed");
+        assert!(diagnosis.fixes[0].contains("npm install -g @openai/codex"));
+        assert!(diagnosis.prerequisite_error.is_none());
+    }
+
+    #[test]
+    fn test_diagnose_missing_prerequisite() {
+        let diagnosis = diagnose(
+            AgentKind::Gemini,
+            Ok(AgentStatus::NotInstalled),
+            Err(InstallError::PrerequisiteMissing {
+                name: "Node.js 20+".to_string(),
+                install_url: Some("https://nodejs.org".to_string()),
+                fix: "Install Node.js 20+ from https://nodejs.org".to_string(),
+            }),
+            Vec::new(),
+        );
+        assert!(diagnosis
+            .prerequisite_error
+            .as_deref()
+            .is_some_and(|e| e.contains("Node.js 20+")));
+        assert!(diagnosis.fixes.iter().any(|f| f.contains("nodejs.org")));
+    }
+
+    #[test]
+    fn test_diagnose_shadowed_install() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = dir.path().join("first");
+        let second = dir.path().join("second");
+        std::fs::write(&first, b"").unwrap();
+        std::fs::write(&second, b"").unwrap();
+
+        let diagnosis = diagnose(
+            AgentKind::ClaudeCode,
+            Ok(AgentStatus::VersionMismatch {
+                found: semver::Version::new(1, 0, 0),
+                required: semver::Version::new(2, 0, 0),
+                path: first.clone(),
+            }),
+            Ok(()),
+            vec![first, second.clone()],
+        );
+        assert_eq!(diagnosis.shadowed, vec![second]);
+        assert_eq!(diagnosis.fixes.len(), 2);
+    }
+}
@@ -0,0 +1,448 @@
+//! Checking a whole environment against a set of requirements.
+//!
+//! [`check_environment`] generalizes `can_install` from one agent's
+//! prerequisites to everything an application needs: executables such as
+//! Node.js or git with minimum versions, the agents it wants to use, and a
+//! sane PATH. Every requirement is checked and reported, pass or fail, so
+//! an onboarding flow can show a complete checklist.
+
+use crate::detection::{parse_version, path_issues};
+use crate::fixes;
+use crate::runtime::version_output;
+use crate::{detect, AgentKind, AgentStatus};
+use futures::future::join_all;
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::future::Future;
+
+/// An executable the environment needs, such as `node` or `git`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutableRequirement {
+    /// Executable name, run as `<name> --version` (e.g., "git").
+    pub name: String,
+
+    /// Oldest acceptable version, or `None` if any version will do.
+    pub min_version: Option<Version>,
+
+    /// Where to get the executable, used in the fix suggestion.
+    pub install_url: Option<String>,
+}
+
+/// Everything an environment must provide.
+///
+/// # Example
+///
+/// ```rust
+/// use rig_acp_discovery::{AgentKind, EnvRequirements, ExecutableRequirement};
+/// use semver::Version;
+///
+/// let requirements = EnvRequirements {
+///     executables: vec![ExecutableRequirement {
+///         name: "node".to_string(),
+///         min_version: Some(Version::new(20, 0, 0)),
+///         install_url: Some("https://nodejs.org".to_string()),
+///     }],
+///     agents: vec![AgentKind::Codex],
+///     ..Default::default()
+/// };
+/// assert!(requirements.check_path);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvRequirements {
+    /// Executables that must be on PATH.
+    pub executables: Vec<ExecutableRequirement>,
+
+    /// Agents that must be installed and usable.
+    pub agents: Vec<AgentKind>,
+
+    /// Check PATH for missing entries and an unlisted user bin directory.
+    ///
+    /// Default: `true`
+    pub check_path: bool,
+}
+
+impl Default for EnvRequirements {
+    fn default() -> Self {
+        Self {
+            executables: Vec::new(),
+            agents: Vec::new(),
+            check_path: true,
+        }
+    }
+}
+
+/// What a single [`EnvCheckItem`] checked.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum EnvItem {
+    /// An executable from `EnvRequirements::executables`.
+    Executable(String),
+
+    /// An agent from `EnvRequirements::agents`.
+    Agent(AgentKind),
+
+    /// The PATH environment variable.
+    Path,
+}
+
+impl fmt::Display for EnvItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Executable(name) => f.write_str(name),
+            Self::Agent(kind) => f.write_str(kind.display_name()),
+            Self::Path => f.write_str("PATH"),
+        }
+    }
+}
+
+/// The result of checking one requirement.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvCheckItem {
+    /// What was checked.
+    pub item: EnvItem,
+
+    /// Whether the requirement is met.
+    pub passed: bool,
+
+    /// What was found: the version of an executable or agent, or the
+    /// problem with PATH.
+    pub detail: Option<String>,
+
+    /// Actionable suggestion for a failed requirement.
+    pub fix: Option<String>,
+}
+
+impl EnvCheckItem {
+    fn pass(item: EnvItem, detail: Option<String>) -> Self {
+        Self {
+            item,
+            passed: true,
+            detail,
+            fix: None,
+        }
+    }
+
+    fn fail(item: EnvItem, detail: Option<String>, fix: String) -> Self {
+        Self {
+            item,
+            passed: false,
+            detail,
+            fix: Some(fix),
+        }
+    }
+}
+
+/// The result of [`check_environment`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvCheckResult {
+    /// One result per requirement: executables and agents in the order
+    /// they were listed, then PATH.
+    pub items: Vec<EnvCheckItem>,
+}
+
+impl EnvCheckResult {
+    /// Whether every requirement is met.
+    pub fn passed(&self) -> bool {
+        self.items.iter().all(|item| item.passed)
+    }
+
+    /// The requirements that are not met.
+    pub fn failures(&self) -> impl Iterator<Item = &EnvCheckItem> {
+        self.items.iter().filter(|item| !item.passed)
+    }
+}
+
+/// Check an environment against a set of requirements.
+///
+/// Executables are run with `--version` and their version compared with
+/// `min_version`; agents are checked with [`detect`] and must be usable;
+/// PATH is checked for entries that don't exist and for a user bin
+/// directory missing from it. All checks run concurrently, and every
+/// requirement gets an [`EnvCheckItem`], with a fix if it failed.
+///
+/// PATH produces one item per problem found, or a single passing item.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use rig_acp_discovery::{check_environment, AgentKind, EnvRequirements, ExecutableRequirement};
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() {
+///     let requirements = EnvRequirements {
+///         executables: vec![ExecutableRequirement {
+///             name: "git".to_string(),
+///             min_version: None,
+///             install_url: Some("https://git-scm.com".to_string()),
+///         }],
+///         agents: vec![AgentKind::ClaudeCode],
+///         ..Default::default()
+///     };
+///
+///     let result = check_environment(&requirements).await;
+///     for item in &result.items {
+///         let mark = if item.passed { "ok" } else { "FAIL" };
+///         println!("{} {}", mark, item.item);
+///         if let Some(fix) = &item.fix {
+///             println!("  fix: {}", fix);
+///         }
+///     }
+/// }
+/// ```
+pub async fn check_environment(requirements: &EnvRequirements) -> EnvCheckResult {
+    check_environment_with(
+        requirements,
+        |name| async move { version_output(&name).await },
+        detect,
+        path_issues,
+    )
+    .await
+}
+
+/// [`check_environment`] with injectable command runner, detection and PATH check.
+///
+/// `run` returns an executable's `--version` output, or `None` if it
+/// couldn't be run.
+async fn check_environment_with<R, RFut, D, DFut>(
+    requirements: &EnvRequirements,
+    run: R,
+    detect: D,
+    path_issues: impl FnOnce() -> Vec<String>,
+) -> EnvCheckResult
+where
+    R: Fn(String) -> RFut,
+    RFut: Future<Output = Option<String>>,
+    D: Fn(AgentKind) -> DFut,
+    DFut: Future<Output = AgentStatus>,
+{
+    let executables = join_all(requirements.executables.iter().map(|requirement| {
+        let output = run(requirement.name.clone());
+        async move { check_executable(requirement, output.await) }
+    }));
+    let agents = join_all(requirements.agents.iter().map(|&kind| {
+        let status = detect(kind);
+        async move { check_agent(kind, status.await) }
+    }));
+    let (executables, agents) = futures::join!(executables, agents);
+
+    let mut items = executables;
+    items.extend(agents);
+    if requirements.check_path {
+        items.extend(check_path(path_issues()));
+    }
+    EnvCheckResult { items }
+}
+
+/// Judge an executable from its `--version` output.
+fn check_executable(requirement: &ExecutableRequirement, output: Option<String>) -> EnvCheckItem {
+    let item = EnvItem::Executable(requirement.name.clone());
+    let Some(output) = output else {
+        let install_url = requirement
+            .install_url
+            .as_deref()
+            .unwrap_or(fixes::UNKNOWN_INSTALL_URL);
+        let fix = fixes::render(
+            fixes::PREREQUISITE_MISSING,
+            &[("name", &requirement.name), ("install_url", &install_url)],
+        );
+        return EnvCheckItem::fail(item, None, fix);
+    };
+
+    let version = parse_version(&output).map(|(version, _)| version);
+    let detail = version.as_ref().map(Version::to_string);
+    match &requirement.min_version {
+        Some(required) if version.as_ref().map_or(true, |found| found < required) => {
+            let fix = fixes::render(
+                fixes::PREREQUISITE_VERSION_MISMATCH,
+                &[("name", &requirement.name), ("required", required)],
+            );
+            EnvCheckItem::fail(item, detail, fix)
+        }
+        _ => EnvCheckItem::pass(item, detail),
+    }
+}
+
+/// Judge an agent from its detection status.
+fn check_agent(kind: AgentKind, status: AgentStatus) -> EnvCheckItem {
+    let item = EnvItem::Agent(kind);
+    let detail = status.version().map(Version::to_string);
+    match &status {
+        AgentStatus::Installed(_) => EnvCheckItem::pass(item, detail),
+        AgentStatus::NotInstalled => {
+            let fix = fixes::render(
+                fixes::NOT_INSTALLED,
+                &[("command", &kind.install_info().primary.raw_command)],
+            );
+            EnvCheckItem::fail(item, None, fix)
+        }
+        AgentStatus::VersionMismatch { required, .. } => {
+            let fix = fixes::render(
+                fixes::UPGRADE_AGENT,
+                &[("agent", &kind.display_name()), ("required", required)],
+            );
+            EnvCheckItem::fail(item, detail, fix)
+        }
+        AgentStatus::Unknown { error, message } => {
+            let fix = fixes::render(
+                fixes::DETECTION_FAILED,
+                &[
+                    ("error", &error.description()),
+                    ("executable", &kind.executable_name()),
+                ],
+            );
+            EnvCheckItem::fail(item, Some(message.clone()), fix)
+        }
+    }
+}
+
+/// One failing item per PATH problem, or a single passing item.
+fn check_path(issues: Vec<String>) -> Vec<EnvCheckItem> {
+    if issues.is_empty() {
+        return vec![EnvCheckItem::pass(EnvItem::Path, None)];
+    }
+    issues
+        .into_iter()
+        .map(|issue| {
+            let fix = fixes::render(fixes::PATH_ISSUE, &[("issue", &issue)]);
+            EnvCheckItem::fail(EnvItem::Path, Some(issue), fix)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn executable(name: &str, min_version: Option<Version>) -> ExecutableRequirement {
+        ExecutableRequirement {
+            name: name.to_string(),
+            min_version,
+            install_url: None,
+        }
+    }
+
+    fn installed(version: Version) -> AgentStatus {
+        AgentStatus::Installed(crate::test_util::installed_meta(
+            "/usr/bin/agent",
+            Some(version),
+        ))
+    }
+
+    /// Mock runner: `node` is too old, `git` is current, `make` has no
+    /// version, anything else is missing.
+    async fn mock_run(name: String) -> Option<String> {
+        match name.as_str() {
+            "node" => Some("v18.19.0".to_string()),
+            "git" => Some("git version 2.43.0".to_string()),
+            "make" => Some("GNU Make".to_string()),
+            _ => None,
+        }
+    }
+
+    async fn mock_detect(kind: AgentKind) -> AgentStatus {
+        match kind {
+            AgentKind::Codex => installed(Version::new(0, 90, 0)),
+            AgentKind::OpenCode => AgentStatus::Unknown {
+                error: crate::DetectionError::Timeout,
+                message: "OpenCode timed out".to_string(),
+            },
+            _ => AgentStatus::NotInstalled,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mixed_requirements() {
+        let requirements = EnvRequirements {
+            executables: vec![
+                executable("node", Some(Version::new(20, 0, 0))),
+                executable("git", Some(Version::new(2, 0, 0))),
+                executable("make", None),
+                executable("docker", None),
+            ],
+            agents: vec![AgentKind::Codex, AgentKind::Gemini],
+            check_path: true,
+        };
+        let result = check_environment_with(&requirements, mock_run, mock_detect, || {
+            vec!["PATH entry does not exist: /nope".to_string()]
+        })
+        .await;
+
+        let summary: Vec<_> = result
+            .items
+            .iter()
+            .map(|item| (item.item.to_string(), item.passed))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("node".to_string(), false),
+                ("git".to_string(), true),
+                ("make".to_string(), true),
+                ("docker".to_string(), false),
+                ("Codex".to_string(), true),
+                ("Gemini CLI".to_string(), false),
+                ("PATH".to_string(), false),
+            ]
+        );
+        assert!(!result.passed());
+        assert_eq!(result.failures().count(), 4);
+
+        let node = &result.items[0];
+        assert_eq!(node.detail.as_deref(), Some("18.19.0"));
+        assert!(node.fix.as_ref().unwrap().contains("20.0.0"));
+        let docker = &result.items[3];
+        assert!(docker.fix.as_ref().unwrap().contains("docker"));
+        let gemini = &result.items[5];
+        assert!(gemini.fix.as_ref().unwrap().contains("@google/gemini-cli"));
+        let path = &result.items[6];
+        assert_eq!(
+            path.detail.as_deref(),
+            Some("PATH entry does not exist: /nope")
+        );
+        // Passing items have no fix
+        assert!(result.items[1].fix.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_unreadable_version_fails_minimum() {
+        let requirements = EnvRequirements {
+            executables: vec![executable("make", Some(Version::new(4, 0, 0)))],
+            check_path: false,
+            ..Default::default()
+        };
+        let result = check_environment_with(&requirements, mock_run, mock_detect, Vec::new).await;
+
+        assert_eq!(result.items.len(), 1);
+        assert!(!result.items[0].passed);
+        assert_eq!(result.items[0].detail, None);
+    }
+
+    #[tokio::test]
+    async fn test_failed_detection_has_template_fix() {
+        let requirements = EnvRequirements {
+            agents: vec![AgentKind::OpenCode],
+            check_path: false,
+            ..Default::default()
+        };
+        let result = check_environment_with(&requirements, mock_run, mock_detect, Vec::new).await;
+
+        let item = &result.items[0];
+        assert!(!item.passed);
+        assert_eq!(item.detail.as_deref(), Some("OpenCode timed out"));
+        let fix = item.fix.as_deref().unwrap();
+        assert!(fix.starts_with("Detection failed"), "{}", fix);
+        assert!(fix.contains("opencode runs from a terminal"), "{}", fix);
+    }
+
+    #[tokio::test]
+    async fn test_empty_requirements_pass() {
+        let result =
+            check_environment_with(&EnvRequirements::default(), mock_run, mock_detect, Vec::new)
+                .await;
+
+        assert!(result.passed());
+        assert_eq!(result.items.len(), 1);
+        assert_eq!(result.items[0].item, EnvItem::Path);
+    }
+}
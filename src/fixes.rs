@@ -0,0 +1,274 @@
+//! Catalog of fix suggestions shown to users.
+//!
+//! Every actionable fix the crate produces, whether an [`InstallError`]'s
+//! `fix` field, a detection message, or a doctor report entry, is built
+//! from one of the templates here. Templates use `{name}` placeholders
+//! filled in by [`render`]. Keeping them in one place keeps the wording
+//! consistent and lets documentation and localization work from
+//! [`all_fix_templates`].
+//!
+//! [`InstallError`]: crate::InstallError
+
+use std::fmt::Display;
+
+/// The agent is not supported on this OS or architecture.
+pub(crate) const UNSUPPORTED_PLATFORM: &str = "See {docs_url} for supported platforms";
+
+/// The install was cancelled before it finished.
+pub(crate) const INSTALL_CANCELLED: &str =
+    "The {agent} install was cancelled and may be incomplete; run the install again to finish it";
+
+/// `update()` was called for an agent that isn't installed.
+pub(crate) const UPDATE_NOT_INSTALLED: &str =
+    "{agent} is not installed; install it first with: {command}";
+
+/// `update()` found the agent installed by a package manager none of its
+/// install methods use.
+pub(crate) const UPDATE_UNSUPPORTED_METHOD: &str =
+    "{agent} was installed with {method}; update it with {method} instead";
+
+/// The agent has no uninstall command (e.g., a native installer).
+pub(crate) const UNINSTALL_UNSUPPORTED: &str =
+    "{agent} has no uninstall command; see {docs_url} for how to remove it";
+
+/// `uninstall()` found the agent installed by a package manager none of
+/// its install methods use.
+pub(crate) const UNINSTALL_UNSUPPORTED_METHOD: &str =
+    "{agent} was installed with {method}; uninstall it with {method} instead";
+
+/// The agent was still found after its uninstall command ran.
+pub(crate) const UNINSTALL_FAILED: &str =
+    "Remove {path} manually, or uninstall {agent} with the package manager that installed it";
+
+/// A prerequisite command is missing or its version can't be read.
+pub(crate) const PREREQUISITE_MISSING: &str = "Install {name} from {install_url}";
+
+/// No install method's program (such as `brew` or `npm`) is available.
+pub(crate) const INSTALL_PROGRAM_MISSING: &str =
+    "Install one of these programs to install {agent}: {programs}";
+
+/// Shown in place of `{install_url}` when a prerequisite has no URL.
+pub(crate) const UNKNOWN_INSTALL_URL: &str = "the official website";
+
+/// A prerequisite is installed but too old.
+pub(crate) const PREREQUISITE_VERSION_MISMATCH: &str = "Upgrade {name} to version {required}+";
+
+/// The installer could not be started due to permissions.
+pub(crate) const INSTALLER_PERMISSION_DENIED: &str = "Try running with appropriate permissions";
+
+/// The installer could not be started.
+pub(crate) const INSTALLER_SPAWN_FAILED: &str = "Check the command and try again";
+
+/// The installer exited with a non-zero status.
+pub(crate) const INSTALLER_FAILED: &str = "See installer output above for details";
+
+/// The installer ran longer than the configured timeout.
+pub(crate) const INSTALL_TIMEOUT: &str =
+    "Installation timed out after {duration}. Try with a longer timeout or check network.";
+
+/// The installer failed with a network error.
+pub(crate) const NETWORK: &str = "Check your internet connection and try again";
+
+/// `install_with_method` was given an index past the last method.
+pub(crate) const INVALID_METHOD_INDEX: &str =
+    "Choose a method index from 0 to {max}; see InstallInfo::methods() for the list";
+
+/// Both a version pin and a dist-tag were requested.
+pub(crate) const VERSION_AND_DIST_TAG: &str =
+    "Set either InstallOptions::version or InstallOptions::dist_tag, not both";
+
+/// Post-install verification found the agent but it failed to run.
+pub(crate) const VERIFY_UNKNOWN: &str =
+    "Installation completed but {agent} could not be verified: {message}";
+
+/// Post-install verification found an older copy of the agent.
+pub(crate) const VERIFY_VERSION_MISMATCH: &str = "Installation completed but {agent} at {path} reports version {found}, and {required} is required. Another copy may be shadowing the new install.";
+
+/// The agent was installed into a directory that isn't on PATH.
+pub(crate) const VERIFY_OFF_PATH: &str =
+    "{agent} was installed to {dir}, which isn't on PATH. Add it to PATH and restart your terminal.";
+
+/// Post-install verification found no copy of the agent anywhere.
+pub(crate) const VERIFY_NOT_FOUND: &str = "Installation completed but {agent} was not found in any known location. You may need to restart your terminal for PATH changes to take effect.";
+
+/// The agent binary is root-owned and not executable (Unix).
+pub(crate) const BINARY_PERMISSION_DENIED_UNIX: &str =
+    "Reinstall {agent} without sudo, or make it executable with: sudo chmod a+rx {path}";
+
+/// The agent binary is not executable by the current user (non-Unix).
+pub(crate) const BINARY_PERMISSION_DENIED: &str = "Reinstall {agent} for the current user";
+
+/// The agent binary is quarantined by macOS Gatekeeper.
+pub(crate) const QUARANTINED: &str = "xattr -d com.apple.quarantine {path}";
+
+/// The agent binary is empty, likely from an interrupted download.
+pub(crate) const CORRUPT_INSTALL: &str = "Reinstall with: {command}";
+
+/// The agent is not installed.
+pub(crate) const NOT_INSTALLED: &str = "Install with: {command}";
+
+/// The installed agent is older than required.
+pub(crate) const UPGRADE_AGENT: &str = "Upgrade {agent} to {required} or later";
+
+/// Detection failed with an error.
+pub(crate) const DETECTION_FAILED: &str =
+    "Detection failed ({error}); check that {executable} runs from a terminal";
+
+/// A problem with PATH found by `check_environment()`.
+pub(crate) const PATH_ISSUE: &str = "Update PATH in your shell profile: {issue}";
+
+/// Several copies of the agent executable were found.
+pub(crate) const SHADOWED_COPIES: &str =
+    "Multiple copies of {executable} found; remove the unused ones or reorder PATH";
+
+/// Every fix template, keyed by the error or diagnostic that produces it.
+const FIX_TEMPLATES: &[(&str, &str)] = &[
+    ("InstallError::UnsupportedPlatform", UNSUPPORTED_PLATFORM),
+    (
+        "InstallError::UnsupportedPlatform(uninstall)",
+        UNINSTALL_UNSUPPORTED,
+    ),
+    ("InstallError::PrerequisiteMissing", PREREQUISITE_MISSING),
+    (
+        "InstallError::PrerequisiteMissing(install_program)",
+        INSTALL_PROGRAM_MISSING,
+    ),
+    (
+        "InstallError::PrerequisiteVersionMismatch",
+        PREREQUISITE_VERSION_MISMATCH,
+    ),
+    (
+        "InstallError::PermissionDenied",
+        INSTALLER_PERMISSION_DENIED,
+    ),
+    (
+        "InstallError::InstallerFailed(spawn)",
+        INSTALLER_SPAWN_FAILED,
+    ),
+    ("InstallError::InstallerFailed(exit)", INSTALLER_FAILED),
+    (
+        "InstallError::InstallerFailed(method_index)",
+        INVALID_METHOD_INDEX,
+    ),
+    (
+        "InstallError::InstallerFailed(update_method)",
+        UPDATE_UNSUPPORTED_METHOD,
+    ),
+    (
+        "InstallError::InstallerFailed(uninstall_method)",
+        UNINSTALL_UNSUPPORTED_METHOD,
+    ),
+    ("InstallError::Timeout", INSTALL_TIMEOUT),
+    ("InstallError::Network", NETWORK),
+    ("InstallError::Cancelled", INSTALL_CANCELLED),
+    ("InstallError::UninstallFailed", UNINSTALL_FAILED),
+    ("InstallError::InvalidOptions", VERSION_AND_DIST_TAG),
+    ("InstallError::VerificationFailed(unknown)", VERIFY_UNKNOWN),
+    (
+        "InstallError::VerificationFailed(version_mismatch)",
+        VERIFY_VERSION_MISMATCH,
+    ),
+    (
+        "InstallError::VerificationFailed(off_path)",
+        VERIFY_OFF_PATH,
+    ),
+    (
+        "InstallError::VerificationFailed(not_found)",
+        VERIFY_NOT_FOUND,
+    ),
+    (
+        "InstallError::VerificationFailed(update_not_installed)",
+        UPDATE_NOT_INSTALLED,
+    ),
+    (
+        "DetectionError::PermissionDenied(unix)",
+        BINARY_PERMISSION_DENIED_UNIX,
+    ),
+    ("DetectionError::PermissionDenied", BINARY_PERMISSION_DENIED),
+    ("DetectionError::Quarantined", QUARANTINED),
+    ("DetectionError::CorruptInstall", CORRUPT_INSTALL),
+    ("doctor::NotInstalled", NOT_INSTALLED),
+    ("doctor::VersionMismatch", UPGRADE_AGENT),
+    ("doctor::DetectionFailed", DETECTION_FAILED),
+    ("doctor::Shadowed", SHADOWED_COPIES),
+    ("check_environment::Path", PATH_ISSUE),
+];
+
+/// List every fix suggestion template the crate can produce.
+///
+/// Each entry pairs the error kind or diagnostic that produces the fix
+/// (e.g., `"InstallError::Timeout"`) with its English template. Templates
+/// contain `{name}` placeholders for values such as the agent name, path,
+/// or version, which are filled in when the fix is produced.
+///
+/// # Example
+///
+/// ```rust
+/// use rig_acp_discovery::all_fix_templates;
+///
+/// for (kind, template) in all_fix_templates() {
+///     println!("{}: {}", kind, template);
+/// }
+/// ```
+pub fn all_fix_templates() -> Vec<(&'static str, &'static str)> {
+    FIX_TEMPLATES.to_vec()
+}
+
+/// Fill a template's `{name}` placeholders with `args`.
+///
+/// Placeholders without a matching argument are left as-is.
+pub(crate) fn render(template: &str, args: &[(&str, &dyn Display)]) -> String {
+    args.iter()
+        .fold(template.to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{}}}", name), &value.to_string())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+
+    #[test]
+    fn test_no_fix_template_is_empty() {
+        for (kind, template) in all_fix_templates() {
+            assert!(!kind.is_empty());
+            assert!(!template.trim().is_empty(), "empty template for {}", kind);
+        }
+    }
+
+    #[test]
+    fn test_fix_template_kinds_are_unique() {
+        let templates = all_fix_templates();
+        for (i, (kind, _)) in templates.iter().enumerate() {
+            assert!(
+                templates[i + 1..].iter().all(|(other, _)| other != kind),
+                "duplicate template kind {}",
+                kind
+            );
+        }
+    }
+
+    #[test]
+    fn test_fix_template_placeholders_are_well_formed() {
+        let placeholder = Regex::new(r"\{[a-z_]+\}").unwrap();
+        for (kind, template) in all_fix_templates() {
+            let stripped = placeholder.replace_all(template, "");
+            assert!(
+                !stripped.contains('{') && !stripped.contains('}'),
+                "malformed placeholder in {}",
+                kind
+            );
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let rendered = render(
+            PREREQUISITE_VERSION_MISMATCH,
+            &[("name", &"Node.js 20+"), ("required", &20)],
+        );
+        assert_eq!(rendered, "Upgrade Node.js 20+ to version 20+");
+        assert_eq!(render(NETWORK, &[]), NETWORK);
+    }
+}
@@ -0,0 +1,316 @@
+//! ACP health check beyond version detection.
+//!
+//! This module provides [`health_check`], which confirms that a detected
+//! agent actually offers an ACP mode by scanning its `--help` output for
+//! the flag or subcommand that starts it. It spawns the agent a second
+//! time, so it is kept separate from `detect()`.
+
+use crate::detection::run_probe;
+use crate::{detect_with_options, AgentKind, AgentStatus, DetectOptions, DetectionError};
+use std::future::Future;
+use std::path::Path;
+use std::time::Duration;
+
+/// Result of an ACP [`health_check`].
+///
+/// # Example
+///
+/// ```rust
+/// use rig_acp_discovery::HealthReport;
+///
+/// fn print_report(report: &HealthReport) {
+///     println!("ACP capable: {}", report.acp_capable);
+///     for note in &report.notes {
+///         println!("  - {}", note);
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HealthReport {
+    /// Whether the agent can be driven over ACP.
+    ///
+    /// `true` for agents without a known probe, since their ACP support
+    /// can't be disproved.
+    pub acp_capable: bool,
+
+    /// Human-readable findings, such as a missing ACP flag or a version
+    /// older than the first with ACP support.
+    pub notes: Vec<String>,
+}
+
+/// Check that an agent is installed and offers an ACP mode.
+///
+/// Detects `kind` with `options`, then runs `<agent> --help` (within
+/// `options.effective_timeout(kind)`, and with a minimal environment if
+/// `options.minimal_env` is set, as the version check is) and looks for
+/// the flag or subcommand that starts the agent as an ACP server:
+/// `--experimental-acp` for Gemini CLI and `opencode acp` for OpenCode.
+/// Claude Code and Codex speak ACP through separate adapter packages, so
+/// there is nothing to probe and they are reported as capable with a
+/// note saying so.
+///
+/// This spawns the agent beyond what `detect()` does; use it when
+/// diagnosing an agent rather than on every detection.
+///
+/// # Returns
+///
+/// - `Ok(report)` with `acp_capable: false` and a note if the agent is not
+///   installed or its help lists no ACP mode
+/// - `Err(DetectionError)` if detection failed, or the help command timed
+///   out or couldn't be run
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use rig_acp_discovery::{health_check, AgentKind, DetectOptions};
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() {
+///     match health_check(AgentKind::Gemini, DetectOptions::default()).await {
+///         Ok(report) if report.acp_capable => println!("Gemini CLI speaks ACP"),
+///         Ok(report) => println!("Gemini CLI can't be used: {:?}", report.notes),
+///         Err(e) => println!("Health check failed: {}", e.description()),
+///     }
+/// }
+/// ```
+pub async fn health_check(
+    kind: AgentKind,
+    options: DetectOptions,
+) -> Result<HealthReport, DetectionError> {
+    let probe_timeout = options.effective_timeout(kind);
+    let minimal_env = options.minimal_env;
+    health_check_with(
+        kind,
+        detect_with_options(kind, options),
+        probe_timeout,
+        minimal_env,
+    )
+    .await
+}
+
+/// [`health_check`] with an injectable detection.
+async fn health_check_with(
+    kind: AgentKind,
+    detect: impl Future<Output = AgentStatus>,
+    probe_timeout: Duration,
+    minimal_env: bool,
+) -> Result<HealthReport, DetectionError> {
+    let status = detect.await;
+    if let AgentStatus::Unknown { error, .. } = status {
+        return Err(error);
+    }
+    let Some(path) = status.path() else {
+        return Ok(HealthReport {
+            acp_capable: false,
+            notes: vec![format!("{} is not installed", kind.display_name())],
+        });
+    };
+
+    let mut notes = Vec::new();
+    let Some(markers) = help_markers(kind) else {
+        notes.push(format!(
+            "No ACP probe is known for {}; it speaks ACP through a separate adapter",
+            kind.display_name()
+        ));
+        return Ok(HealthReport {
+            acp_capable: true,
+            notes,
+        });
+    };
+
+    let help = run_help(path, probe_timeout, minimal_env).await?;
+    let listed = markers.iter().any(|marker| lists_marker(&help, marker));
+    if !listed {
+        notes.push(format!(
+            "`{} --help` doesn't list {}",
+            kind.executable_name(),
+            markers.join(" or ")
+        ));
+    }
+    if let (Some(floor), Some(version)) = (kind.min_supported_version(), status.version()) {
+        if *version < floor {
+            notes.push(format!(
+                "{} {} is older than {}, the oldest release usable over ACP",
+                kind.display_name(),
+                version,
+                floor
+            ));
+        }
+    }
+
+    Ok(HealthReport {
+        acp_capable: listed,
+        notes,
+    })
+}
+
+/// Flags or subcommands in an agent's `--help` output that show it has an
+/// ACP mode.
+///
+/// Returns `None` for agents without a native ACP mode to look for.
+fn help_markers(kind: AgentKind) -> Option<&'static [&'static str]> {
+    match kind {
+        AgentKind::Gemini => Some(&["--experimental-acp", "--acp"]),
+        AgentKind::OpenCode => Some(&["opencode acp"]),
+        AgentKind::ClaudeCode | AgentKind::Codex => None,
+    }
+}
+
+/// Whether `help` lists `marker`.
+///
+/// A flag (`--acp`) may appear anywhere as a word. A subcommand usage
+/// (`opencode acp`) must start a line, as in a command list, so the word
+/// "acp" in a description doesn't count.
+fn lists_marker(help: &str, marker: &str) -> bool {
+    if marker.starts_with('-') {
+        return help
+            .split_whitespace()
+            .any(|word| word.trim_end_matches(',') == marker);
+    }
+    help.lines().any(|line| {
+        let mut words = line.split_whitespace();
+        marker
+            .split_whitespace()
+            .all(|part| words.next() == Some(part))
+    })
+}
+
+/// Run `<path> --help` and return its stdout and stderr.
+///
+/// A non-zero exit is not an error, since some CLIs exit with 1 after
+/// printing help.
+async fn run_help(
+    path: &Path,
+    probe_timeout: Duration,
+    minimal_env: bool,
+) -> Result<String, DetectionError> {
+    let output = run_probe(path, &["--help"], probe_timeout, minimal_env).await?;
+    Ok(format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use semver::Version;
+    use std::path::PathBuf;
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    fn installed(path: PathBuf, version: Version) -> AgentStatus {
+        AgentStatus::Installed(crate::test_util::installed_meta(path, Some(version)))
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_gemini_help_lists_acp_flag() {
+        let dir = tempfile::tempdir().unwrap();
+        let gemini = crate::test_util::fake_executable(
+            dir.path(),
+            "gemini",
+            "echo 'Options:'\n\
+             echo '  -m, --model               Model  [string]'\n\
+             echo '      --experimental-acp    Starts the agent in ACP mode  [boolean]'",
+        );
+
+        let status = installed(gemini, Version::new(0, 9, 0));
+        let report = health_check_with(AgentKind::Gemini, async { status }, TIMEOUT, false)
+            .await
+            .unwrap();
+        assert_eq!(
+            report,
+            HealthReport {
+                acp_capable: true,
+                notes: vec![],
+            }
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_opencode_help_without_acp() {
+        let dir = tempfile::tempdir().unwrap();
+        let opencode = crate::test_util::fake_executable(
+            dir.path(),
+            "opencode",
+            "echo 'Commands:'\n\
+             echo '  opencode run [message..]   run opencode with a message'\n\
+             echo '  opencode serve             starts a headless opencode server, no acp'\n\
+             exit 1",
+        );
+
+        let status = installed(opencode, Version::new(0, 3, 0));
+        let report = health_check_with(AgentKind::OpenCode, async { status }, TIMEOUT, false)
+            .await
+            .unwrap();
+        assert!(!report.acp_capable);
+        assert_eq!(report.notes.len(), 2, "{:?}", report.notes);
+        assert!(report.notes[0].contains("acp"), "{:?}", report.notes);
+        assert!(report.notes[1].contains("0.15.0"), "{:?}", report.notes);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_opencode_help_lists_acp_subcommand() {
+        let dir = tempfile::tempdir().unwrap();
+        let opencode = crate::test_util::fake_executable(
+            dir.path(),
+            "opencode",
+            "echo 'Commands:'\n\
+             echo '  opencode acp               start ACP (Agent Client Protocol) server'\n\
+             echo '  opencode serve             starts a headless opencode server'",
+        );
+
+        let status = installed(opencode, Version::new(0, 15, 0));
+        let report = health_check_with(AgentKind::OpenCode, async { status }, TIMEOUT, false)
+            .await
+            .unwrap();
+        assert!(report.acp_capable, "{:?}", report.notes);
+        assert!(report.notes.is_empty(), "{:?}", report.notes);
+    }
+
+    #[test]
+    fn test_lists_marker() {
+        let help = "Usage: opencode [command]\n  opencode acp   start ACP server\n";
+        assert!(lists_marker(help, "opencode acp"));
+        assert!(!lists_marker("Talks acp to editors", "opencode acp"));
+        assert!(!lists_marker("  opencode acpx   other", "opencode acp"));
+        assert!(lists_marker("  -e, --acp, --experimental-acp", "--acp"));
+    }
+
+    #[tokio::test]
+    async fn test_agents_without_probe_are_capable() {
+        let status = installed(PathBuf::from("/usr/local/bin/codex"), Version::new(1, 0, 0));
+        let report = health_check_with(AgentKind::Codex, async { status }, TIMEOUT, false)
+            .await
+            .unwrap();
+        assert!(report.acp_capable);
+        assert_eq!(report.notes.len(), 1);
+        assert!(report.notes[0].contains("adapter"), "{:?}", report.notes);
+    }
+
+    #[tokio::test]
+    async fn test_missing_or_failed_detection() {
+        let report = health_check_with(
+            AgentKind::Gemini,
+            async { AgentStatus::NotInstalled },
+            TIMEOUT,
+            false,
+        )
+        .await
+        .unwrap();
+        assert!(!report.acp_capable);
+        assert_eq!(report.notes, ["Gemini CLI is not installed"]);
+
+        let unknown = AgentStatus::Unknown {
+            error: DetectionError::Timeout,
+            message: "timed out".to_string(),
+        };
+        let result = health_check_with(AgentKind::Gemini, async { unknown }, TIMEOUT, false).await;
+        assert_eq!(result, Err(DetectionError::Timeout));
+    }
+}
@@ -0,0 +1,211 @@
+//! Download progress from curl-based installers.
+//!
+//! The native install scripts are fetched with curl, which redraws its
+//! progress meter (or `--progress-bar`) on stderr. [`CurlProgress`] reads
+//! the percentage from those lines so `install()` can report how far a
+//! download is and estimate the time remaining.
+//!
+//! Only transfers whose progress reaches the installer's output can be
+//! tracked: the fetch of the install script itself, and the script's own
+//! download of the agent binary if the script shows curl's progress. A
+//! script that downloads silently reports no progress for that download.
+
+use crate::install::StructuredCommand;
+use regex::Regex;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Whether `cmd` runs a shell script fetched with curl.
+pub(super) fn is_curl_script(cmd: &StructuredCommand) -> bool {
+    matches!(cmd.program.as_str(), "bash" | "sh") && cmd.args.iter().any(|a| a.contains("curl"))
+}
+
+/// Progress of one curl transfer, reported by [`CurlProgress::advance`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct TransferProgress {
+    /// Percentage of the transfer completed.
+    pub(super) percent: f64,
+    /// Estimated time left, extrapolated from the transfer's start.
+    pub(super) estimated_remaining: Option<Duration>,
+}
+
+/// The percentage complete of curl transfers, tracked across output lines.
+///
+/// Within a transfer only increases are reported, so a stray line with a
+/// lower percentage never moves a progress bar backwards. Once a transfer
+/// reaches 100%, a lower percentage starts the next transfer (such as the
+/// binary download that follows the script fetch), whose time remaining
+/// is measured from its own start.
+pub(super) struct CurlProgress {
+    state: Mutex<Option<Transfer>>,
+}
+
+/// The transfer currently tracked by a [`CurlProgress`].
+struct Transfer {
+    /// When the transfer's first progress line was seen.
+    started: Instant,
+    /// Highest percentage seen so far.
+    best: f64,
+}
+
+impl CurlProgress {
+    /// Start tracking with no transfer seen yet.
+    pub(super) fn new() -> Self {
+        Self {
+            state: Mutex::new(None),
+        }
+    }
+
+    /// Read one output line, returning the progress if it advanced.
+    pub(super) fn advance(&self, line: &str) -> Option<TransferProgress> {
+        self.advance_at(line, Instant::now())
+    }
+
+    /// [`advance`](Self::advance) for a line read at `now`.
+    fn advance_at(&self, line: &str, now: Instant) -> Option<TransferProgress> {
+        let percent = parse_curl_percent(line)?;
+        let mut state = self.state.lock().unwrap();
+        let transfer = match state.as_mut() {
+            Some(transfer) if percent > transfer.best => transfer,
+            Some(transfer) if transfer.best < 100.0 || percent == 100.0 => return None,
+            _ => state.insert(Transfer {
+                started: now,
+                best: percent,
+            }),
+        };
+        transfer.best = percent;
+        Some(TransferProgress {
+            percent,
+            estimated_remaining: estimate_remaining(now - transfer.started, percent),
+        })
+    }
+}
+
+/// Extrapolate the remaining time from `elapsed` at `percent` complete.
+///
+/// Returns `None` before any progress, since nothing can be inferred yet.
+fn estimate_remaining(elapsed: Duration, percent: f64) -> Option<Duration> {
+    if !(percent > 0.0 && percent <= 100.0) {
+        return None;
+    }
+    Some(elapsed.mul_f64((100.0 - percent) / percent))
+}
+
+/// Parse the percentage from a curl progress line.
+///
+/// Understands the default progress meter, whose first column is the
+/// percentage of the total (` 45 52.3M   45 23.5M    0     0 ...`), and
+/// `--progress-bar` output (`######         12.5%`).
+fn parse_curl_percent(line: &str) -> Option<f64> {
+    static PATTERNS: OnceLock<(Regex, Regex)> = OnceLock::new();
+    let (meter, bar) = PATTERNS.get_or_init(|| {
+        (
+            Regex::new(r"^\s*(\d{1,3})\s+\d+(?:\.\d+)?[kMGT]?\s+\d{1,3}\s+\d+(?:\.\d+)?[kMGT]?\s")
+                .expect("Invalid curl meter regex"),
+            Regex::new(r"^[#\s]*#[#\s]*(\d{1,3}(?:\.\d+)?)%\s*$").expect("Invalid curl bar regex"),
+        )
+    });
+
+    let caps = meter.captures(line).or_else(|| bar.captures(line))?;
+    let percent: f64 = caps.get(1)?.as_str().parse().ok()?;
+    (percent <= 100.0).then_some(percent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_curl_percent() {
+        assert_eq!(
+            parse_curl_percent(
+                " 45 52.3M   45 23.5M    0     0  10.2M      0  0:00:05  0:00:02  0:00:03 10.2M"
+            ),
+            Some(45.0)
+        );
+        assert_eq!(
+            parse_curl_percent("########                                                   12.5%"),
+            Some(12.5)
+        );
+        assert_eq!(
+            parse_curl_percent("######################### 100.0%"),
+            Some(100.0)
+        );
+        assert_eq!(
+            parse_curl_percent("  % Total    % Received % Xferd  Average Speed   Time"),
+            None
+        );
+        assert_eq!(parse_curl_percent("Installing Claude Code 2.1.0"), None);
+        assert_eq!(parse_curl_percent("added 1 package, 100%"), None);
+    }
+
+    #[test]
+    fn test_progress_only_moves_forward() {
+        let progress = CurlProgress::new();
+        let lines = [
+            "  % Total    % Received % Xferd  Average Speed   Time    Time     Time  Current",
+            "  0     0    0     0    0     0      0      0 --:--:-- --:--:-- --:--:--     0",
+            " 10 52.3M   10 5.2M    0     0  5.0M      0  0:00:10  0:00:01  0:00:09 5.0M",
+            "#####                                                       8.0%",
+            " 10 52.3M   10 5.3M    0     0  5.0M      0  0:00:10  0:00:01  0:00:09 5.0M",
+            " 55 52.3M   55 28.8M    0     0  9.6M      0  0:00:05  0:00:03  0:00:02 9.6M",
+            "100 52.3M  100 52.3M    0     0  10.4M     0  0:00:05  0:00:05 --:--:-- 10.4M",
+        ];
+
+        let advanced: Vec<f64> = lines
+            .iter()
+            .filter_map(|line| progress.advance(line))
+            .map(|p| p.percent)
+            .collect();
+        assert_eq!(advanced, [0.0, 10.0, 55.0, 100.0]);
+    }
+
+    #[test]
+    fn test_next_transfer_after_completion() {
+        let progress = CurlProgress::new();
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        // The install script: done almost at once
+        let script = progress.advance_at("###### 100.0%", at(0)).unwrap();
+        assert_eq!(script.estimated_remaining, Some(Duration::ZERO));
+        // A repeated 100% doesn't start anything
+        assert_eq!(progress.advance_at("###### 100.0%", at(1)), None);
+
+        // The binary: timed from its own first line, not from the script
+        assert_eq!(
+            progress.advance_at("#      0.0%", at(10)).unwrap().percent,
+            0.0
+        );
+        let binary = progress.advance_at("###   25.0%", at(12)).unwrap();
+        assert_eq!(binary.percent, 25.0);
+        assert_eq!(binary.estimated_remaining, Some(Duration::from_secs(6)));
+        assert_eq!(progress.advance_at("##    20.0%", at(13)), None);
+    }
+
+    #[test]
+    fn test_estimate_remaining() {
+        let elapsed = Duration::from_secs(10);
+        assert_eq!(
+            estimate_remaining(elapsed, 25.0),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(estimate_remaining(elapsed, 100.0), Some(Duration::ZERO));
+        assert_eq!(estimate_remaining(elapsed, 0.0), None);
+    }
+
+    #[test]
+    fn test_is_curl_script() {
+        let cmd = |program: &str, arg: &str| StructuredCommand {
+            program: program.to_string(),
+            args: vec!["-c".to_string(), arg.to_string()],
+            env_vars: vec![],
+        };
+        assert!(is_curl_script(&cmd(
+            "bash",
+            "curl -fSL https://example.com/install.sh | bash"
+        )));
+        assert!(!is_curl_script(&cmd("npm", "curl")));
+        assert!(!is_curl_script(&cmd("bash", "./install.sh")));
+    }
+}
@@ -4,7 +4,12 @@
 //! Each error variant includes an actionable fix suggestion to help users
 //! resolve the issue.
 
-use crate::AgentKind;
+use crate::install::remediation::{
+    detect_node_manager, node_install_command, required_major, NodeManager,
+};
+use crate::install::StructuredCommand;
+use crate::{AgentKind, AgentStatus};
+use std::path::PathBuf;
 use std::time::Duration;
 use thiserror::Error;
 
@@ -115,6 +120,42 @@ pub enum InstallError {
     VerificationFailed {
         /// The agent that was being installed.
         agent: AgentKind,
+        /// Detection status from the last verification attempt.
+        status: Box<AgentStatus>,
+        /// A copy of the agent found in a directory that isn't on PATH, if any.
+        off_path: Option<PathBuf>,
+        /// Actionable suggestion for resolving the issue.
+        fix: String,
+    },
+
+    /// The install options are contradictory or invalid.
+    #[error("Invalid install options: {message}")]
+    InvalidOptions {
+        /// Description of the problem.
+        message: String,
+        /// Actionable suggestion for resolving the issue.
+        fix: String,
+    },
+
+    /// The installation was cancelled through the token passed to
+    /// `install_with_cancel()`.
+    ///
+    /// The installer is killed, so the agent may be partly installed.
+    #[error("Installation of {agent:?} was cancelled")]
+    Cancelled {
+        /// The agent that was being installed.
+        agent: AgentKind,
+        /// Actionable suggestion for resolving the issue.
+        fix: String,
+    },
+
+    /// The agent was still detected after its uninstall command ran.
+    #[error("Uninstall failed: {agent:?} is still present at {}", path.display())]
+    UninstallFailed {
+        /// The agent that was being uninstalled.
+        agent: AgentKind,
+        /// Where the agent was still found.
+        path: PathBuf,
         /// Actionable suggestion for resolving the issue.
         fix: String,
     },
@@ -158,9 +199,55 @@ impl InstallError {
             Self::Timeout { fix, .. } => fix,
             Self::InstallerFailed { fix, .. } => fix,
             Self::VerificationFailed { fix, .. } => fix,
+            Self::InvalidOptions { fix, .. } => fix,
+            Self::Cancelled { fix, .. } => fix,
+            Self::UninstallFailed { fix, .. } => fix,
             Self::UnsupportedPlatform { fix, .. } => fix,
         }
     }
+
+    /// Get an executable command that would fix a missing prerequisite.
+    ///
+    /// For `PrerequisiteMissing` and `PrerequisiteVersionMismatch` errors
+    /// about Node.js, this returns a command that installs Node.js with the
+    /// package manager found on the system (nvm, Homebrew, winget or apt,
+    /// in that order of preference). Returns `None` for other errors, other
+    /// prerequisites, or when no supported package manager is found.
+    ///
+    /// The command is not run. As with `install()`, the caller's UI should
+    /// confirm with the user before executing it.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rig_acp_discovery::{AgentKind, can_install};
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn main() {
+    ///     if let Err(e) = can_install(AgentKind::Codex).await {
+    ///         if let Some(cmd) = e.remediation() {
+    ///             println!("Run: {} {}", cmd.program, cmd.args.join(" "));
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn remediation(&self) -> Option<StructuredCommand> {
+        self.remediation_with(detect_node_manager())
+    }
+
+    /// [`remediation`](Self::remediation) with a given package manager.
+    fn remediation_with(&self, manager: Option<NodeManager>) -> Option<StructuredCommand> {
+        let (name, required) = match self {
+            Self::PrerequisiteMissing { name, .. } => (name, name),
+            Self::PrerequisiteVersionMismatch { name, required, .. } => (name, required),
+            _ => return None,
+        };
+        if !name.contains("Node.js") {
+            return None;
+        }
+
+        Some(node_install_command(&manager?, required_major(required)))
+    }
 }
 
 #[cfg(test)]
@@ -224,6 +311,8 @@ mod tests {
             },
             InstallError::VerificationFailed {
                 agent: AgentKind::ClaudeCode,
+                status: Box::new(AgentStatus::NotInstalled),
+                off_path: None,
                 fix: "Check PATH and restart terminal".to_string(),
             },
             InstallError::UnsupportedPlatform {
@@ -269,6 +358,8 @@ mod tests {
     fn test_verification_failed_display() {
         let error = InstallError::VerificationFailed {
             agent: AgentKind::ClaudeCode,
+            status: Box::new(AgentStatus::NotInstalled),
+            off_path: None,
             fix: "Check PATH".to_string(),
         };
         assert!(error.to_string().contains("Verification failed"));
@@ -282,4 +373,53 @@ mod tests {
         };
         assert!(error.to_string().contains("Platform not supported"));
     }
+
+    #[test]
+    fn test_remediation_for_missing_node() {
+        let error = InstallError::PrerequisiteMissing {
+            name: "Node.js 18+".to_string(),
+            install_url: Some("https://nodejs.org".to_string()),
+            fix: "Install Node.js 18+".to_string(),
+        };
+
+        let cmd = error.remediation_with(Some(NodeManager::Brew)).unwrap();
+        assert_eq!(cmd.program, "brew");
+        assert_eq!(cmd.args, ["install", "node"]);
+
+        let nvm = NodeManager::Nvm(std::path::PathBuf::from("/home/u/.nvm"));
+        let cmd = error.remediation_with(Some(nvm)).unwrap();
+        assert!(cmd.args[1].ends_with("nvm install 18"));
+
+        // No package manager available
+        assert!(error.remediation_with(None).is_none());
+    }
+
+    #[test]
+    fn test_remediation_for_node_version_mismatch() {
+        let error = InstallError::PrerequisiteVersionMismatch {
+            name: "Node.js 20+".to_string(),
+            required: "20+".to_string(),
+            found: "18.19".to_string(),
+            fix: "Upgrade Node.js to version 20+".to_string(),
+        };
+        let nvm = NodeManager::Nvm(std::path::PathBuf::from("/home/u/.nvm"));
+        let cmd = error.remediation_with(Some(nvm)).unwrap();
+        assert!(cmd.args[1].ends_with("nvm install 20"));
+    }
+
+    #[test]
+    fn test_no_remediation_for_other_errors() {
+        let error = InstallError::PrerequisiteMissing {
+            name: "winget".to_string(),
+            install_url: None,
+            fix: "Install winget".to_string(),
+        };
+        assert!(error.remediation_with(Some(NodeManager::Brew)).is_none());
+
+        let error = InstallError::Timeout {
+            duration: Duration::from_secs(300),
+            fix: "Try again".to_string(),
+        };
+        assert!(error.remediation_with(Some(NodeManager::Brew)).is_none());
+    }
 }
@@ -3,19 +3,47 @@
 //! This module provides the main [`install`] function that executes agent
 //! installation with progress reporting, timeout handling, and verification.
 
-use crate::install::{InstallError, InstallOptions, InstallProgress};
-use crate::{detect, AgentKind};
+use crate::detect::detect_install_method;
+#[cfg(feature = "network")]
+use crate::detection::parse_version;
+use crate::detection::{find_executable, find_off_path};
+use crate::fixes;
+use crate::install::download::{is_curl_script, CurlProgress};
+use crate::install::info::update_command;
+use crate::install::lock::InstallLocks;
+use crate::install::marker::{marker_dir, with_marker};
+use crate::install::session::non_interactive_env;
+use crate::install::{
+    verify_install, InstallError, InstallInfo, InstallMethod, InstallOptions, InstallOutcome,
+    InstallProgress, NpmSummary, Registry, StructuredCommand,
+};
+use crate::{detect, AgentKind, AgentStatus, InstallMethodKind};
+use futures::future::{select, Either};
+use futures::stream::StreamExt;
+#[cfg(feature = "network")]
+use semver::Version;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::pin;
 use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt};
 use tokio::process::Command;
-use tokio::time::timeout;
+use tokio::time::{interval_at, timeout, Instant};
+use tokio_util::sync::CancellationToken;
 
 /// Install an agent programmatically.
 ///
 /// This function:
 /// 1. Runs pre-flight checks (can_install)
 /// 2. Reports progress via callback
-/// 3. Executes the installer command with timeout
-/// 4. Verifies installation via detect()
+/// 3. Chooses the primary install method, or the first alternative whose
+///    program is available if the primary's isn't
+/// 4. Executes the installer command with timeout
+/// 5. Verifies installation via detect() and the agent's verification
+///    step (unless `skip_verification` is set)
 ///
 /// # Arguments
 ///
@@ -25,7 +53,7 @@ use tokio::time::timeout;
 ///
 /// # Returns
 ///
-/// - `Ok(())` if installation and verification succeeded
+/// - `Ok(InstallOutcome)` if installation and verification succeeded
 /// - `Err(InstallError)` with actionable fix suggestion if failed
 ///
 /// # Consent Model
@@ -33,6 +61,13 @@ use tokio::time::timeout;
 /// Calling this function IS consent to install. The caller's UI
 /// is responsible for confirming with the user before calling.
 ///
+/// # Concurrency
+///
+/// Several installs can run at once. Installers that use the same package
+/// manager are serialized, since concurrent `npm install -g` runs can
+/// corrupt the global package store; unrelated installers (for example a
+/// native install script and npm) still run in parallel.
+///
 /// # Example
 ///
 /// ```rust,no_run
@@ -47,7 +82,7 @@ use tokio::time::timeout;
 ///     ).await;
 ///
 ///     match result {
-///         Ok(()) => println!("Installed successfully!"),
+///         Ok(outcome) => println!("Installed {:?} successfully!", outcome.version),
 ///         Err(e) => println!("Failed: {}. Fix: {}", e, e.fix_suggestion()),
 ///     }
 /// }
@@ -56,43 +91,747 @@ pub async fn install<F>(
     kind: AgentKind,
     options: InstallOptions,
     on_progress: F,
-) -> Result<(), InstallError>
+) -> Result<InstallOutcome, InstallError>
+where
+    F: Fn(InstallProgress) + Send + Sync,
+{
+    let info = kind.install_info();
+    install_with(kind, &info, None, options, &on_progress, || {
+        verify_install(kind)
+    })
+    .await
+}
+
+/// Install an agent using a specific install method.
+///
+/// Like [`install`], but runs the method at `method_index` instead of
+/// choosing one: 0 is [`InstallInfo::primary`] and `1..=N` index into
+/// [`InstallInfo::alternatives`], matching the indices from
+/// [`InstallInfo::methods`]. The method's own prerequisites are checked
+/// instead of the agent's. There is no fallback if the method's program
+/// is missing.
+///
+/// Returns `InstallError::InstallerFailed` if `method_index` is out of
+/// range.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use rig_acp_discovery::{AgentKind, InstallOptions, install_with_method};
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() {
+///     // Let the user pick from every available method
+///     let info = AgentKind::OpenCode.install_info();
+///     for method in info.methods() {
+///         println!("{}: {}", method.index, method.method.description);
+///     }
+///
+///     let npm = info
+///         .methods()
+///         .into_iter()
+///         .find(|m| m.method.command.program == "npm")
+///         .unwrap();
+///     let result = install_with_method(
+///         AgentKind::OpenCode,
+///         npm.index,
+///         InstallOptions::default(),
+///         |progress| println!("{:?}", progress),
+///     )
+///     .await;
+///     println!("{:?}", result.map(|outcome| outcome.version));
+/// }
+/// ```
+pub async fn install_with_method<F>(
+    kind: AgentKind,
+    method_index: usize,
+    options: InstallOptions,
+    on_progress: F,
+) -> Result<InstallOutcome, InstallError>
+where
+    F: Fn(InstallProgress) + Send + Sync,
+{
+    let info = kind.install_info();
+    install_with(
+        kind,
+        &info,
+        Some(method_index),
+        options,
+        &on_progress,
+        || verify_install(kind),
+    )
+    .await
+}
+
+/// Install an agent, stopping early if `token` is cancelled.
+///
+/// Behaves like [`install`] until `token` is cancelled, at which point the
+/// install is abandoned and `InstallError::Cancelled` is returned. A
+/// running installer is killed and reaped. If the token is already
+/// cancelled, nothing runs and no progress is reported.
+///
+/// A cancelled install may leave the agent partly installed; with
+/// `InstallOptions::write_marker` set, `had_interrupted_install()` reports
+/// it afterward.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use rig_acp_discovery::{install_with_cancel, AgentKind, InstallOptions};
+/// use tokio_util::sync::CancellationToken;
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() {
+///     let token = CancellationToken::new();
+///
+///     // Cancel from elsewhere, e.g. when the user presses Esc
+///     let cancel = token.clone();
+///     tokio::spawn(async move { cancel.cancel() });
+///
+///     let result = install_with_cancel(
+///         AgentKind::Codex,
+///         InstallOptions::default(),
+///         token,
+///         |progress| println!("{:?}", progress),
+///     )
+///     .await;
+///     println!("{:?}", result.map(|outcome| outcome.version));
+/// }
+/// ```
+pub async fn install_with_cancel<F>(
+    kind: AgentKind,
+    options: InstallOptions,
+    token: CancellationToken,
+    on_progress: F,
+) -> Result<InstallOutcome, InstallError>
+where
+    F: Fn(InstallProgress) + Send + Sync,
+{
+    let cancelled = || InstallError::Cancelled {
+        agent: kind,
+        fix: fixes::render(fixes::INSTALL_CANCELLED, &[("agent", &kind.display_name())]),
+    };
+    if token.is_cancelled() {
+        return Err(cancelled());
+    }
+
+    // Boxed: the install future is too large to pin on the stack
+    let install = Box::pin(install(kind, options, on_progress));
+    // Dropping the losing install future kills the installer (kill_on_drop)
+    match select(pin!(token.cancelled()), install).await {
+        Either::Left(_) => Err(cancelled()),
+        Either::Right((result, _)) => result,
+    }
+}
+
+/// Install several agents, running up to `options.max_parallel` at once.
+///
+/// Each agent is installed as by [`install`] with a copy of `options`.
+/// Installers that use the same package manager are still serialized, so
+/// a higher limit only helps installs with unrelated installers.
+/// `on_progress` receives the agent alongside each event, which suits
+/// [`ProgressAggregator`](crate::ProgressAggregator).
+///
+/// Returns one result per agent, in the order of `kinds`. A failed install
+/// doesn't stop the others.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use rig_acp_discovery::{install_many, AgentKind, InstallOptions};
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() {
+///     let results = install_many(
+///         &[AgentKind::Codex, AgentKind::Gemini],
+///         InstallOptions::default(),
+///         |agent, progress| println!("{}: {}", agent.display_name(), progress.description()),
+///     )
+///     .await;
+///
+///     for (agent, result) in results {
+///         if let Err(e) = result {
+///             println!("{} failed: {}", agent.display_name(), e);
+///         }
+///     }
+/// }
+/// ```
+pub async fn install_many<F>(
+    kinds: &[AgentKind],
+    options: InstallOptions,
+    on_progress: F,
+) -> Vec<(AgentKind, Result<InstallOutcome, InstallError>)>
+where
+    F: Fn(AgentKind, InstallProgress) + Send + Sync,
+{
+    let on_progress = &on_progress;
+    install_many_with(kinds, options.effective_max_parallel(), |kind| {
+        // Boxed: the install future is too large to keep several on the stack
+        Box::pin(install(kind, options.clone(), move |progress| {
+            on_progress(kind, progress)
+        }))
+    })
+    .await
+}
+
+/// [`install_many`] with an injectable install.
+async fn install_many_with<I, Fut>(
+    kinds: &[AgentKind],
+    max_parallel: usize,
+    install: I,
+) -> Vec<(AgentKind, Result<InstallOutcome, InstallError>)>
+where
+    I: Fn(AgentKind) -> Fut,
+    Fut: Future<Output = Result<InstallOutcome, InstallError>>,
+{
+    futures::stream::iter(kinds.iter().map(|&kind| {
+        let install = install(kind);
+        async move { (kind, install.await) }
+    }))
+    .buffered(max_parallel.max(1))
+    .collect()
+    .await
+}
+
+/// Shared implementation of [`install`], [`install_with_method`] and [`update`].
+///
+/// The method is `info`'s method at `method_index`, or with no index the
+/// one chosen by [`select_method`]. The installation is verified with
+/// `verify`.
+async fn install_with<F, V, Fut>(
+    kind: AgentKind,
+    info: &InstallInfo,
+    method_index: Option<usize>,
+    options: InstallOptions,
+    on_progress: &F,
+    verify: V,
+) -> Result<InstallOutcome, InstallError>
 where
     F: Fn(InstallProgress) + Send + Sync,
+    V: Fn() -> Fut,
+    Fut: Future<Output = AgentStatus>,
 {
     // Step 1: Report Started
     on_progress(InstallProgress::Started { agent: kind });
 
     // Step 2: Pre-flight check
     on_progress(InstallProgress::CheckingPrerequisites);
-    super::prereq::can_install(kind).await?;
+    validate_options(&options)?;
+    let chosen = match method_index {
+        Some(index) => {
+            let method = method_at(kind, info, index)?;
+            super::prereq::can_install_method(kind, method).await?;
+            Some(method)
+        }
+        None => {
+            // An overridden command doesn't need any method's program
+            let override_set = options.command_override.is_some();
+            super::prereq::can_install_with_programs(kind, |program| {
+                override_set || find_executable(program).is_some()
+            })
+            .await?;
+            None
+        }
+    };
 
-    // Step 3: Get install info and build command
-    let info = kind.install_info();
-    let cmd = &info.primary.command;
+    // Step 3: Choose an install method and resolve its command
+    let method = if options.command_override.is_some() {
+        &info.primary
+    } else {
+        let method = match chosen {
+            Some(method) => method,
+            None => select_method(kind, info, |program| find_executable(program).is_some())?,
+        };
+        on_progress(InstallProgress::UsingMethod {
+            agent: kind,
+            description: method.description.clone(),
+        });
+        method
+    };
+    let cmd = resolve_command(method, &options);
 
-    let mut command = Command::new(&cmd.program);
-    command
-        .args(&cmd.args)
-        .envs(cmd.env_vars.iter().cloned())
-        .kill_on_drop(true)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped());
+    #[cfg(feature = "network")]
+    if let Some(progress) = resolved_progress(&cmd, npm_view_version).await {
+        on_progress(progress);
+    }
 
-    // Step 4: Report Installing and execute with timeout
+    // Step 4: Report Installing and run the installer
     on_progress(InstallProgress::Installing { agent: kind });
 
-    let result = timeout(options.timeout, command.output()).await;
+    let run = async {
+        let log = run_installer_with_retries(kind, &cmd, &options, on_progress).await?;
+
+        // Steps 5-6: Verify and report completion
+        finish_install(kind, &options, log, on_progress, verify).await
+    };
+    if options.write_marker {
+        with_marker(&marker_dir(), kind, run).await
+    } else {
+        run.await
+    }
+}
+
+/// Update an installed agent to its latest version.
+///
+/// This function:
+/// 1. Detects the installed agent and its current version
+/// 2. Reinstalls it with the method that installed it, with npm methods
+///    pulling the `latest` dist-tag unless `options.version` or
+///    `options.dist_tag` is set
+/// 3. Re-detects the agent and reports `InstallProgress::Updated` with the
+///    old and new versions after `Completed`
+///
+/// The agent is updated the way it was installed, judged from the
+/// detected executable's location: a copy under a global `node_modules`
+/// is updated through the npm method, a Homebrew copy through the brew
+/// method, and so on, so the update replaces the copy on PATH instead of
+/// adding a second one. Claude Code's native installer doesn't update in
+/// place, so a native install, or one whose origin can't be told (such as
+/// `/opt/claude/claude`), is updated with `claude update` using the
+/// detected executable. Any other agent whose origin can't be told is
+/// updated with the method [`install`] would choose.
+/// `options.command_override` replaces the update command as it does the
+/// install command.
+///
+/// Returns `InstallError::VerificationFailed` if the agent isn't installed,
+/// with a fix suggesting to install it first, and
+/// `InstallError::InstallerFailed` if it was installed by a package manager
+/// none of its install methods use (such as mise). With
+/// `skip_verification` set, the new version is not re-detected and is
+/// reported as `None`.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use rig_acp_discovery::{update, AgentKind, InstallOptions, InstallProgress};
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() {
+///     let result = update(
+///         AgentKind::Codex,
+///         InstallOptions::default(),
+///         |progress| {
+///             if let InstallProgress::Updated { from, to, .. } = progress {
+///                 println!("Updated from {:?} to {:?}", from, to);
+///             }
+///         },
+///     )
+///     .await;
+///
+///     if let Err(e) = result {
+///         println!("Update failed: {}. Fix: {}", e, e.fix_suggestion());
+///     }
+/// }
+/// ```
+pub async fn update<F>(
+    kind: AgentKind,
+    options: InstallOptions,
+    on_progress: F,
+) -> Result<InstallOutcome, InstallError>
+where
+    F: Fn(InstallProgress) + Send + Sync,
+{
+    update_with(
+        kind,
+        options,
+        &on_progress,
+        || detect(kind),
+        || verify_install(kind),
+    )
+    .await
+}
+
+/// [`update`] with an injectable detection before the update and
+/// verification after it.
+async fn update_with<F, D, DFut, V, VFut>(
+    kind: AgentKind,
+    options: InstallOptions,
+    on_progress: &F,
+    detect: D,
+    verify: V,
+) -> Result<InstallOutcome, InstallError>
+where
+    F: Fn(InstallProgress) + Send + Sync,
+    D: FnOnce() -> DFut,
+    DFut: Future<Output = AgentStatus>,
+    V: Fn() -> VFut,
+    VFut: Future<Output = AgentStatus>,
+{
+    let before = detect().await;
+    let Some(path) = before.path() else {
+        let fix = fixes::render(
+            fixes::UPDATE_NOT_INSTALLED,
+            &[
+                ("agent", &kind.display_name()),
+                ("command", &kind.install_info().primary.raw_command),
+            ],
+        );
+        return Err(InstallError::VerificationFailed {
+            agent: kind,
+            status: Box::new(before),
+            off_path: None,
+            fix,
+        });
+    };
+    let from = before.version().cloned();
+
+    let mut options = update_options(options);
+    let mut method_index = None;
+    let info = kind.install_info();
+    if options.command_override.is_none() {
+        match update_route(kind, path, &info, |program| {
+            find_executable(program).is_some()
+        })? {
+            UpdateRoute::SelfUpdate(cmd) => options.command_override = Some(cmd),
+            UpdateRoute::Reinstall { index, .. } => method_index = Some(index),
+        }
+    }
+
+    let outcome = install_with(kind, &info, method_index, options, on_progress, verify).await?;
+    on_progress(InstallProgress::Updated {
+        agent: kind,
+        from,
+        to: outcome.version.clone(),
+    });
+    Ok(outcome)
+}
+
+/// How [`update`] brings an installed agent up to date.
+#[derive(Debug)]
+pub(crate) enum UpdateRoute {
+    /// Run the agent's own update command.
+    SelfUpdate(StructuredCommand),
+    /// Run an install method again; `index` is its index in
+    /// [`InstallInfo::methods`].
+    Reinstall { index: usize },
+}
+
+/// Choose how [`update`] updates `kind` installed at `path`.
+///
+/// The install method is judged from `path`. A native or unknown-origin
+/// install of an agent with its own update command uses it, run from
+/// `path`. Otherwise the method whose registry matches the one that
+/// installed the agent is rerun, and an install whose origin is unknown
+/// falls back to [`select_method`] with `available`.
+pub(crate) fn update_route(
+    kind: AgentKind,
+    path: &Path,
+    info: &InstallInfo,
+    available: impl Fn(&str) -> bool,
+) -> Result<UpdateRoute, InstallError> {
+    let installed_by = detect_install_method(path);
+    if matches!(installed_by, None | Some(InstallMethodKind::Native)) {
+        if let Some(cmd) = update_command(kind, path) {
+            return Ok(UpdateRoute::SelfUpdate(cmd));
+        }
+    }
+
+    let mut methods = std::iter::once(&info.primary)
+        .chain(&info.alternatives)
+        .enumerate();
+    let Some(installed_by) = installed_by else {
+        let chosen = select_method(kind, info, available)?;
+        let (index, _) = methods
+            .find(|(_, method)| std::ptr::eq(*method, chosen))
+            .expect("select_method returns one of the agent's methods");
+        return Ok(UpdateRoute::Reinstall { index });
+    };
+
+    let registries = installed_registries(&installed_by);
+    methods
+        .find(|(_, method)| method.registry().is_some_and(|r| registries.contains(&r)))
+        .map(|(index, _)| UpdateRoute::Reinstall { index })
+        .ok_or_else(|| InstallError::InstallerFailed {
+            message: format!(
+                "{} was installed with {}, which none of its install methods use",
+                kind.display_name(),
+                installed_by
+            ),
+            exit_code: None,
+            stdout: None,
+            stderr: None,
+            fix: fixes::render(
+                fixes::UPDATE_UNSUPPORTED_METHOD,
+                &[("agent", &kind.display_name()), ("method", &installed_by)],
+            ),
+        })
+}
+
+/// The install-method registries that install an agent the way
+/// `installed_by` did.
+///
+/// Empty for package managers no install method uses.
+pub(super) fn installed_registries(installed_by: &InstallMethodKind) -> &'static [Registry] {
+    match installed_by {
+        InstallMethodKind::Npm => &[Registry::Npm],
+        InstallMethodKind::Cargo => &[Registry::Cargo],
+        InstallMethodKind::Brew => &[Registry::Brew],
+        InstallMethodKind::Scoop => &[Registry::Scoop],
+        InstallMethodKind::Winget => &[Registry::Winget],
+        InstallMethodKind::Native => &[Registry::Curl, Registry::PowerShell],
+        _ => &[],
+    }
+}
+
+/// The command [`update`] runs for `kind` installed at `path`.
+///
+/// Chosen by the same [`update_route`] as `update()` itself, with default
+/// options: npm methods pull the `latest` dist-tag. Returns the error
+/// `update()` would fail with if no method can update the agent.
+pub(crate) fn update_plan_command(
+    kind: AgentKind,
+    path: &Path,
+    available: impl Fn(&str) -> bool,
+) -> Result<StructuredCommand, InstallError> {
+    let info = kind.install_info();
+    Ok(match update_route(kind, path, &info, available)? {
+        UpdateRoute::SelfUpdate(cmd) => cmd,
+        UpdateRoute::Reinstall { index } => {
+            let method = method_at(kind, &info, index)?;
+            resolve_command(method, &update_options(InstallOptions::default()))
+        }
+    })
+}
+
+/// `options` for an update: npm methods pull the `latest` dist-tag unless
+/// a version or dist-tag is set.
+fn update_options(mut options: InstallOptions) -> InstallOptions {
+    if options.version.is_none() && options.dist_tag.is_none() {
+        options.dist_tag = Some("latest".to_string());
+    }
+    options
+}
+
+/// Verify a completed installer run and build the outcome.
+///
+/// Verification uses `verify` unless `options.skip_verification` is set,
+/// in which case it is skipped entirely and the version is left unknown.
+/// An agent that is installed but not resolvable until a restart is
+/// reported as a success with `needs_restart` set.
+async fn finish_install<F, V, Fut>(
+    kind: AgentKind,
+    options: &InstallOptions,
+    log: String,
+    on_progress: &F,
+    verify: V,
+) -> Result<InstallOutcome, InstallError>
+where
+    F: Fn(InstallProgress),
+    V: Fn() -> Fut,
+    Fut: Future<Output = AgentStatus>,
+{
+    // Step 5: Verify installation
+    let (version, needs_restart) = if options.skip_verification {
+        (None, false)
+    } else {
+        on_progress(InstallProgress::Verifying { agent: kind });
+        let verified = verify_installation(
+            kind,
+            options.verify_attempts,
+            VERIFY_SETTLE_DELAY,
+            on_progress,
+            verify,
+        )
+        .await;
+        match verified {
+            Ok(status) => (status.version().cloned(), false),
+            Err(error) => {
+                restart_pending(error)?;
+                (None, true)
+            }
+        }
+    };
+
+    // Step 6: Report Completed
+    on_progress(InstallProgress::Completed { agent: kind });
+    Ok(InstallOutcome {
+        agent: kind,
+        version,
+        npm_summary: NpmSummary::parse(&log),
+        log: options.capture_log.then_some(log),
+        needs_restart,
+    })
+}
+
+/// Check whether a failed verification only needs a new shell to succeed.
+///
+/// On Windows, installers add their directory to the user PATH in the
+/// registry, which running processes never see. If verification couldn't
+/// resolve the agent by name but found its executable in a known install
+/// location, the install worked and only a restart is needed. Elsewhere an
+/// off-PATH copy means PATH itself needs fixing, so the error is returned.
+fn restart_pending(error: InstallError) -> Result<(), InstallError> {
+    match &error {
+        InstallError::VerificationFailed {
+            status,
+            off_path: Some(path),
+            ..
+        } if cfg!(windows) && matches!(**status, AgentStatus::NotInstalled) && path.is_file() => {
+            Ok(())
+        }
+        _ => Err(error),
+    }
+}
+
+/// Delay before each verification attempt, giving PATH changes time to settle.
+const VERIFY_SETTLE_DELAY: Duration = Duration::from_millis(500);
+
+/// Check that the agent is usable after installation, retrying if needed.
+///
+/// Makes up to `max_attempts` calls to `verify`, reporting `VerifyAttempt`
+/// progress, and returns the first usable status. The first attempt runs
+/// immediately, since installs into a directory already on PATH are
+/// detectable right away; `delay` is only waited before retries.
+///
+/// If no attempt succeeds, the returned `VerificationFailed` carries the
+/// last status and any copy of the agent found off PATH.
+async fn verify_installation<F, V, Fut>(
+    kind: AgentKind,
+    max_attempts: u32,
+    delay: Duration,
+    on_progress: &F,
+    verify: V,
+) -> Result<AgentStatus, InstallError>
+where
+    F: Fn(InstallProgress),
+    V: Fn() -> Fut,
+    Fut: Future<Output = AgentStatus>,
+{
+    let max = max_attempts.max(1);
+    let mut status = AgentStatus::NotInstalled;
+    for attempt in 1..=max {
+        on_progress(InstallProgress::VerifyAttempt { attempt, max });
+
+        // Not resolvable yet: give PATH changes a moment to settle
+        if attempt > 1 {
+            tokio::time::sleep(delay).await;
+        }
+
+        status = verify().await;
+        if status.is_usable() {
+            return Ok(status);
+        }
+    }
+
+    let off_path = match status {
+        AgentStatus::NotInstalled => find_off_path(kind.executable_name()),
+        _ => None,
+    };
+    Err(verification_failed(kind, status, off_path))
+}
+
+/// Build a `VerificationFailed` error with a fix specific to why detection failed.
+fn verification_failed(
+    kind: AgentKind,
+    status: AgentStatus,
+    off_path: Option<PathBuf>,
+) -> InstallError {
+    let agent = kind.display_name();
+    let fix = match (&status, &off_path) {
+        (AgentStatus::Unknown { message, .. }, _) => fixes::render(
+            fixes::VERIFY_UNKNOWN,
+            &[("agent", &agent), ("message", message)],
+        ),
+        (
+            AgentStatus::VersionMismatch {
+                found,
+                required,
+                path,
+            },
+            _,
+        ) => fixes::render(
+            fixes::VERIFY_VERSION_MISMATCH,
+            &[
+                ("agent", &agent),
+                ("path", &path.display()),
+                ("found", found),
+                ("required", required),
+            ],
+        ),
+        (_, Some(path)) => {
+            let dir = path.parent().unwrap_or(path);
+            fixes::render(
+                fixes::VERIFY_OFF_PATH,
+                &[("agent", &agent), ("dir", &dir.display())],
+            )
+        }
+        _ => fixes::render(fixes::VERIFY_NOT_FOUND, &[("agent", &agent)]),
+    };
+
+    InstallError::VerificationFailed {
+        agent: kind,
+        status: Box::new(status),
+        off_path,
+        fix,
+    }
+}
+
+/// Run the installer command with the configured timeout.
+///
+/// Output is read as it is produced and each stream is capped at
+/// `options.max_captured_output` bytes. The first output line that looks
+/// like a download (see [`is_download_line`]) reports
+/// `InstallProgress::Downloading` for `kind`. For curl-based scripts,
+/// every increase in curl's progress percentage reports it again with an
+/// estimate of the time remaining. Returns the installer's
+/// combined output (stdout followed by stderr) on success, or an
+/// `InstallError` classifying the failure.
+pub(super) async fn run_installer(
+    kind: AgentKind,
+    cmd: &StructuredCommand,
+    options: &InstallOptions,
+    on_progress: &impl Fn(InstallProgress),
+) -> Result<String, InstallError> {
+    let mut command = build_command(cmd, options);
+
+    let downloading = AtomicBool::new(false);
+    let curl = is_curl_script(cmd).then(CurlProgress::new);
+    let on_line = |line: &str| {
+        if let Some(transfer) = curl.as_ref().and_then(|curl| curl.advance(line)) {
+            downloading.store(true, Ordering::Relaxed);
+            on_progress(InstallProgress::Downloading {
+                agent: kind,
+                percent: Some(transfer.percent),
+                estimated_remaining: transfer.estimated_remaining,
+            });
+        } else if is_download_line(line) && !downloading.swap(true, Ordering::Relaxed) {
+            on_progress(InstallProgress::Downloading {
+                agent: kind,
+                percent: None,
+                estimated_remaining: None,
+            });
+        }
+    };
+
+    let result = match command.spawn() {
+        Ok(mut child) => {
+            let limit = options.max_captured_output;
+            let stdout = child.stdout.take();
+            let stderr = child.stderr.take();
+            let run = async {
+                let (stdout, stderr) = futures::join!(
+                    read_capped(stdout, limit, &on_line),
+                    read_capped(stderr, limit, &on_line)
+                );
+                let status = child.wait().await?;
+                Ok((status, stdout, stderr))
+            };
+            let run = with_heartbeat(kind, run, options.heartbeat_interval, on_progress);
+            // On timeout the child is dropped, which kills it
+            timeout(options.timeout, run).await
+        }
+        Err(e) => Ok(Err(e)),
+    };
 
-    // Step 5: Handle timeout and execution result
-    let output = match result {
+    // Handle timeout and execution result
+    let (status, stdout, stderr) = match result {
         Ok(Ok(output)) => output,
         Ok(Err(e)) => {
             // Check for permission denied
             if e.kind() == std::io::ErrorKind::PermissionDenied {
                 return Err(InstallError::PermissionDenied {
                     message: e.to_string(),
-                    fix: "Try running with appropriate permissions".to_string(),
+                    fix: fixes::INSTALLER_PERMISSION_DENIED.to_string(),
                 });
             }
             return Err(InstallError::InstallerFailed {
@@ -100,25 +839,25 @@ where
                 exit_code: None,
                 stdout: None,
                 stderr: None,
-                fix: "Check the command and try again".to_string(),
+                fix: fixes::INSTALLER_SPAWN_FAILED.to_string(),
             });
         }
         Err(_) => {
             return Err(InstallError::Timeout {
                 duration: options.timeout,
-                fix: format!(
-                    "Installation timed out after {:?}. Try with a longer timeout or check network.",
-                    options.timeout
+                fix: fixes::render(
+                    fixes::INSTALL_TIMEOUT,
+                    &[("duration", &format!("{:?}", options.timeout))],
                 ),
             });
         }
     };
 
-    // Step 6: Check exit status
-    if !output.status.success() {
-        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+    let stdout = stdout.into_text(options.max_captured_output);
+    let stderr = stderr.into_text(options.max_captured_output);
 
+    // Check exit status
+    if !status.success() {
         // Detect network errors from stderr
         let is_network = stderr.contains("network")
             || stderr.contains("connection")
@@ -130,56 +869,486 @@ where
             return Err(InstallError::Network {
                 message: "Network error during installation".to_string(),
                 stderr: Some(stderr),
-                fix: "Check your internet connection and try again".to_string(),
+                fix: fixes::NETWORK.to_string(),
             });
         }
 
         return Err(InstallError::InstallerFailed {
-            message: format!("Installer exited with code {:?}", output.status.code()),
-            exit_code: output.status.code(),
+            message: format!("Installer exited with code {:?}", status.code()),
+            exit_code: status.code(),
             stdout: Some(stdout),
             stderr: Some(stderr),
-            fix: "See installer output above for details".to_string(),
+            fix: fixes::INSTALLER_FAILED.to_string(),
         });
     }
 
-    // Step 7: Verify installation
-    on_progress(InstallProgress::Verifying { agent: kind });
-
-    // Small delay for PATH to potentially update
-    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    Ok(stdout + &stderr)
+}
 
-    let status = detect(kind).await;
-    if !status.is_usable() {
-        return Err(InstallError::VerificationFailed {
-            agent: kind,
-            fix: "Installation completed but agent not found. You may need to restart your terminal for PATH changes to take effect.".to_string(),
-        });
+/// [`run_installer`], rerun after network errors up to `options.max_retries` times.
+///
+/// Retry `n` is reported as `InstallProgress::Retrying` and waits
+/// `options.retry_backoff * n` first. Other errors are returned at once.
+///
+/// Installs sharing a package manager (such as two `npm -g` runs) take
+/// turns, one attempt at a time: the lock is released during the wait, so
+/// another install can run while this one backs off.
+async fn run_installer_with_retries(
+    kind: AgentKind,
+    cmd: &StructuredCommand,
+    options: &InstallOptions,
+    on_progress: &impl Fn(InstallProgress),
+) -> Result<String, InstallError> {
+    let mut attempt = 0;
+    loop {
+        // Boxed: the installer future is too large to nest on the stack
+        let result = InstallLocks::global()
+            .run(
+                cmd,
+                Box::pin(run_installer(kind, cmd, options, on_progress)),
+            )
+            .await;
+        match result {
+            Err(InstallError::Network { message, .. }) if attempt < options.max_retries => {
+                attempt += 1;
+                tracing::debug!(
+                    "{} install hit a network error ({}); retry {} of {}",
+                    kind.display_name(),
+                    message,
+                    attempt,
+                    options.max_retries
+                );
+                tokio::time::sleep(options.retry_backoff.saturating_mul(attempt)).await;
+                on_progress(InstallProgress::Retrying {
+                    agent: kind,
+                    attempt,
+                    max: options.max_retries,
+                });
+            }
+            result => return result,
+        }
     }
+}
 
-    // Step 8: Report Completed
-    on_progress(InstallProgress::Completed { agent: kind });
-    Ok(())
+/// Output captured from one installer stream.
+struct CapturedOutput {
+    /// The last bytes of the stream, at most the capture limit.
+    bytes: VecDeque<u8>,
+    truncated: bool,
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::sync::{Arc, Mutex};
+impl CapturedOutput {
+    /// Decode the output, noting where earlier output was dropped.
+    fn into_text(mut self, limit: usize) -> String {
+        let tail = String::from_utf8_lossy(self.bytes.make_contiguous()).into_owned();
+        if self.truncated {
+            format!("[output truncated; last {} bytes kept]\n{}", limit, tail)
+        } else {
+            tail
+        }
+    }
+}
 
-    #[tokio::test]
-    async fn test_install_progress_callback() {
-        // Verify callback is called with expected progress stages
-        let stages = Arc::new(Mutex::new(Vec::new()));
-        let stages_clone = stages.clone();
+/// Longest prefix of an output line kept for [`is_download_line`].
+const MAX_SCANNED_LINE: usize = 512;
 
-        // Run install - it will fail at some point but should call callback
-        let _ = install(
-            AgentKind::ClaudeCode,
-            InstallOptions::default(),
-            move |progress| {
-                stages_clone.lock().unwrap().push(format!("{:?}", progress));
-            },
+/// Read a stream to the end, keeping its last `limit` bytes.
+///
+/// Installers print their errors last (npm's `ETIMEDOUT` or `ENOTFOUND`
+/// lines follow pages of log output), so the tail is kept and earlier
+/// output is dropped once the limit is reached. The stream is always
+/// drained so the child never blocks on a full pipe. Every line is passed
+/// to `on_line` as it arrives. Lines end at `\n` or `\r` (curl redraws its
+/// progress meter with `\r`) and only their first [`MAX_SCANNED_LINE`]
+/// bytes are passed.
+async fn read_capped<R: AsyncRead + Unpin>(
+    reader: Option<R>,
+    limit: usize,
+    on_line: &impl Fn(&str),
+) -> CapturedOutput {
+    let mut captured = CapturedOutput {
+        bytes: VecDeque::new(),
+        truncated: false,
+    };
+    let Some(mut reader) = reader else {
+        return captured;
+    };
+
+    let mut chunk = [0u8; 8192];
+    let mut line = Vec::new();
+    loop {
+        let n = match reader.read(&mut chunk).await {
+            Ok(0) | Err(_) => break,
+            Ok(n) => n,
+        };
+        let kept = &chunk[n.saturating_sub(limit)..n];
+        let overflow = (captured.bytes.len() + kept.len()).saturating_sub(limit);
+        captured.bytes.drain(..overflow);
+        captured.bytes.extend(kept);
+        captured.truncated |= overflow > 0 || kept.len() < n;
+
+        for &byte in &chunk[..n] {
+            if byte == b'\n' || byte == b'\r' {
+                if !line.is_empty() {
+                    on_line(&String::from_utf8_lossy(&line));
+                    line.clear();
+                }
+            } else if line.len() < MAX_SCANNED_LINE {
+                line.push(byte);
+            }
+        }
+    }
+    if !line.is_empty() {
+        on_line(&String::from_utf8_lossy(&line));
+    }
+    captured
+}
+
+/// Check whether an installer output line shows a download in progress.
+///
+/// Matches npm's `http fetch` log lines, curl's progress meter and the
+/// "Downloading" messages printed by the native install scripts.
+fn is_download_line(line: &str) -> bool {
+    let line = line.to_ascii_lowercase();
+    ["download", "http fetch", "% total", "% received"]
+        .iter()
+        .any(|indicator| line.contains(indicator))
+}
+
+/// Run `future`, reporting a `Heartbeat` every `interval` until it completes.
+///
+/// Installers can go quiet for minutes; the heartbeat lets a UI show that
+/// the install is still running. With no interval, `future` runs as is.
+async fn with_heartbeat<T>(
+    kind: AgentKind,
+    future: impl Future<Output = T>,
+    interval: Option<Duration>,
+    on_progress: &impl Fn(InstallProgress),
+) -> T {
+    let Some(period) = interval.filter(|period| !period.is_zero()) else {
+        return future.await;
+    };
+
+    let start = Instant::now();
+    let mut ticks = interval_at(start + period, period);
+    let mut future = pin!(future);
+    loop {
+        match select(future.as_mut(), pin!(ticks.tick())).await {
+            Either::Left((output, _)) => return output,
+            Either::Right(_) => on_progress(InstallProgress::Heartbeat {
+                agent: kind,
+                elapsed: start.elapsed(),
+            }),
+        }
+    }
+}
+
+/// Resolve the environment variables `install()` would set for an agent.
+///
+/// Returns the variables applied on top of the inherited environment when
+/// installing `kind` with `options`: the installer's non-interactive
+/// settings (such as `npm_config_yes`), the install method's own
+/// `env_vars`, then any overrides derived from the options (such as
+/// `NPM_CONFIG_PREFIX` from `npm_prefix`). The method is the one
+/// `install()` would choose, so an alternative is reported when the
+/// primary method's program is missing. Nothing is executed.
+///
+/// # Example
+///
+/// ```rust
+/// use rig_acp_discovery::{AgentKind, InstallOptions, install_environment};
+///
+/// let options = InstallOptions {
+///     npm_prefix: Some("/opt/agents".into()),
+///     ..Default::default()
+/// };
+/// for (key, value) in install_environment(AgentKind::Codex, &options) {
+///     println!("{}={}", key, value);
+/// }
+/// ```
+pub fn install_environment(kind: AgentKind, options: &InstallOptions) -> Vec<(String, String)> {
+    install_environment_with(kind, &kind.install_info(), options, |program| {
+        find_executable(program).is_some()
+    })
+}
+
+/// [`install_environment`] for explicit install info and program lookup.
+///
+/// With no method's program available `install()` fails before running
+/// anything; the primary method's environment is reported then.
+fn install_environment_with(
+    kind: AgentKind,
+    info: &InstallInfo,
+    options: &InstallOptions,
+    available: impl Fn(&str) -> bool,
+) -> Vec<(String, String)> {
+    let method = select_method(kind, info, available).unwrap_or(&info.primary);
+    resolved_env(&resolve_command(method, options), options)
+}
+
+/// The install method at `index`: 0 for primary, `1..=N` for alternatives.
+fn method_at(
+    kind: AgentKind,
+    info: &InstallInfo,
+    index: usize,
+) -> Result<&InstallMethod, InstallError> {
+    let method = match index {
+        0 => Some(&info.primary),
+        n => info.alternatives.get(n - 1),
+    };
+    method.ok_or_else(|| InstallError::InstallerFailed {
+        message: format!(
+            "{} has no install method {} (valid indices are 0 to {})",
+            kind.display_name(),
+            index,
+            info.alternatives.len()
+        ),
+        exit_code: None,
+        stdout: None,
+        stderr: None,
+        fix: fixes::render(
+            fixes::INVALID_METHOD_INDEX,
+            &[("max", &info.alternatives.len())],
+        ),
+    })
+}
+
+/// Choose the install method to run.
+///
+/// Uses the primary method if its program is available, otherwise the
+/// first alternative whose program is. This lets, for example, an npm
+/// alternative install an agent on a machine without Homebrew. If no
+/// method's program is available, returns `PrerequisiteMissing` naming
+/// the missing programs.
+pub(super) fn select_method(
+    kind: AgentKind,
+    info: &InstallInfo,
+    available: impl Fn(&str) -> bool,
+) -> Result<&InstallMethod, InstallError> {
+    let methods = || std::iter::once(&info.primary).chain(&info.alternatives);
+    if let Some(method) = methods().find(|method| available(&method.command.program)) {
+        return Ok(method);
+    }
+
+    let mut programs: Vec<&str> = methods()
+        .map(|method| method.command.program.as_str())
+        .collect();
+    programs.dedup();
+    let programs = programs.join(", ");
+    Err(InstallError::PrerequisiteMissing {
+        fix: fixes::render(
+            fixes::INSTALL_PROGRAM_MISSING,
+            &[("agent", &kind.display_name()), ("programs", &programs)],
+        ),
+        name: programs,
+        install_url: None,
+    })
+}
+
+/// The command `install()` runs for an install method.
+///
+/// This is `options.command_override` when set, otherwise the method's
+/// command with `version` or `dist_tag` applied to npm commands.
+fn resolve_command(method: &InstallMethod, options: &InstallOptions) -> StructuredCommand {
+    if let Some(cmd) = &options.command_override {
+        return cmd.clone();
+    }
+
+    let mut cmd = method.command.clone();
+    let spec = options
+        .version
+        .as_ref()
+        .map(|v| v.to_string())
+        .or_else(|| options.dist_tag.clone());
+    if let Some(spec) = spec {
+        apply_npm_spec(&mut cmd, &spec);
+    }
+    cmd
+}
+
+/// Check that the install options are not contradictory.
+fn validate_options(options: &InstallOptions) -> Result<(), InstallError> {
+    if let (Some(version), Some(tag)) = (&options.version, &options.dist_tag) {
+        return Err(InstallError::InvalidOptions {
+            message: format!(
+                "both version {} and dist-tag '{}' were requested",
+                version, tag
+            ),
+            fix: fixes::VERSION_AND_DIST_TAG.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Time allowed for resolving a package version from the registry.
+#[cfg(feature = "network")]
+const RESOLVE_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Resolve the concrete version an npm install command would install.
+///
+/// `query` looks up a package spec (e.g., `@openai/codex` or
+/// `@openai/codex@beta`) in the registry. Returns the
+/// [`InstallProgress::Resolved`] event to report, or `None` for non-npm
+/// commands and failed lookups.
+#[cfg(feature = "network")]
+async fn resolved_progress<Q, Fut>(cmd: &StructuredCommand, query: Q) -> Option<InstallProgress>
+where
+    Q: FnOnce(String) -> Fut,
+    Fut: Future<Output = Option<Version>>,
+{
+    if cmd.program != "npm" {
+        return None;
+    }
+    let index = npm_package_index(&cmd.args)?;
+    let version = query(cmd.args[index].clone()).await?;
+    Some(InstallProgress::Resolved { version })
+}
+
+/// Look up a package's version with `npm view <spec> version`.
+#[cfg(feature = "network")]
+pub(crate) async fn npm_view_version(spec: String) -> Option<Version> {
+    let mut command = Command::new("npm");
+    command
+        .args(["view", &spec, "version"])
+        .stdin(Stdio::null())
+        .kill_on_drop(true);
+
+    let output = timeout(RESOLVE_TIMEOUT, command.output())
+        .await
+        .ok()?
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    // A range spec lists several versions; the last is the newest
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let last = stdout.lines().rev().find(|line| !line.trim().is_empty())?;
+    parse_version(last).map(|(version, _)| version)
+}
+
+/// Index of the package in npm install arguments: the last non-flag one.
+pub(super) fn npm_package_index(args: &[String]) -> Option<usize> {
+    args.iter().rposition(|arg| !arg.starts_with('-'))
+}
+
+/// Point an npm install command's package argument at `spec`.
+///
+/// The package is the last non-flag argument. Any version or tag already
+/// on it (e.g., `opencode-ai@latest`) is replaced; the leading `@` of a
+/// scoped package name is kept. Non-npm commands are left unchanged.
+fn apply_npm_spec(cmd: &mut StructuredCommand, spec: &str) {
+    if cmd.program != "npm" {
+        return;
+    }
+    let Some(index) = npm_package_index(&cmd.args) else {
+        return;
+    };
+
+    let package = &mut cmd.args[index];
+    *package = format!("{}@{}", npm_package_name(package), spec);
+}
+
+/// An npm package argument without its version or tag.
+///
+/// `opencode-ai@latest` becomes `opencode-ai`; the leading `@` of a scoped
+/// package name such as `@openai/codex` is kept.
+pub(super) fn npm_package_name(package: &str) -> &str {
+    // The version `@` is any after the first character
+    package
+        .char_indices()
+        .skip(1)
+        .find(|&(_, c)| c == '@')
+        .map_or(package, |(at, _)| &package[..at])
+}
+
+/// Environment variables for running `cmd` with the given options.
+///
+/// The installer's non-interactive settings come first, so the method's
+/// own `env_vars` and the option overrides can replace them.
+fn resolved_env(cmd: &StructuredCommand, options: &InstallOptions) -> Vec<(String, String)> {
+    let mut env: Vec<(String, String)> = non_interactive_env(cmd)
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+    env.extend(cmd.env_vars.iter().cloned());
+
+    if cmd.program == "npm" {
+        if let Some(prefix) = &options.npm_prefix {
+            env.push((
+                "NPM_CONFIG_PREFIX".to_string(),
+                prefix.to_string_lossy().into_owned(),
+            ));
+        }
+    }
+
+    env
+}
+
+/// Niceness applied to installers when `low_priority` is set (Unix).
+#[cfg(unix)]
+const LOW_PRIORITY_NICENESS: &str = "10";
+
+/// `BELOW_NORMAL_PRIORITY_CLASS` process creation flag (Windows).
+#[cfg(windows)]
+const BELOW_NORMAL_PRIORITY_CLASS: u32 = 0x0000_4000;
+
+/// Build the installer process for a structured command.
+///
+/// Output is piped for error reporting, stdin is closed with the
+/// installer's non-interactive settings enabled, and the process is
+/// killed if the install future is dropped. With `low_priority`, the installer runs
+/// under `nice` on Unix or with a below-normal priority class on Windows.
+fn build_command(cmd: &StructuredCommand, options: &InstallOptions) -> Command {
+    #[cfg(unix)]
+    let mut command = if options.low_priority {
+        let mut command = Command::new("nice");
+        command
+            .args(["-n", LOW_PRIORITY_NICENESS])
+            .arg(&cmd.program);
+        command
+    } else {
+        Command::new(&cmd.program)
+    };
+
+    #[cfg(not(unix))]
+    let mut command = Command::new(&cmd.program);
+
+    #[cfg(windows)]
+    if options.low_priority {
+        command.creation_flags(BELOW_NORMAL_PRIORITY_CLASS);
+    }
+
+    // Output is piped, so nobody would see a prompt, even from a terminal:
+    // close stdin and ask the installer not to prompt, so it fails fast
+    // instead of hanging
+    command
+        .args(&cmd.args)
+        .envs(resolved_env(cmd, options))
+        .kill_on_drop(true)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[tokio::test]
+    async fn test_install_progress_callback() {
+        // Verify callback is called with expected progress stages
+        let stages = Arc::new(Mutex::new(Vec::new()));
+        let stages_clone = stages.clone();
+
+        // Run install - it will fail at some point but should call callback
+        let _ = install(
+            AgentKind::ClaudeCode,
+            InstallOptions::default(),
+            move |progress| {
+                stages_clone.lock().unwrap().push(format!("{:?}", progress));
+            },
         )
         .await;
 
@@ -196,6 +1365,7 @@ mod tests {
     async fn test_install_options_timeout() {
         let opts = InstallOptions {
             timeout: std::time::Duration::from_secs(1),
+            ..Default::default()
         };
         assert_eq!(opts.timeout.as_secs(), 1);
     }
@@ -236,10 +1406,16 @@ mod tests {
                 let stage_name = match &progress {
                     InstallProgress::Started { .. } => "Started",
                     InstallProgress::CheckingPrerequisites => "CheckingPrerequisites",
+                    InstallProgress::UsingMethod { .. } => "UsingMethod",
+                    InstallProgress::Resolved { .. } => "Resolved",
                     InstallProgress::Downloading { .. } => "Downloading",
                     InstallProgress::Installing { .. } => "Installing",
                     InstallProgress::Verifying { .. } => "Verifying",
+                    InstallProgress::Heartbeat { .. } => "Heartbeat",
+                    InstallProgress::Retrying { .. } => "Retrying",
+                    InstallProgress::VerifyAttempt { .. } => "VerifyAttempt",
                     InstallProgress::Completed { .. } => "Completed",
+                    InstallProgress::Updated { .. } => "Updated",
                 };
                 stages_clone.lock().unwrap().push(stage_name.to_string());
             },
@@ -263,6 +1439,7 @@ mod tests {
             AgentKind::ClaudeCode,
             InstallOptions {
                 timeout: std::time::Duration::from_millis(1),
+                ..Default::default()
             },
             move |progress| {
                 stages_clone.lock().unwrap().push(format!("{:?}", progress));
@@ -277,4 +1454,1041 @@ mod tests {
         let stages = stages.lock().unwrap();
         assert!(!stages.is_empty());
     }
+
+    fn npm_command() -> StructuredCommand {
+        StructuredCommand {
+            program: "npm".to_string(),
+            args: vec!["install".to_string(), "-g".to_string(), "pkg".to_string()],
+            env_vars: vec![],
+        }
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_build_command_low_priority_uses_nice() {
+        let options = InstallOptions {
+            low_priority: true,
+            ..Default::default()
+        };
+        let command = build_command(&npm_command(), &options);
+        let std_cmd = command.as_std();
+
+        assert_eq!(std_cmd.get_program(), "nice");
+        let args: Vec<_> = std_cmd.get_args().collect();
+        assert_eq!(args, ["-n", "10", "npm", "install", "-g", "pkg"]);
+    }
+
+    #[test]
+    fn test_build_command_default_priority() {
+        let command = build_command(&npm_command(), &InstallOptions::default());
+        assert_eq!(command.as_std().get_program(), "npm");
+    }
+
+    #[test]
+    fn test_install_environment_injects_npm_prefix() {
+        let options = InstallOptions {
+            npm_prefix: Some(std::path::PathBuf::from("/opt/agents")),
+            ..Default::default()
+        };
+
+        let env = install_environment(AgentKind::Codex, &options);
+        assert!(env.contains(&("NPM_CONFIG_PREFIX".to_string(), "/opt/agents".to_string())));
+        // The non-interactive settings install() spawns with are reported too
+        assert!(env.contains(&("npm_config_yes".to_string(), "true".to_string())));
+
+        // Without the option, nothing is injected
+        let env = install_environment(AgentKind::Codex, &InstallOptions::default());
+        assert!(env.iter().all(|(key, _)| key != "NPM_CONFIG_PREFIX"));
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_install_environment_ignores_npm_prefix_for_native() {
+        // Claude Code's primary method is the native installer, not npm
+        let options = InstallOptions {
+            npm_prefix: Some(std::path::PathBuf::from("/opt/agents")),
+            ..Default::default()
+        };
+        let env = install_environment(AgentKind::ClaudeCode, &options);
+        assert!(env.iter().all(|(key, _)| key != "NPM_CONFIG_PREFIX"));
+    }
+
+    #[test]
+    fn test_build_command_applies_resolved_env() {
+        let options = InstallOptions {
+            npm_prefix: Some(std::path::PathBuf::from("/opt/agents")),
+            ..Default::default()
+        };
+        let command = build_command(&npm_command(), &options);
+        let envs: Vec<_> = command.as_std().get_envs().collect();
+        assert!(envs.contains(&(
+            std::ffi::OsStr::new("NPM_CONFIG_PREFIX"),
+            Some(std::ffi::OsStr::new("/opt/agents"))
+        )));
+        // Prompts are disabled whether or not a terminal is attached
+        assert!(envs.contains(&(
+            std::ffi::OsStr::new("npm_config_yes"),
+            Some(std::ffi::OsStr::new("true"))
+        )));
+    }
+
+    #[test]
+    fn test_command_override_replaces_primary() {
+        let override_cmd = StructuredCommand {
+            program: "my-installer".to_string(),
+            args: vec!["--agent".to_string(), "codex".to_string()],
+            env_vars: vec![],
+        };
+        let options = InstallOptions {
+            command_override: Some(override_cmd),
+            ..Default::default()
+        };
+
+        let cmd = resolve_command(&AgentKind::Codex.install_info().primary, &options);
+        let command = build_command(&cmd, &options);
+        let std_command = command.as_std();
+        assert_eq!(std_command.get_program(), "my-installer");
+        let args: Vec<_> = std_command.get_args().collect();
+        assert_eq!(args, ["--agent", "codex"]);
+
+        // Without an override the primary method is used
+        let cmd = resolve_command(
+            &AgentKind::Codex.install_info().primary,
+            &InstallOptions::default(),
+        );
+        assert_eq!(
+            cmd.program,
+            AgentKind::Codex.install_info().primary.command.program
+        );
+    }
+
+    /// Claude Code's install info with the primary program replaced by one
+    /// that doesn't exist.
+    fn info_with_missing_primary() -> InstallInfo {
+        let mut info = AgentKind::ClaudeCode.install_info();
+        info.primary.command.program = "definitely-not-a-real-installer-xyz".to_string();
+        info.alternatives = vec![AgentKind::Codex.install_info().primary];
+        info
+    }
+
+    #[test]
+    fn test_install_environment_follows_selected_method() {
+        let info = info_with_missing_primary();
+        let options = InstallOptions {
+            npm_prefix: Some(std::path::PathBuf::from("/opt/agents")),
+            ..Default::default()
+        };
+
+        // The npm alternative is what install() would run
+        let env = install_environment_with(AgentKind::ClaudeCode, &info, &options, |program| {
+            program == "npm"
+        });
+        assert!(env.contains(&("NPM_CONFIG_PREFIX".to_string(), "/opt/agents".to_string())));
+        assert!(env.contains(&("npm_config_yes".to_string(), "true".to_string())));
+    }
+
+    #[test]
+    fn test_select_method_prefers_primary() {
+        let info = AgentKind::Codex.install_info();
+        let method = select_method(AgentKind::Codex, &info, |_| true).unwrap();
+        assert_eq!(method.raw_command, info.primary.raw_command);
+    }
+
+    #[test]
+    fn test_select_method_falls_back_to_alternative() {
+        let info = info_with_missing_primary();
+        let method = select_method(AgentKind::ClaudeCode, &info, |program| {
+            find_executable(program).is_some() || program == "npm"
+        })
+        .unwrap();
+        assert_eq!(method.command.program, "npm");
+    }
+
+    #[test]
+    fn test_select_method_no_program_available() {
+        let info = info_with_missing_primary();
+        match select_method(AgentKind::ClaudeCode, &info, |program| program == "nothing") {
+            Err(InstallError::PrerequisiteMissing {
+                name,
+                install_url,
+                fix,
+            }) => {
+                assert_eq!(name, "definitely-not-a-real-installer-xyz, npm");
+                assert!(install_url.is_none());
+                assert!(fix.contains("Claude Code"), "{}", fix);
+                assert!(fix.contains(&name), "{}", fix);
+            }
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_method_at() {
+        let info = AgentKind::OpenCode.install_info();
+        let primary = method_at(AgentKind::OpenCode, &info, 0).unwrap();
+        assert_eq!(primary.raw_command, info.primary.raw_command);
+
+        let last = info.alternatives.len();
+        let alternative = method_at(AgentKind::OpenCode, &info, last).unwrap();
+        assert_eq!(
+            alternative.raw_command,
+            info.alternatives[last - 1].raw_command
+        );
+    }
+
+    #[tokio::test]
+    async fn test_install_with_method_rejects_invalid_index() {
+        let index = AgentKind::Codex.install_info().alternatives.len() + 1;
+        let result =
+            install_with_method(AgentKind::Codex, index, InstallOptions::default(), |_| {}).await;
+
+        match result {
+            Err(InstallError::InstallerFailed {
+                message,
+                exit_code,
+                fix,
+                ..
+            }) => {
+                assert!(message.contains("no install method"), "{}", message);
+                assert!(exit_code.is_none());
+                assert!(fix.contains("InstallInfo::methods"), "{}", fix);
+            }
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_install_with_method_runs_chosen_method() {
+        let dir = tempfile::tempdir().unwrap();
+        let ran = dir.path().join("ran");
+        let primary = crate::test_util::fake_executable(dir.path(), "primary-installer", "exit 1");
+        let chosen = crate::test_util::fake_executable(
+            dir.path(),
+            "chosen-installer",
+            &format!("touch '{}'", ran.display()),
+        );
+        let mut info = AgentKind::ClaudeCode.install_info();
+        info.primary.command = run(&primary);
+        info.primary.prerequisites.clear();
+        let mut alternative = info.primary.clone();
+        alternative.command = run(&chosen);
+        info.alternatives = vec![alternative];
+
+        let options = InstallOptions {
+            skip_verification: true,
+            ..Default::default()
+        };
+        let verify = || async { AgentStatus::NotInstalled };
+        let outcome = install_with(
+            AgentKind::ClaudeCode,
+            &info,
+            Some(1),
+            options,
+            &|_| {},
+            verify,
+        )
+        .await
+        .unwrap();
+        assert_eq!(outcome.agent, AgentKind::ClaudeCode);
+        assert!(ran.exists(), "the chosen method's installer should run");
+    }
+
+    #[test]
+    fn test_dist_tag_applied_to_npm_package() {
+        let options = InstallOptions {
+            dist_tag: Some("beta".to_string()),
+            ..Default::default()
+        };
+        let cmd = resolve_command(&AgentKind::Codex.install_info().primary, &options);
+        assert_eq!(cmd.program, "npm");
+        assert_eq!(cmd.args.last().unwrap(), "@openai/codex@beta");
+
+        let options = InstallOptions {
+            version: Some(semver::Version::new(0, 87, 0)),
+            ..Default::default()
+        };
+        let cmd = resolve_command(&AgentKind::Codex.install_info().primary, &options);
+        assert_eq!(cmd.args.last().unwrap(), "@openai/codex@0.87.0");
+    }
+
+    #[test]
+    fn test_apply_npm_spec_replaces_existing_tag() {
+        let mut cmd = npm_command();
+        cmd.args[2] = "opencode-ai@latest".to_string();
+        apply_npm_spec(&mut cmd, "beta");
+        assert_eq!(cmd.args[2], "opencode-ai@beta");
+
+        // Non-npm commands are left alone
+        let mut cmd = StructuredCommand {
+            program: "brew".to_string(),
+            args: vec!["install".to_string(), "opencode".to_string()],
+            env_vars: vec![],
+        };
+        apply_npm_spec(&mut cmd, "beta");
+        assert_eq!(cmd.args[1], "opencode");
+    }
+
+    #[test]
+    fn test_apply_npm_spec_odd_package_arguments() {
+        // Reachable through command_override; must not panic
+        let mut cmd = npm_command();
+        cmd.args[2] = String::new();
+        apply_npm_spec(&mut cmd, "beta");
+        assert_eq!(cmd.args[2], "@beta");
+
+        let mut cmd = npm_command();
+        cmd.args[2] = "épée@1.0.0".to_string();
+        apply_npm_spec(&mut cmd, "beta");
+        assert_eq!(cmd.args[2], "épée@beta");
+
+        let mut cmd = npm_command();
+        cmd.args[2] = "@openai/codex@latest".to_string();
+        apply_npm_spec(&mut cmd, "beta");
+        assert_eq!(cmd.args[2], "@openai/codex@beta");
+    }
+
+    #[cfg(feature = "network")]
+    #[tokio::test]
+    async fn test_resolved_progress_uses_registry_version() {
+        let options = InstallOptions {
+            dist_tag: Some("beta".to_string()),
+            ..Default::default()
+        };
+        let cmd = resolve_command(&AgentKind::Codex.install_info().primary, &options);
+
+        let queried = Arc::new(Mutex::new(None));
+        let queried_clone = queried.clone();
+        let progress = resolved_progress(&cmd, |spec| async move {
+            *queried_clone.lock().unwrap() = Some(spec);
+            Some(Version::new(0, 90, 0))
+        })
+        .await;
+
+        assert_eq!(
+            queried.lock().unwrap().as_deref(),
+            Some("@openai/codex@beta")
+        );
+        assert!(matches!(
+            progress,
+            Some(InstallProgress::Resolved { version }) if version == Version::new(0, 90, 0)
+        ));
+
+        // Failed lookups are skipped
+        let progress = resolved_progress(&cmd, |_| async { None }).await;
+        assert!(progress.is_none());
+    }
+
+    #[test]
+    fn test_version_and_dist_tag_conflict() {
+        let options = InstallOptions {
+            version: Some(semver::Version::new(1, 0, 0)),
+            dist_tag: Some("beta".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            validate_options(&options),
+            Err(InstallError::InvalidOptions { .. })
+        ));
+        assert!(validate_options(&InstallOptions::default()).is_ok());
+    }
+
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn test_heartbeat_fires_while_installer_is_silent() {
+        let dir = tempfile::tempdir().unwrap();
+        let script = crate::test_util::fake_executable(dir.path(), "installer", "sleep 0.3");
+        let cmd = StructuredCommand {
+            program: script.to_string_lossy().into_owned(),
+            args: vec![],
+            env_vars: vec![],
+        };
+        let options = InstallOptions {
+            heartbeat_interval: Some(Duration::from_millis(50)),
+            ..Default::default()
+        };
+
+        let heartbeats = Mutex::new(Vec::new());
+        let on_progress = |p| {
+            if let InstallProgress::Heartbeat { elapsed, .. } = p {
+                heartbeats.lock().unwrap().push(elapsed);
+            }
+        };
+        run_installer(AgentKind::Codex, &cmd, &options, &on_progress)
+            .await
+            .unwrap();
+
+        let heartbeats = heartbeats.into_inner().unwrap();
+        assert!(!heartbeats.is_empty());
+        assert!(heartbeats.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn test_downloading_reported_from_installer_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let script = crate::test_util::fake_executable(
+            dir.path(),
+            "installer",
+            "echo 'resolving packages'\n\
+             echo 'npm http fetch GET 200 https://registry.npmjs.org/@openai/codex 120ms'\n\
+             echo 'npm http fetch GET 200 https://registry.npmjs.org/@openai/codex-sdk 80ms'\n\
+             echo 'added 1 package'",
+        );
+        let cmd = StructuredCommand {
+            program: script.to_string_lossy().into_owned(),
+            args: vec![],
+            env_vars: vec![],
+        };
+
+        let events = Mutex::new(Vec::new());
+        let on_progress = |p| {
+            if let InstallProgress::Downloading {
+                agent,
+                estimated_remaining,
+                ..
+            } = p
+            {
+                events.lock().unwrap().push((agent, estimated_remaining));
+            }
+        };
+        let log = run_installer(
+            AgentKind::Codex,
+            &cmd,
+            &InstallOptions::default(),
+            &on_progress,
+        )
+        .await
+        .unwrap();
+
+        // Reported once, however many download lines there are
+        assert_eq!(*events.lock().unwrap(), [(AgentKind::Codex, None)]);
+        // The full output is still collected
+        assert!(log.contains("resolving packages"));
+        assert!(log.contains("added 1 package"));
+    }
+
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn test_curl_download_progress_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let curl = crate::test_util::fake_executable(
+            dir.path(),
+            "curl",
+            "printf '####          25.0%%\\r' >&2\n\
+             printf '########      50.0%%\\r' >&2\n\
+             printf '######        40.0%%\\r' >&2\n\
+             printf '############ 100.0%%\\n' >&2\n\
+             echo 'echo installed'",
+        );
+        let cmd = StructuredCommand {
+            program: "bash".to_string(),
+            args: vec!["-c".to_string(), format!("{} | bash", curl.display())],
+            env_vars: vec![],
+        };
+
+        let events = Mutex::new(Vec::new());
+        let on_progress = |p| {
+            if let InstallProgress::Downloading {
+                percent,
+                estimated_remaining,
+                ..
+            } = p
+            {
+                events.lock().unwrap().push((percent, estimated_remaining));
+            }
+        };
+        let log = run_installer(
+            AgentKind::ClaudeCode,
+            &cmd,
+            &InstallOptions::default(),
+            &on_progress,
+        )
+        .await
+        .unwrap();
+
+        // One report per increase; the step back to 40% is ignored
+        let events = events.into_inner().unwrap();
+        let percents: Vec<_> = events.iter().map(|(percent, _)| *percent).collect();
+        assert_eq!(percents, [Some(25.0), Some(50.0), Some(100.0)]);
+        assert!(events.iter().all(|(_, remaining)| remaining.is_some()));
+        assert_eq!(events[2].1, Some(Duration::ZERO));
+        assert!(log.contains("installed"));
+    }
+
+    #[test]
+    fn test_is_download_line() {
+        assert!(is_download_line(
+            "npm http fetch GET 200 https://registry.npmjs.org/x"
+        ));
+        assert!(is_download_line(
+            "  % Total    % Received % Xferd  Average Speed   Time"
+        ));
+        assert!(is_download_line("Downloading Claude Code..."));
+        assert!(!is_download_line("added 1 package in 2s"));
+    }
+
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn test_run_installer_returns_log_on_success() {
+        let dir = tempfile::tempdir().unwrap();
+        let script = crate::test_util::fake_executable(
+            dir.path(),
+            "installer",
+            "echo installed ok\necho 'npm warn deprecated' >&2",
+        );
+        let cmd = StructuredCommand {
+            program: script.to_string_lossy().into_owned(),
+            args: vec![],
+            env_vars: vec![],
+        };
+
+        let log = run_installer(AgentKind::Codex, &cmd, &InstallOptions::default(), &|_| {})
+            .await
+            .unwrap();
+        assert!(log.contains("installed ok"));
+        assert!(log.contains("npm warn deprecated"));
+    }
+
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn test_run_installer_truncates_output() {
+        let dir = tempfile::tempdir().unwrap();
+        // ~20 KB on stdout, well past the cap, then a failing exit status
+        let script = crate::test_util::fake_executable(
+            dir.path(),
+            "installer",
+            "i=0\nwhile [ $i -lt 2000 ]; do echo 0123456789; i=$((i+1)); done\n\
+             echo 'npm error code ETIMEDOUT'\nexit 4",
+        );
+        let cmd = StructuredCommand {
+            program: script.to_string_lossy().into_owned(),
+            args: vec![],
+            env_vars: vec![],
+        };
+        let options = InstallOptions {
+            max_captured_output: 100,
+            ..Default::default()
+        };
+
+        match run_installer(AgentKind::Codex, &cmd, &options, &|_| {}).await {
+            Err(InstallError::InstallerFailed {
+                exit_code, stdout, ..
+            }) => {
+                assert_eq!(exit_code, Some(4));
+                let stdout = stdout.unwrap();
+                // The end of the output, with the error, is what's kept
+                assert!(stdout.starts_with("[output truncated; last 100 bytes kept]\n"));
+                assert!(stdout.ends_with("0123456789\nnpm error code ETIMEDOUT\n"));
+                assert!(stdout.len() < 200);
+            }
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn test_run_installer_failure_keeps_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let script =
+            crate::test_util::fake_executable(dir.path(), "installer", "echo boom >&2\nexit 3");
+        let cmd = StructuredCommand {
+            program: script.to_string_lossy().into_owned(),
+            args: vec![],
+            env_vars: vec![],
+        };
+
+        match run_installer(AgentKind::Codex, &cmd, &InstallOptions::default(), &|_| {}).await {
+            Err(InstallError::InstallerFailed {
+                exit_code, stderr, ..
+            }) => {
+                assert_eq!(exit_code, Some(3));
+                assert_eq!(stderr.as_deref(), Some("boom\n"));
+            }
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
+
+    fn installed_status(path: &str) -> AgentStatus {
+        AgentStatus::Installed(crate::test_util::installed_meta(
+            path,
+            Some(semver::Version::new(1, 0, 0)),
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_verify_installation_reports_attempts() {
+        let calls = Mutex::new(0);
+        let events = Mutex::new(Vec::new());
+        let on_progress = |p| events.lock().unwrap().push(p);
+
+        // Not detectable until the third attempt
+        let verify = || {
+            let mut calls = calls.lock().unwrap();
+            *calls += 1;
+            let status = if *calls < 3 {
+                AgentStatus::NotInstalled
+            } else {
+                installed_status("/usr/bin/claude")
+            };
+            async move { status }
+        };
+
+        let status = verify_installation(
+            AgentKind::ClaudeCode,
+            3,
+            Duration::ZERO,
+            &on_progress,
+            verify,
+        )
+        .await
+        .unwrap();
+        assert!(status.is_usable());
+
+        let attempts: Vec<_> = events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|p| match p {
+                InstallProgress::VerifyAttempt { attempt, max } => Some((*attempt, *max)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(attempts, [(1, 3), (2, 3), (3, 3)]);
+    }
+
+    #[tokio::test]
+    async fn test_verify_installation_fails_after_max_attempts() {
+        let result = verify_installation(AgentKind::Codex, 2, Duration::ZERO, &|_| {}, || async {
+            AgentStatus::NotInstalled
+        })
+        .await;
+        assert!(matches!(
+            result,
+            Err(InstallError::VerificationFailed {
+                agent: AgentKind::Codex,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_verification_failed_reports_off_path_install() {
+        let dir = tempfile::tempdir().unwrap();
+        let local_bin = dir.path().join(".local").join("bin");
+        let error = verification_failed(
+            AgentKind::ClaudeCode,
+            AgentStatus::NotInstalled,
+            Some(local_bin.join("claude")),
+        );
+
+        let fix = error.fix_suggestion();
+        assert!(fix.contains(&local_bin.display().to_string()));
+        assert!(fix.contains("isn't on PATH"));
+        assert!(matches!(
+            error,
+            InstallError::VerificationFailed { status, off_path: Some(_), .. }
+                if matches!(*status, AgentStatus::NotInstalled)
+        ));
+
+        // Without an off-PATH copy the agent wasn't found anywhere
+        let error = verification_failed(AgentKind::ClaudeCode, AgentStatus::NotInstalled, None);
+        assert!(error
+            .fix_suggestion()
+            .contains("not found in any known location"));
+    }
+
+    #[test]
+    #[cfg(windows)]
+    fn test_off_path_install_needs_restart_on_windows() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("claude.exe");
+        std::fs::write(&path, "binary").unwrap();
+
+        let error =
+            verification_failed(AgentKind::ClaudeCode, AgentStatus::NotInstalled, Some(path));
+        assert!(restart_pending(error).is_ok());
+
+        // Nothing at the install location: still a failure
+        let missing = dir.path().join("missing.exe");
+        let error = verification_failed(
+            AgentKind::ClaudeCode,
+            AgentStatus::NotInstalled,
+            Some(missing),
+        );
+        assert!(restart_pending(error).is_err());
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_off_path_install_is_an_error_elsewhere() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("claude");
+        std::fs::write(&path, "binary").unwrap();
+
+        let error =
+            verification_failed(AgentKind::ClaudeCode, AgentStatus::NotInstalled, Some(path));
+        assert!(matches!(
+            restart_pending(error),
+            Err(InstallError::VerificationFailed { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_verify_installation_skips_delay_when_detectable() {
+        let start = std::time::Instant::now();
+        let result = verify_installation(
+            AgentKind::Codex,
+            3,
+            Duration::from_secs(30),
+            &|_| {},
+            || async { installed_status("/usr/bin/codex") },
+        )
+        .await;
+
+        assert!(result.is_ok());
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn test_skip_verification_avoids_detect() {
+        let options = InstallOptions {
+            skip_verification: true,
+            ..Default::default()
+        };
+        let events = Mutex::new(Vec::new());
+        let on_progress = |p: InstallProgress| events.lock().unwrap().push(p.description());
+
+        let outcome = finish_install(
+            AgentKind::Codex,
+            &options,
+            String::new(),
+            &on_progress,
+            || async { panic!("verification should be skipped") },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(outcome.agent, AgentKind::Codex);
+        assert!(outcome.version.is_none());
+        assert_eq!(*events.lock().unwrap(), ["Installation complete"]);
+    }
+
+    #[tokio::test]
+    async fn test_finish_install_reports_verified_version() {
+        let options = InstallOptions {
+            capture_log: true,
+            ..Default::default()
+        };
+        let outcome = finish_install(
+            AgentKind::ClaudeCode,
+            &options,
+            "installed".to_string(),
+            &|_| {},
+            || async { installed_status("/usr/bin/claude") },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(outcome.version, Some(semver::Version::new(1, 0, 0)));
+        assert_eq!(outcome.log.as_deref(), Some("installed"));
+        assert!(!outcome.needs_restart);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_update_reports_before_and_after_versions() {
+        let calls = Mutex::new(0);
+        let events = Mutex::new(Vec::new());
+        let on_progress = |p| events.lock().unwrap().push(p);
+
+        // 1.0.0 before the update, 1.1.0 after
+        let detect = || {
+            let mut calls = calls.lock().unwrap();
+            *calls += 1;
+            let mut status = installed_status("/usr/local/bin/opencode");
+            if *calls > 1 {
+                if let AgentStatus::Installed(meta) = &mut status {
+                    meta.version = Some(semver::Version::new(1, 1, 0));
+                }
+            }
+            async move { status }
+        };
+        let options = InstallOptions {
+            command_override: Some(StructuredCommand {
+                program: "true".to_string(),
+                args: vec![],
+                env_vars: vec![],
+            }),
+            verify_attempts: 1,
+            ..Default::default()
+        };
+
+        let outcome = update_with(AgentKind::OpenCode, options, &on_progress, &detect, &detect)
+            .await
+            .unwrap();
+
+        assert_eq!(outcome.version, Some(semver::Version::new(1, 1, 0)));
+        assert_eq!(*calls.lock().unwrap(), 2);
+        let events = events.lock().unwrap();
+        assert!(events[events.len() - 2].is_complete());
+        match events.last() {
+            Some(InstallProgress::Updated { agent, from, to }) => {
+                assert_eq!(*agent, AgentKind::OpenCode);
+                assert_eq!(*from, Some(semver::Version::new(1, 0, 0)));
+                assert_eq!(*to, Some(semver::Version::new(1, 1, 0)));
+            }
+            other => panic!("Unexpected last event: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_update_requires_installed_agent() {
+        let result = update_with(
+            AgentKind::Codex,
+            InstallOptions::default(),
+            &|_| {},
+            || async { AgentStatus::NotInstalled },
+            || async { AgentStatus::NotInstalled },
+        )
+        .await;
+
+        match result {
+            Err(InstallError::VerificationFailed { agent, fix, .. }) => {
+                assert_eq!(agent, AgentKind::Codex);
+                assert!(fix.contains("install it first"), "{}", fix);
+                assert!(fix.contains("@openai/codex"), "{}", fix);
+            }
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn test_update_route_follows_install_method() {
+        let route = |kind: AgentKind, path: &str| {
+            let info = kind.install_info();
+            match update_route(kind, Path::new(path), &info, |_| true) {
+                Ok(UpdateRoute::Reinstall { index }) => Ok((
+                    Some(index),
+                    info.methods()[index].method.command.program.clone(),
+                )),
+                Ok(UpdateRoute::SelfUpdate(cmd)) => Ok((None, cmd.program)),
+                Err(e) => Err(e.fix_suggestion().to_string()),
+            }
+        };
+
+        // Native installs: Claude Code updates itself, OpenCode reruns its script
+        assert_eq!(
+            route(AgentKind::ClaudeCode, "/home/u/.claude/local/claude"),
+            Ok((None, "/home/u/.claude/local/claude".to_string()))
+        );
+        assert_eq!(
+            route(AgentKind::OpenCode, "/home/u/.opencode/bin/opencode"),
+            Ok((Some(0), "bash".to_string()))
+        );
+
+        // npm copies are updated through npm, not the primary installer
+        assert_eq!(
+            route(
+                AgentKind::ClaudeCode,
+                "/usr/lib/node_modules/@anthropic-ai/claude-code/cli.js"
+            ),
+            Ok((Some(1), "npm".to_string()))
+        );
+        assert_eq!(
+            route(AgentKind::OpenCode, "/home/u/.npm-global/bin/opencode"),
+            Ok((Some(1), "npm".to_string()))
+        );
+
+        // Codex has no brew method, so a brew copy isn't shadowed by an npm one
+        let fix = route(AgentKind::Codex, "/opt/homebrew/bin/codex").unwrap_err();
+        assert!(fix.contains("update it with brew"), "{}", fix);
+
+        // An unknown origin uses the method install() would choose
+        assert_eq!(
+            route(AgentKind::Codex, "/usr/local/bin/codex"),
+            Ok((Some(0), "npm".to_string()))
+        );
+    }
+
+    /// Run `install_many_with` over every agent with a fake install,
+    /// returning the most installs that were running at once.
+    async fn max_concurrent_installs(max_parallel: usize) -> u32 {
+        use std::sync::atomic::AtomicU32;
+
+        let (running, max_running) = (AtomicU32::new(0), AtomicU32::new(0));
+        let (running, max_running) = (&running, &max_running);
+        let kinds: Vec<_> = AgentKind::all().collect();
+        let results = install_many_with(&kinds, max_parallel, |agent| async move {
+            let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+            max_running.fetch_max(now, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            running.fetch_sub(1, Ordering::SeqCst);
+            Ok(InstallOutcome {
+                agent,
+                version: None,
+                log: None,
+                needs_restart: false,
+                npm_summary: None,
+            })
+        })
+        .await;
+
+        // Results come back in input order
+        assert_eq!(results.iter().map(|(k, _)| *k).collect::<Vec<_>>(), kinds);
+        assert!(results.iter().all(|(_, result)| result.is_ok()));
+        max_running.load(Ordering::SeqCst)
+    }
+
+    #[tokio::test]
+    async fn test_install_many_respects_max_parallel() {
+        assert_eq!(max_concurrent_installs(1).await, 1);
+        assert_eq!(max_concurrent_installs(2).await, 2);
+        assert_eq!(
+            max_concurrent_installs(8).await,
+            AgentKind::all().count() as u32
+        );
+    }
+
+    /// A command running `program` with no arguments.
+    fn run(program: &std::path::Path) -> StructuredCommand {
+        StructuredCommand {
+            program: program.display().to_string(),
+            args: vec![],
+            env_vars: vec![],
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_network_failure_is_retried() {
+        let dir = tempfile::tempdir().unwrap();
+        let seen = dir.path().join("seen");
+        let flaky = crate::test_util::fake_executable(
+            dir.path(),
+            "flaky-installer",
+            &format!(
+                "if [ -e '{0}' ]; then echo installed; exit 0; fi\n\
+                 touch '{0}'\n\
+                 echo 'npm ERR! network request failed, reason: ETIMEDOUT' >&2\n\
+                 exit 1",
+                seen.display()
+            ),
+        );
+        let events = Mutex::new(Vec::new());
+        let on_progress = |p: InstallProgress| {
+            if let InstallProgress::Retrying { attempt, max, .. } = p {
+                events.lock().unwrap().push((attempt, max));
+            }
+        };
+        let options = InstallOptions {
+            max_retries: 2,
+            retry_backoff: Duration::from_millis(10),
+            ..Default::default()
+        };
+
+        let result =
+            run_installer_with_retries(AgentKind::Codex, &run(&flaky), &options, &on_progress)
+                .await;
+        assert_eq!(result.unwrap().trim(), "installed");
+        assert_eq!(*events.lock().unwrap(), [(1, 2)]);
+
+        // Without retries, the first failure is returned
+        std::fs::remove_file(&seen).unwrap();
+        let options = InstallOptions {
+            max_retries: 0,
+            ..options
+        };
+        let result =
+            run_installer_with_retries(AgentKind::Codex, &run(&flaky), &options, &|_| {}).await;
+        assert!(matches!(result, Err(InstallError::Network { .. })));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_installer_failure_is_not_retried() {
+        let dir = tempfile::tempdir().unwrap();
+        let count = dir.path().join("count");
+        let failing = crate::test_util::fake_executable(
+            dir.path(),
+            "failing-installer",
+            &format!(
+                "echo run >> '{}'\necho 'EACCES: permission denied' >&2\nexit 1",
+                count.display()
+            ),
+        );
+        let options = InstallOptions {
+            max_retries: 3,
+            retry_backoff: Duration::from_millis(10),
+            ..Default::default()
+        };
+
+        let result =
+            run_installer_with_retries(AgentKind::Codex, &run(&failing), &options, &|_| {}).await;
+        assert!(matches!(result, Err(InstallError::InstallerFailed { .. })));
+        assert_eq!(std::fs::read_to_string(&count).unwrap().lines().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_install_with_cancel_before_start() {
+        let token = CancellationToken::new();
+        token.cancel();
+        let events = Mutex::new(Vec::new());
+
+        let result = install_with_cancel(AgentKind::Codex, InstallOptions::default(), token, |p| {
+            events.lock().unwrap().push(p)
+        })
+        .await;
+
+        match result {
+            Err(InstallError::Cancelled { agent, fix }) => {
+                assert_eq!(agent, AgentKind::Codex);
+                assert!(fix.contains("Codex"), "{}", fix);
+            }
+            other => panic!("Unexpected result: {:?}", other),
+        }
+        assert!(events.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    #[cfg(target_os = "linux")]
+    async fn test_install_with_cancel_reaps_installer() {
+        let dir = tempfile::tempdir().unwrap();
+        let pid_file = dir.path().join("pid");
+        let script = crate::test_util::fake_executable(
+            dir.path(),
+            "installer",
+            &crate::test_util::record_pid_and_sleep(&pid_file),
+        );
+        let options = InstallOptions {
+            command_override: Some(StructuredCommand {
+                program: script.to_string_lossy().into_owned(),
+                args: vec![],
+                env_vars: vec![],
+            }),
+            ..Default::default()
+        };
+
+        // Cancel once the installer is running
+        let token = CancellationToken::new();
+        let cancel = token.clone();
+        let watched = pid_file.clone();
+        let canceller = tokio::spawn(async move {
+            let pid = crate::test_util::wait_for_pid(&watched).await;
+            cancel.cancel();
+            pid
+        });
+
+        // OpenCode's primary method has no prerequisites to check
+        let result = install_with_cancel(AgentKind::OpenCode, options, token, |_| {}).await;
+        assert!(matches!(result, Err(InstallError::Cancelled { .. })));
+
+        let pid = canceller.await.unwrap().expect("installer never started");
+        assert!(
+            crate::test_util::is_reaped(pid).await,
+            "installer {} lingers",
+            pid
+        );
+    }
 }
@@ -5,17 +5,30 @@
 //! appropriate commands for the current platform.
 
 use super::{
-    InstallInfo, InstallLocation, InstallMethod, Prerequisite, StructuredCommand, VerificationStep,
+    InstallInfo, InstallLocation, InstallMethod, Prerequisite, StructuredCommand, UninstallMethod,
+    VerificationStep,
 };
+use crate::AgentKind;
+use std::path::Path;
 
 /// Version verification pattern that matches semantic versions.
 /// Reuses the same pattern structure from detection/parser.rs.
 const VERSION_PATTERN: &str = r"\d+\.\d+\.\d+";
 
+/// Node.js prerequisite for npm-based methods (e.g., "Node.js 18+").
+fn node_prerequisite(name: &str) -> Prerequisite {
+    Prerequisite {
+        name: name.to_string(),
+        check_command: Some("node --version".to_string()),
+        install_url: Some("https://nodejs.org".to_string()),
+    }
+}
+
 /// Claude Code installation information.
 ///
 /// - Linux/macOS: curl script (native installer)
 /// - Windows: PowerShell script (native installer)
+/// - Alternative (Windows): winget install
 /// - Alternative: npm install (requires Node.js 18+)
 pub(crate) fn claude_code_install_info() -> InstallInfo {
     #[cfg(windows)]
@@ -31,21 +44,24 @@ pub(crate) fn claude_code_install_info() -> InstallInfo {
         raw_command: "irm https://claude.ai/install.ps1 | iex".to_string(),
         description: "Install via PowerShell (native installer)".to_string(),
         location: InstallLocation::UserLocal,
+        prerequisites: vec![],
     };
 
     #[cfg(not(windows))]
     let primary = InstallMethod {
         command: StructuredCommand {
             program: "bash".to_string(),
+            // --progress-bar instead of -s so install() can report download progress
             args: vec![
                 "-c".to_string(),
-                "curl -fsSL https://claude.ai/install.sh | bash".to_string(),
+                "curl -fSL --progress-bar https://claude.ai/install.sh | bash".to_string(),
             ],
             env_vars: vec![],
         },
-        raw_command: "curl -fsSL https://claude.ai/install.sh | bash".to_string(),
+        raw_command: "curl -fSL --progress-bar https://claude.ai/install.sh | bash".to_string(),
         description: "Install via curl script (native installer)".to_string(),
         location: InstallLocation::UserLocal,
+        prerequisites: vec![],
     };
 
     let npm_alternative = InstallMethod {
@@ -61,11 +77,40 @@ pub(crate) fn claude_code_install_info() -> InstallInfo {
         raw_command: "npm install -g @anthropic-ai/claude-code".to_string(),
         description: "Install via npm (requires Node.js 18+)".to_string(),
         location: InstallLocation::UserLocal,
+        prerequisites: vec![node_prerequisite("Node.js 18+")],
     };
 
+    #[cfg(windows)]
+    let alternatives = vec![
+        InstallMethod {
+            command: StructuredCommand {
+                program: "winget".to_string(),
+                args: vec![
+                    "install".to_string(),
+                    "--id".to_string(),
+                    "Anthropic.ClaudeCode".to_string(),
+                    "--exact".to_string(),
+                ],
+                env_vars: vec![],
+            },
+            raw_command: "winget install --id Anthropic.ClaudeCode --exact".to_string(),
+            description: "Install via winget (Windows Package Manager)".to_string(),
+            location: InstallLocation::UserLocal,
+            prerequisites: vec![Prerequisite {
+                name: "winget".to_string(),
+                check_command: Some("winget --version".to_string()),
+                install_url: Some("https://aka.ms/getwinget".to_string()),
+            }],
+        },
+        npm_alternative,
+    ];
+
+    #[cfg(not(windows))]
+    let alternatives = vec![npm_alternative];
+
     InstallInfo {
         primary,
-        alternatives: vec![npm_alternative],
+        alternatives,
         // Native installer has no prerequisites
         prerequisites: vec![],
         verification: VerificationStep {
@@ -73,7 +118,7 @@ pub(crate) fn claude_code_install_info() -> InstallInfo {
             expected_pattern: VERSION_PATTERN.to_string(),
             success_message: "Claude Code is installed".to_string(),
         },
-        is_supported: true,
+        is_supported: AgentKind::ClaudeCode.is_supported_here(),
         docs_url: "https://docs.anthropic.com/en/docs/claude-code".to_string(),
     }
 }
@@ -96,13 +141,10 @@ pub(crate) fn codex_install_info() -> InstallInfo {
         raw_command: "npm install -g @openai/codex".to_string(),
         description: "Install via npm (Node.js package manager)".to_string(),
         location: InstallLocation::UserLocal,
+        prerequisites: vec![node_prerequisite("Node.js 18+")],
     };
 
-    let prerequisites = vec![Prerequisite {
-        name: "Node.js 18+".to_string(),
-        check_command: Some("node --version".to_string()),
-        install_url: Some("https://nodejs.org".to_string()),
-    }];
+    let prerequisites = vec![node_prerequisite("Node.js 18+")];
 
     #[cfg(windows)]
     let description_note = " (Windows support is experimental; consider WSL)";
@@ -118,7 +160,7 @@ pub(crate) fn codex_install_info() -> InstallInfo {
             expected_pattern: VERSION_PATTERN.to_string(),
             success_message: format!("Codex is installed{}", description_note),
         },
-        is_supported: true,
+        is_supported: AgentKind::Codex.is_supported_here(),
         docs_url: "https://github.com/openai/codex".to_string(),
     }
 }
@@ -139,21 +181,24 @@ pub(crate) fn opencode_install_info() -> InstallInfo {
         raw_command: "scoop install opencode".to_string(),
         description: "Install via Scoop (Windows package manager)".to_string(),
         location: InstallLocation::UserLocal,
+        prerequisites: vec![],
     };
 
     #[cfg(not(windows))]
     let primary = InstallMethod {
         command: StructuredCommand {
             program: "bash".to_string(),
+            // --progress-bar instead of -s so install() can report download progress
             args: vec![
                 "-c".to_string(),
-                "curl -fsSL https://opencode.ai/install | bash".to_string(),
+                "curl -fSL --progress-bar https://opencode.ai/install | bash".to_string(),
             ],
             env_vars: vec![],
         },
-        raw_command: "curl -fsSL https://opencode.ai/install | bash".to_string(),
+        raw_command: "curl -fSL --progress-bar https://opencode.ai/install | bash".to_string(),
         description: "Install via curl script (native Go binary)".to_string(),
         location: InstallLocation::UserLocal,
+        prerequisites: vec![],
     };
 
     let npm_alternative = InstallMethod {
@@ -169,11 +214,11 @@ pub(crate) fn opencode_install_info() -> InstallInfo {
         raw_command: "npm i -g opencode-ai@latest".to_string(),
         description: "Install via npm (requires Node.js)".to_string(),
         location: InstallLocation::UserLocal,
+        prerequisites: vec![node_prerequisite("Node.js")],
     };
 
     // Primary method (curl or scoop) has no prerequisites
-    // The npm alternative would need Node.js but we don't list it
-    // since it's just an alternative
+    // The npm alternative needs Node.js, listed on the method itself
     let prerequisites = vec![];
 
     InstallInfo {
@@ -185,7 +230,7 @@ pub(crate) fn opencode_install_info() -> InstallInfo {
             expected_pattern: VERSION_PATTERN.to_string(),
             success_message: "OpenCode is installed".to_string(),
         },
-        is_supported: true,
+        is_supported: AgentKind::OpenCode.is_supported_here(),
         docs_url: "https://github.com/anomalyco/opencode".to_string(),
     }
 }
@@ -208,14 +253,11 @@ pub(crate) fn gemini_install_info() -> InstallInfo {
         raw_command: "npm install -g @google/gemini-cli".to_string(),
         description: "Install via npm (Node.js package manager)".to_string(),
         location: InstallLocation::UserLocal,
+        prerequisites: vec![node_prerequisite("Node.js 20+")],
     };
 
     // Gemini requires Node.js 20+ (higher than other agents)
-    let prerequisites = vec![Prerequisite {
-        name: "Node.js 20+".to_string(),
-        check_command: Some("node --version".to_string()),
-        install_url: Some("https://nodejs.org".to_string()),
-    }];
+    let prerequisites = vec![node_prerequisite("Node.js 20+")];
 
     InstallInfo {
         primary,
@@ -226,11 +268,96 @@ pub(crate) fn gemini_install_info() -> InstallInfo {
             expected_pattern: VERSION_PATTERN.to_string(),
             success_message: "Gemini CLI is installed".to_string(),
         },
-        is_supported: true,
+        is_supported: AgentKind::Gemini.is_supported_here(),
         docs_url: "https://github.com/google-gemini/gemini-cli".to_string(),
     }
 }
 
+/// Command that updates an installed agent in place, if its install
+/// command can't be rerun to update.
+///
+/// Claude Code's native installer is updated with `claude update`, run
+/// from the detected `executable`. Other agents are updated by running
+/// their install command again, as is an `executable` whose path isn't
+/// valid UTF-8 and so can't be run as a command's program.
+pub(crate) fn update_command(kind: AgentKind, executable: &Path) -> Option<StructuredCommand> {
+    match kind {
+        AgentKind::ClaudeCode => Some(StructuredCommand {
+            program: executable.to_str()?.to_string(),
+            args: vec!["update".to_string()],
+            env_vars: vec![],
+        }),
+        AgentKind::Codex | AgentKind::OpenCode | AgentKind::Gemini => None,
+    }
+}
+
+/// Uninstall a globally installed npm package.
+fn npm_uninstall(package: &str) -> UninstallMethod {
+    UninstallMethod {
+        command: StructuredCommand {
+            program: "npm".to_string(),
+            args: vec![
+                "uninstall".to_string(),
+                "-g".to_string(),
+                package.to_string(),
+            ],
+            env_vars: vec![],
+        },
+        raw_command: format!("npm uninstall -g {}", package),
+        description: "Uninstall via npm".to_string(),
+    }
+}
+
+/// Claude Code uninstall method.
+///
+/// The native installer has no uninstall command, so there is none.
+pub(crate) fn claude_code_uninstall_method() -> Option<UninstallMethod> {
+    None
+}
+
+/// Codex uninstall method (npm, all platforms).
+pub(crate) fn codex_uninstall_method() -> Option<UninstallMethod> {
+    Some(npm_uninstall("@openai/codex"))
+}
+
+/// OpenCode uninstall method.
+///
+/// - Linux/macOS: remove the binary placed by the curl script
+/// - Windows: scoop uninstall
+pub(crate) fn opencode_uninstall_method() -> Option<UninstallMethod> {
+    #[cfg(windows)]
+    let method = UninstallMethod {
+        command: StructuredCommand {
+            program: "scoop".to_string(),
+            args: vec!["uninstall".to_string(), "opencode".to_string()],
+            env_vars: vec![],
+        },
+        raw_command: "scoop uninstall opencode".to_string(),
+        description: "Uninstall via Scoop (Windows package manager)".to_string(),
+    };
+
+    #[cfg(not(windows))]
+    let method = UninstallMethod {
+        command: StructuredCommand {
+            program: "bash".to_string(),
+            args: vec![
+                "-c".to_string(),
+                "rm -f \"$HOME/.opencode/bin/opencode\"".to_string(),
+            ],
+            env_vars: vec![],
+        },
+        raw_command: "rm -f ~/.opencode/bin/opencode".to_string(),
+        description: "Remove the native binary installed by the curl script".to_string(),
+    };
+
+    Some(method)
+}
+
+/// Gemini CLI uninstall method (npm, all platforms).
+pub(crate) fn gemini_uninstall_method() -> Option<UninstallMethod> {
+    Some(npm_uninstall("@google/gemini-cli"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -248,6 +375,16 @@ mod tests {
         assert!(!info.alternatives.is_empty());
     }
 
+    #[test]
+    #[cfg(windows)]
+    fn test_claude_code_winget_alternative() {
+        let info = claude_code_install_info();
+        assert!(info
+            .alternatives
+            .iter()
+            .any(|m| m.command.program == "winget"));
+    }
+
     #[test]
     fn test_codex_install_info() {
         let info = codex_install_info();
@@ -279,6 +416,20 @@ mod tests {
         assert_eq!(info.verification.command, "gemini --version");
     }
 
+    #[test]
+    fn test_shell_raw_commands_match_what_runs() {
+        for kind in AgentKind::all() {
+            let info = kind.install_info();
+            for method in std::iter::once(&info.primary).chain(&info.alternatives) {
+                if let [flag, script] = method.command.args.as_slice() {
+                    if method.command.program == "bash" && flag == "-c" {
+                        assert_eq!(&method.raw_command, script);
+                    }
+                }
+            }
+        }
+    }
+
     #[test]
     fn test_agent_kind_install_info() {
         // Verify method works on AgentKind
@@ -352,4 +503,32 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_uninstall_methods() {
+        // Native installer has no uninstall command
+        assert!(claude_code_uninstall_method().is_none());
+
+        let codex = codex_uninstall_method().unwrap();
+        assert_eq!(codex.command.program, "npm");
+        assert_eq!(codex.command.args, ["uninstall", "-g", "@openai/codex"]);
+        assert_eq!(codex.raw_command, "npm uninstall -g @openai/codex");
+
+        assert!(opencode_uninstall_method().is_some());
+        assert!(gemini_uninstall_method()
+            .unwrap()
+            .raw_command
+            .contains("@google/gemini-cli"));
+    }
+
+    #[test]
+    fn test_update_command() {
+        let path = Path::new("/home/user/.local/bin/claude");
+        let cmd = update_command(AgentKind::ClaudeCode, path).unwrap();
+        assert_eq!(cmd.program, "/home/user/.local/bin/claude");
+        assert_eq!(cmd.args, ["update"]);
+
+        // npm and script installs update by reinstalling
+        assert!(update_command(AgentKind::Codex, path).is_none());
+    }
 }
@@ -0,0 +1,104 @@
+//! Serialization of installs that share a package manager.
+//!
+//! Two concurrent `npm install -g` runs contend for the same global
+//! package store and can corrupt it. Installs are therefore serialized per
+//! package manager, while installers with nothing in common (such as a
+//! native install script and npm) still run in parallel.
+
+use crate::install::StructuredCommand;
+use futures::lock::Mutex as AsyncMutex;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// Per-package-manager locks held while an installer runs.
+#[derive(Default)]
+pub(crate) struct InstallLocks {
+    locks: Mutex<HashMap<&'static str, Arc<AsyncMutex<()>>>>,
+}
+
+impl InstallLocks {
+    /// The locks shared by every `install()` call in this process.
+    pub(crate) fn global() -> &'static Self {
+        static LOCKS: OnceLock<InstallLocks> = OnceLock::new();
+        LOCKS.get_or_init(InstallLocks::default)
+    }
+
+    /// Run `install` while holding the lock for `cmd`'s package manager.
+    ///
+    /// Commands that don't use a shared package manager (such as native
+    /// install scripts) run without waiting.
+    pub(crate) async fn run<F: Future>(&self, cmd: &StructuredCommand, install: F) -> F::Output {
+        let Some(key) = lock_key(cmd) else {
+            return install.await;
+        };
+        let lock = self.locks.lock().unwrap().entry(key).or_default().clone();
+
+        let _guard = lock.lock().await;
+        install.await
+    }
+}
+
+/// The package manager whose global state `cmd` modifies, if any.
+fn lock_key(cmd: &StructuredCommand) -> Option<&'static str> {
+    match cmd.program.as_str() {
+        "npm" => Some("npm"),
+        "brew" => Some("brew"),
+        "cargo" => Some("cargo"),
+        "scoop" => Some("scoop"),
+        "winget" => Some("winget"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::join;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::time::Duration;
+
+    fn command(program: &str) -> StructuredCommand {
+        StructuredCommand {
+            program: program.to_string(),
+            args: vec![],
+            env_vars: vec![],
+        }
+    }
+
+    /// A fake installer that records the most installers running at once.
+    async fn fake_install(running: &AtomicU32, max_running: &AtomicU32) {
+        let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+        max_running.fetch_max(now, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        running.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    #[tokio::test]
+    async fn test_npm_installs_do_not_overlap() {
+        let locks = InstallLocks::default();
+        let (running, max_running) = (AtomicU32::new(0), AtomicU32::new(0));
+        let npm = command("npm");
+
+        join!(
+            locks.run(&npm, fake_install(&running, &max_running)),
+            locks.run(&npm, fake_install(&running, &max_running)),
+        );
+
+        assert_eq!(max_running.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_npm_and_native_installs_overlap() {
+        let locks = InstallLocks::default();
+        let (running, max_running) = (AtomicU32::new(0), AtomicU32::new(0));
+        let (npm, native) = (command("npm"), command("bash"));
+
+        join!(
+            locks.run(&npm, fake_install(&running, &max_running)),
+            locks.run(&native, fake_install(&running, &max_running)),
+        );
+
+        assert_eq!(max_running.load(Ordering::SeqCst), 2);
+    }
+}
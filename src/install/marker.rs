@@ -0,0 +1,244 @@
+//! Markers recording that an installation is in progress.
+//!
+//! When `InstallOptions::write_marker` is set, `install()` writes a marker
+//! file before running the installer and removes it once the install
+//! finishes, whether it succeeded or failed. A marker is left behind only
+//! if the install never finished: its process was killed, or the install
+//! was cancelled. [`had_interrupted_install`] reports such markers.
+
+use crate::AgentKind;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Check whether an earlier install of `kind` may not have completed.
+///
+/// Returns `true` if an install started with
+/// `InstallOptions::write_marker` set never finished, because the process
+/// running it was killed or the install was cancelled. An install that is
+/// still running in a live process, or that finished with an error, is
+/// not reported. The marker is cleared when the next install finishes.
+///
+/// Liveness is judged from the recorded process id, so a killed install
+/// whose id has since been reused by another process is missed.
+///
+/// # Example
+///
+/// ```rust
+/// use rig_acp_discovery::{had_interrupted_install, AgentKind};
+///
+/// if had_interrupted_install(AgentKind::Codex) {
+///     println!("A previous Codex install may not have completed.");
+/// }
+/// ```
+pub fn had_interrupted_install(kind: AgentKind) -> bool {
+    is_interrupted(&marker_path(&marker_dir(), kind))
+}
+
+/// Whether the marker at `path` records an install that never finished.
+fn is_interrupted(path: &Path) -> bool {
+    let Ok(contents) = std::fs::read_to_string(path) else {
+        return false;
+    };
+    let field = |name: &str| {
+        contents
+            .lines()
+            .find_map(|line| line.strip_prefix(name)?.strip_prefix('='))
+    };
+    if field("interrupted").is_some() {
+        return true;
+    }
+    // A marker without a readable pid can't belong to a running install
+    match field("pid").and_then(|pid| pid.parse().ok()) {
+        Some(pid) => !process_alive(pid),
+        None => true,
+    }
+}
+
+/// Whether a process with id `pid` is running.
+#[cfg(target_os = "linux")]
+fn process_alive(pid: u32) -> bool {
+    Path::new("/proc").join(pid.to_string()).exists()
+}
+
+/// Whether a process with id `pid` is running.
+#[cfg(target_os = "macos")]
+fn process_alive(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    // Signal 0 only checks that the process exists and may be signalled
+    // SAFETY: kill with signal 0 has no side effects
+    let alive = unsafe { libc::kill(pid, 0) } == 0;
+    alive || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+/// Whether a process with id `pid` is running.
+#[cfg(all(unix, not(any(target_os = "linux", target_os = "macos"))))]
+fn process_alive(pid: u32) -> bool {
+    std::process::Command::new("kill")
+        .args(["-0", &pid.to_string()])
+        .stderr(std::process::Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// Whether a process with id `pid` is running.
+#[cfg(windows)]
+fn process_alive(pid: u32) -> bool {
+    std::process::Command::new("tasklist")
+        .args(["/FI", &format!("PID eq {}", pid), "/NH", "/FO", "CSV"])
+        .output()
+        .is_ok_and(|output| {
+            String::from_utf8_lossy(&output.stdout).contains(&format!("\"{}\"", pid))
+        })
+}
+
+/// Directory holding install markers.
+///
+/// The user's state directory where the platform has one, otherwise the
+/// cache directory, falling back to the temp directory.
+pub(crate) fn marker_dir() -> PathBuf {
+    dirs::state_dir()
+        .or_else(dirs::cache_dir)
+        .unwrap_or_else(std::env::temp_dir)
+        .join("rig-acp-discovery")
+}
+
+/// Path of the marker for `kind` in `dir`.
+fn marker_path(dir: &Path, kind: AgentKind) -> PathBuf {
+    dir.join(format!("install-{}.marker", kind.executable_name()))
+}
+
+/// Run `install` with a marker for `kind` in `dir` while it runs.
+///
+/// The marker records this process's id and is removed once `install`
+/// finishes, successfully or not. If the future is dropped first (the
+/// install was cancelled), the marker is kept and flagged as interrupted,
+/// since this process is still alive. Failing to write the marker doesn't
+/// stop the install.
+pub(crate) async fn with_marker<T, E>(
+    dir: &Path,
+    kind: AgentKind,
+    install: impl Future<Output = Result<T, E>>,
+) -> Result<T, E> {
+    let mut marker = Marker::write(dir, kind);
+    let result = install.await;
+    marker.finish();
+    result
+}
+
+/// A written install marker, flagged as interrupted if dropped unfinished.
+struct Marker {
+    path: PathBuf,
+    contents: String,
+    finished: bool,
+}
+
+impl Marker {
+    /// Write the marker for `kind` in `dir`.
+    fn write(dir: &Path, kind: AgentKind) -> Self {
+        let path = marker_path(dir, kind);
+        let started = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let contents = format!("pid={}\nstarted={}\n", std::process::id(), started);
+        if let Err(e) = std::fs::create_dir_all(dir).and_then(|()| std::fs::write(&path, &contents))
+        {
+            tracing::debug!("Failed to write install marker {}: {}", path.display(), e);
+        }
+        Self {
+            path,
+            contents,
+            finished: false,
+        }
+    }
+
+    /// Remove the marker: the install ran to completion.
+    fn finish(&mut self) {
+        self.finished = true;
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+impl Drop for Marker {
+    fn drop(&mut self) {
+        if !self.finished {
+            let contents = format!("{}interrupted=1\n", self.contents);
+            let _ = std::fs::write(&self.path, contents);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_marker_cleared_after_failed_install() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = marker_path(dir.path(), AgentKind::Codex);
+
+        let result: Result<(), &str> = with_marker(dir.path(), AgentKind::Codex, async {
+            assert!(path.exists(), "marker should exist while installing");
+            // Running in this (live) process: not interrupted
+            assert!(!is_interrupted(&path));
+            Err("installer failed")
+        })
+        .await;
+
+        assert!(result.is_err());
+        assert!(!path.exists());
+        assert!(!is_interrupted(&path));
+    }
+
+    #[tokio::test]
+    async fn test_marker_cleared_after_success() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = marker_path(dir.path(), AgentKind::Gemini);
+
+        let result: Result<(), &str> = with_marker(dir.path(), AgentKind::Gemini, async {
+            assert!(path.exists(), "marker should exist while installing");
+            Ok(())
+        })
+        .await;
+
+        assert!(result.is_ok());
+        assert!(!path.exists());
+        // Other agents' markers are separate
+        assert_ne!(path, marker_path(dir.path(), AgentKind::Codex));
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_install_is_interrupted() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = marker_path(dir.path(), AgentKind::OpenCode);
+
+        let install = with_marker(dir.path(), AgentKind::OpenCode, async {
+            futures::future::pending::<Result<(), ()>>().await
+        });
+        // Poll once so the marker is written, then drop the install
+        let mut install = Box::pin(install);
+        assert!(futures::poll!(install.as_mut()).is_pending());
+        drop(install);
+
+        assert!(is_interrupted(&path));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_marker_of_dead_process_is_interrupted() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = marker_path(dir.path(), AgentKind::Codex);
+
+        let mut child = std::process::Command::new("true").spawn().unwrap();
+        let dead = child.id();
+        child.wait().unwrap();
+        std::fs::write(&path, format!("pid={}\nstarted=0\n", dead)).unwrap();
+        assert!(is_interrupted(&path));
+
+        std::fs::write(&path, format!("pid={}\nstarted=0\n", std::process::id())).unwrap();
+        assert!(!is_interrupted(&path));
+    }
+}
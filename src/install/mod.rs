@@ -3,9 +3,20 @@
 //! This module provides:
 //! - [`can_install`] - Pre-flight check for prerequisites
 //! - [`install`] - Programmatic installation with progress reporting
+//! - [`install_with_method`] - Installation with a specific install method
+//! - [`install_many`] - Installation of several agents, a few at a time
+//! - [`install_with_cancel`] - Installation that stops when a token is cancelled
+//! - [`update`] - Update of an installed agent to its latest version
+//! - [`uninstall`] - Removal of an installed agent, with verification
+//! - [`run_verification`] - Standalone run of an agent's verification step
 //! - [`InstallError`] - Error types with actionable fix suggestions
 //! - [`InstallProgress`] - Progress stages for UI updates
 //! - [`InstallOptions`] - Configuration (timeout, etc.)
+//! - [`InstallOutcome`] - Result of a successful installation
+//! - [`NpmSummary`] - What npm changed during an installation
+//! - [`ProgressAggregator`] - Overall progress across several installations
+//! - [`had_interrupted_install`] - Whether an earlier install never finished
+//! - [`is_interactive_session`] - Whether installers can prompt the user
 //!
 //! # Consent Model
 //!
@@ -68,17 +79,38 @@
 //! println!("  {}", info.verification.command);
 //! ```
 
+mod download;
 mod errors;
 mod executor;
 pub(crate) mod info;
+mod lock;
+mod marker;
 mod prereq;
 mod progress;
+mod remediation;
+mod session;
 mod types;
+mod uninstall;
+mod verify;
 
 pub use errors::InstallError;
-pub use executor::install;
-pub use prereq::can_install;
-pub use progress::{InstallOptions, InstallProgress};
+#[cfg(feature = "network")]
+pub(crate) use executor::npm_view_version;
+pub(crate) use executor::update_plan_command;
+pub use executor::{
+    install, install_environment, install_many, install_with_cancel, install_with_method, update,
+};
+pub use marker::had_interrupted_install;
+pub use prereq::{can_install, can_install_all, can_install_method, ready_methods};
+pub use progress::{
+    InstallOptions, InstallOutcome, InstallProgress, NpmSummary, ProgressAggregator,
+    UninstallProgress,
+};
+pub use session::is_interactive_session;
 pub use types::{
-    InstallInfo, InstallLocation, InstallMethod, Prerequisite, StructuredCommand, VerificationStep,
+    InstallInfo, InstallLocation, InstallMethod, MethodDescriptor, Prerequisite, Registry,
+    StructuredCommand, UninstallMethod, VerificationStep,
 };
+pub use uninstall::uninstall;
+pub use verify::run_verification;
+pub(crate) use verify::verify_install;
@@ -1,10 +1,17 @@
 //! Prerequisite checking for agent installation.
 //!
 //! This module provides the [`can_install`] function for pre-flight checks
-//! before attempting to install an agent.
+//! before attempting to install an agent, and per-method checks via
+//! [`can_install_method`] and [`ready_methods`].
 
-use crate::{AgentKind, InstallError};
+use crate::detection::find_executable;
+use crate::fixes;
+use crate::install::executor::select_method;
+use crate::{AgentKind, InstallError, InstallInfo, InstallMethod, MethodDescriptor, Prerequisite};
+use futures::future::join_all;
 use regex::Regex;
+use std::collections::HashMap;
+use std::future::Future;
 use std::time::Duration;
 use tokio::process::Command;
 use tokio::time::timeout;
@@ -16,8 +23,17 @@ const PREREQ_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
 ///
 /// This performs a pre-flight check before installation:
 /// 1. Verifies the agent is supported on this platform
-/// 2. Checks each prerequisite's check_command
-/// 3. Parses version output and compares to minimum requirement
+/// 2. Verifies the primary install method's program (e.g., `npm` or
+///    `scoop`) is on PATH, or else that an alternative method's is
+/// 3. Checks each prerequisite's check_command
+/// 4. Parses version output and compares to minimum requirement
+///
+/// If no method's program is found, `InstallError::PrerequisiteMissing`
+/// names the missing programs. When an alternative method is used, its own
+/// prerequisites are checked instead of the primary's.
+///
+/// Prerequisites are checked concurrently. If several fail, the error for
+/// the first one (in `InstallInfo::prerequisites` order) is returned.
 ///
 /// Returns `Ok(())` if installation can proceed, or an [`InstallError`]
 /// with an actionable fix suggestion if not.
@@ -43,28 +59,188 @@ const PREREQ_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
 /// }
 /// ```
 pub async fn can_install(kind: AgentKind) -> Result<(), InstallError> {
-    let info = kind.install_info();
+    can_install_with_programs(kind, |program| find_executable(program).is_some()).await
+}
 
+/// [`can_install`], deciding with `available` whether a method's program exists.
+pub(super) async fn can_install_with_programs(
+    kind: AgentKind,
+    available: impl Fn(&str) -> bool,
+) -> Result<(), InstallError> {
+    can_install_info_with(kind, &kind.install_info(), available, |prereq| async move {
+        check_prerequisite(&prereq).await
+    })
+    .await
+}
+
+/// [`can_install`] for `info`, with injectable program and prerequisite checks.
+async fn can_install_info_with<F, Fut>(
+    kind: AgentKind,
+    info: &InstallInfo,
+    available: impl Fn(&str) -> bool,
+    check: F,
+) -> Result<(), InstallError>
+where
+    F: Fn(Prerequisite) -> Fut,
+    Fut: Future<Output = Result<(), InstallError>>,
+{
     // Check platform support
     if !info.is_supported {
         return Err(InstallError::UnsupportedPlatform {
             agent: kind,
-            fix: format!("See {} for supported platforms", info.docs_url),
+            fix: fixes::render(fixes::UNSUPPORTED_PLATFORM, &[("docs_url", &info.docs_url)]),
         });
     }
 
-    // Check each prerequisite
-    for prereq in &info.prerequisites {
-        check_prerequisite(prereq).await?;
+    let method = select_method(kind, info, available)?;
+    let prerequisites = if std::ptr::eq(method, &info.primary) {
+        &info.prerequisites
+    } else {
+        &method.prerequisites
+    };
+    check_all(prerequisites, check).await
+}
+
+/// Check prerequisites concurrently, returning the first failure in order.
+async fn check_all<F, Fut>(prerequisites: &[Prerequisite], check: F) -> Result<(), InstallError>
+where
+    F: Fn(Prerequisite) -> Fut,
+    Fut: Future<Output = Result<(), InstallError>>,
+{
+    let results = join_all(prerequisites.iter().cloned().map(check)).await;
+    results.into_iter().collect()
+}
+
+/// Check prerequisites for all known agents in parallel.
+///
+/// Runs [`can_install`] for every agent in `AgentKind` concurrently and
+/// returns a map of agent kinds to their pre-flight check results.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use rig_acp_discovery::can_install_all;
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() {
+///     for (kind, result) in can_install_all().await {
+///         match result {
+///             Ok(()) => println!("{}: ready to install", kind.display_name()),
+///             Err(e) => println!("{}: {}", kind.display_name(), e.fix_suggestion()),
+///         }
+///     }
+/// }
+/// ```
+pub async fn can_install_all() -> HashMap<AgentKind, Result<(), InstallError>> {
+    let futures: Vec<_> = AgentKind::all()
+        .map(|kind| async move { (kind, can_install(kind).await) })
+        .collect();
+
+    join_all(futures).await.into_iter().collect()
+}
+
+/// Check if a specific install method's prerequisites are met.
+///
+/// Like [`can_install`], but checks the prerequisites listed on `method`
+/// (`InstallMethod::prerequisites`) rather than the agent's primary
+/// prerequisites. Use this to tell whether an alternative method, such as
+/// npm, is ready to run.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use rig_acp_discovery::{AgentKind, can_install_method};
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() {
+///     let info = AgentKind::ClaudeCode.install_info();
+///     for method in &info.alternatives {
+///         let ready = can_install_method(AgentKind::ClaudeCode, method).await.is_ok();
+///         println!("{}: ready = {}", method.description, ready);
+///     }
+/// }
+/// ```
+pub async fn can_install_method(
+    kind: AgentKind,
+    method: &InstallMethod,
+) -> Result<(), InstallError> {
+    can_install_method_with(kind, method, |prereq| async move {
+        check_prerequisite(&prereq).await
+    })
+    .await
+}
+
+/// List the install methods whose prerequisites are already satisfied.
+///
+/// Runs [`can_install_method`] for every method in
+/// [`InstallInfo::methods`](crate::InstallInfo::methods) concurrently and
+/// returns those that pass, primary first. This surfaces, for example,
+/// that the native installer is ready while npm needs Node.js.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use rig_acp_discovery::{AgentKind, ready_methods};
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() {
+///     for ready in ready_methods(AgentKind::ClaudeCode).await {
+///         println!("Ready: {}", ready.method.raw_command);
+///     }
+/// }
+/// ```
+pub async fn ready_methods(kind: AgentKind) -> Vec<MethodDescriptor> {
+    ready_methods_with(
+        kind,
+        |prereq| async move { check_prerequisite(&prereq).await },
+    )
+    .await
+}
+
+/// [`can_install_method`] with an injectable prerequisite check.
+async fn can_install_method_with<F, Fut>(
+    kind: AgentKind,
+    method: &InstallMethod,
+    check: F,
+) -> Result<(), InstallError>
+where
+    F: Fn(Prerequisite) -> Fut,
+    Fut: Future<Output = Result<(), InstallError>>,
+{
+    let info = kind.install_info();
+    if !info.is_supported {
+        return Err(InstallError::UnsupportedPlatform {
+            agent: kind,
+            fix: fixes::render(fixes::UNSUPPORTED_PLATFORM, &[("docs_url", &info.docs_url)]),
+        });
     }
 
-    Ok(())
+    check_all(&method.prerequisites, check).await
+}
+
+/// [`ready_methods`] with an injectable prerequisite check.
+async fn ready_methods_with<F, Fut>(kind: AgentKind, check: F) -> Vec<MethodDescriptor>
+where
+    F: Fn(Prerequisite) -> Fut,
+    Fut: Future<Output = Result<(), InstallError>>,
+{
+    let methods = kind.install_info().methods();
+    let checks = methods
+        .iter()
+        .map(|descriptor| can_install_method_with(kind, &descriptor.method, &check));
+    let results = join_all(checks).await;
+
+    methods
+        .into_iter()
+        .zip(results)
+        .filter_map(|(descriptor, result)| result.ok().map(|()| descriptor))
+        .collect()
 }
 
 /// Check a single prerequisite.
 ///
 /// Runs the check_command and verifies the version meets the minimum requirement.
-async fn check_prerequisite(prereq: &crate::Prerequisite) -> Result<(), InstallError> {
+async fn check_prerequisite(prereq: &Prerequisite) -> Result<(), InstallError> {
     let check_command = match &prereq.check_command {
         Some(cmd) => cmd,
         None => return Ok(()), // No check command means we can't verify, assume OK
@@ -90,14 +266,7 @@ async fn check_prerequisite(prereq: &crate::Prerequisite) -> Result<(), InstallE
             return Err(InstallError::PrerequisiteMissing {
                 name: prereq.name.clone(),
                 install_url: prereq.install_url.clone(),
-                fix: format!(
-                    "Install {} from {}",
-                    prereq.name,
-                    prereq
-                        .install_url
-                        .as_deref()
-                        .unwrap_or("the official website")
-                ),
+                fix: prerequisite_missing_fix(prereq),
             });
         }
     };
@@ -128,14 +297,7 @@ async fn check_prerequisite(prereq: &crate::Prerequisite) -> Result<(), InstallE
             return Err(InstallError::PrerequisiteMissing {
                 name: prereq.name.clone(),
                 install_url: prereq.install_url.clone(),
-                fix: format!(
-                    "Install {} from {}",
-                    prereq.name,
-                    prereq
-                        .install_url
-                        .as_deref()
-                        .unwrap_or("the official website")
-                ),
+                fix: prerequisite_missing_fix(prereq),
             });
         }
     };
@@ -154,13 +316,28 @@ async fn check_prerequisite(prereq: &crate::Prerequisite) -> Result<(), InstallE
             name: prereq.name.clone(),
             required: format!("{}+", required_major),
             found: format!("{}.{}", found_major, found_minor),
-            fix: format!("Upgrade {} to version {}+", prereq.name, required_major),
+            fix: fixes::render(
+                fixes::PREREQUISITE_VERSION_MISMATCH,
+                &[("name", &prereq.name), ("required", &required_major)],
+            ),
         });
     }
 
     Ok(())
 }
 
+/// Fix suggestion for a prerequisite that is missing or unreadable.
+fn prerequisite_missing_fix(prereq: &Prerequisite) -> String {
+    let install_url = prereq
+        .install_url
+        .as_deref()
+        .unwrap_or(fixes::UNKNOWN_INSTALL_URL);
+    fixes::render(
+        fixes::PREREQUISITE_MISSING,
+        &[("name", &prereq.name), ("install_url", &install_url)],
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -225,9 +402,163 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_can_install_all_returns_all_agents() {
+        let all = can_install_all().await;
+        assert_eq!(all.len(), AgentKind::all().count());
+        // Claude Code has no prerequisites
+        assert!(all[&AgentKind::ClaudeCode].is_ok());
+    }
+
     #[test]
     fn test_install_options_default() {
         let opts = InstallOptions::default();
         assert_eq!(opts.timeout, Duration::from_secs(300));
     }
+
+    /// Prerequisite check simulating a system without Node.js.
+    async fn without_node(prereq: Prerequisite) -> Result<(), InstallError> {
+        if prereq.name.contains("Node.js") {
+            Err(InstallError::PrerequisiteMissing {
+                name: prereq.name,
+                install_url: prereq.install_url,
+                fix: "Install Node.js".to_string(),
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prerequisites_checked_concurrently() {
+        let named = |name: &str| Prerequisite {
+            name: name.to_string(),
+            check_command: None,
+            install_url: None,
+        };
+        let prerequisites = [named("Node.js 18+"), named("git")];
+
+        let start = std::time::Instant::now();
+        let result = check_all(&prerequisites, |_| async {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            Ok(())
+        })
+        .await;
+
+        assert!(result.is_ok());
+        // Closer to one check (300ms) than to both (600ms)
+        assert!(start.elapsed() < Duration::from_millis(500));
+    }
+
+    #[tokio::test]
+    async fn test_check_all_returns_first_error_in_order() {
+        let prerequisites = [
+            Prerequisite {
+                name: "git".to_string(),
+                check_command: None,
+                install_url: None,
+            },
+            Prerequisite {
+                name: "Node.js 18+".to_string(),
+                check_command: None,
+                install_url: None,
+            },
+        ];
+
+        let result = check_all(&prerequisites, |prereq| async move {
+            // The second prerequisite fails first
+            let delay = if prereq.name == "git" { 100 } else { 0 };
+            tokio::time::sleep(Duration::from_millis(delay)).await;
+            Err(InstallError::PrerequisiteMissing {
+                name: prereq.name,
+                install_url: None,
+                fix: "Install it".to_string(),
+            })
+        })
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(InstallError::PrerequisiteMissing { name, .. }) if name == "git"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_ready_methods_without_node() {
+        let ready = ready_methods_with(AgentKind::ClaudeCode, without_node).await;
+
+        // The native installer is ready, the npm alternative is not
+        assert_eq!(ready[0].index, 0);
+        assert!(ready.iter().all(|d| d.method.command.program != "npm"));
+        let info = AgentKind::ClaudeCode.install_info();
+        // Every method except npm
+        assert_eq!(ready.len(), info.methods().len() - 1);
+
+        // npm-only agents have no ready methods
+        assert!(ready_methods_with(AgentKind::Codex, without_node)
+            .await
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_can_install_method_checks_method_prereqs() {
+        let info = AgentKind::ClaudeCode.install_info();
+        let npm = info
+            .alternatives
+            .iter()
+            .find(|m| m.command.program == "npm")
+            .unwrap();
+
+        assert!(
+            can_install_method_with(AgentKind::ClaudeCode, &info.primary, without_node)
+                .await
+                .is_ok()
+        );
+        assert!(matches!(
+            can_install_method_with(AgentKind::ClaudeCode, npm, without_node).await,
+            Err(InstallError::PrerequisiteMissing { .. })
+        ));
+    }
+
+    /// Claude Code's install info with a primary installer that doesn't exist.
+    fn info_with_missing_primary(alternatives: Vec<InstallMethod>) -> InstallInfo {
+        let mut info = AgentKind::ClaudeCode.install_info();
+        info.primary.command.program = "definitely-not-a-real-installer-xyz".to_string();
+        info.alternatives = alternatives;
+        info
+    }
+
+    #[tokio::test]
+    async fn test_can_install_fails_when_primary_program_missing() {
+        let info = info_with_missing_primary(vec![]);
+        let result =
+            can_install_info_with(AgentKind::ClaudeCode, &info, |_| false, without_node).await;
+
+        match result {
+            Err(InstallError::PrerequisiteMissing { name, fix, .. }) => {
+                assert_eq!(name, "definitely-not-a-real-installer-xyz");
+                assert!(fix.contains(&name), "{}", fix);
+            }
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_can_install_falls_back_to_alternative_program() {
+        let npm = AgentKind::Codex.install_info().primary;
+        let info = info_with_missing_primary(vec![npm]);
+        let npm_only = |program: &str| program == "npm";
+
+        let all_met = |_: Prerequisite| async { Ok(()) };
+        assert!(
+            can_install_info_with(AgentKind::ClaudeCode, &info, npm_only, all_met)
+                .await
+                .is_ok()
+        );
+        // The alternative's own prerequisites (Node.js for npm) are checked
+        assert!(matches!(
+            can_install_info_with(AgentKind::ClaudeCode, &info, npm_only, without_node).await,
+            Err(InstallError::PrerequisiteMissing { name, .. }) if name.contains("Node.js")
+        ));
+    }
 }
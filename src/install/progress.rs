@@ -2,9 +2,17 @@
 //!
 //! This module provides types for tracking and reporting installation progress.
 //! The [`InstallProgress`] enum represents discrete stages of installation that
-//! can be reported to users via a callback.
+//! can be reported to users via a callback. [`InstallOutcome`] describes
+//! a successful installation (with npm's [`NpmSummary`] for npm
+//! installs), and [`UninstallProgress`] the stages of an uninstallation.
+//! [`ProgressAggregator`] combines the events of several installations
+//! into one overall fraction.
 
+use crate::install::StructuredCommand;
 use crate::AgentKind;
+use semver::Version;
+use std::collections::HashMap;
+use std::path::PathBuf;
 use std::time::Duration;
 
 /// Progress stages during agent installation.
@@ -26,7 +34,13 @@ use std::time::Duration;
 ///         InstallProgress::CheckingPrerequisites => {
 ///             println!("Checking prerequisites...");
 ///         }
-///         InstallProgress::Downloading { agent, estimated_remaining } => {
+///         InstallProgress::UsingMethod { description, .. } => {
+///             println!("Installing with: {}", description);
+///         }
+///         InstallProgress::Resolved { version } => {
+///             println!("About to install version {}", version);
+///         }
+///         InstallProgress::Downloading { agent, estimated_remaining, .. } => {
 ///             if let Some(remaining) = estimated_remaining {
 ///                 println!("Downloading {} ({:?} remaining)", agent.display_name(), remaining);
 ///             } else {
@@ -39,13 +53,18 @@ use std::time::Duration;
 ///         InstallProgress::Verifying { agent } => {
 ///             println!("Verifying {} installation...", agent.display_name());
 ///         }
+///         InstallProgress::VerifyAttempt { attempt, max } => {
+///             println!("Verification attempt {}/{}", attempt, max);
+///         }
 ///         InstallProgress::Completed { agent } => {
 ///             println!("{} installed successfully!", agent.display_name());
 ///         }
+///         _ => {}
 ///     }
 /// }
 /// ```
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub enum InstallProgress {
     /// Installation has started.
     Started {
@@ -56,11 +75,46 @@ pub enum InstallProgress {
     /// Checking prerequisites before installation.
     CheckingPrerequisites,
 
+    /// The install method to run was chosen.
+    ///
+    /// Usually the agent's primary method; an alternative is used when the
+    /// primary method's program (such as `brew` or `scoop`) isn't
+    /// available. Not reported when `InstallOptions::command_override` is
+    /// set.
+    UsingMethod {
+        /// The agent being installed.
+        agent: AgentKind,
+        /// Description of the chosen method (e.g., "Install via npm").
+        description: String,
+    },
+
+    /// The package version about to be installed was resolved.
+    ///
+    /// Reported for npm install methods when the `network` feature is
+    /// enabled, after the dist-tag (e.g., `latest`) is resolved to a
+    /// concrete version with `npm view`. Skipped if the lookup fails.
+    Resolved {
+        /// The version that will be installed.
+        version: Version,
+    },
+
     /// Downloading the agent.
+    ///
+    /// Reported after `Installing` when the installer's output first shows
+    /// a download (such as npm `http fetch` lines). For curl-based install
+    /// scripts it is reported again each time the progress of a curl
+    /// transfer shown in the installer's output increases. That is always
+    /// the fetch of the script itself, and the agent binary only if the
+    /// script shows its own download progress. Installers that print
+    /// nothing of the kind never report it.
     Downloading {
         /// The agent being downloaded.
         agent: AgentKind,
-        /// Estimated time remaining, if known.
+        /// Percentage of the current transfer completed, if known
+        /// (curl-based installers only).
+        percent: Option<f64>,
+        /// Estimated time remaining for the current transfer, measured from
+        /// its start, if known (curl-based installers only).
         estimated_remaining: Option<Duration>,
     },
 
@@ -70,17 +124,66 @@ pub enum InstallProgress {
         agent: AgentKind,
     },
 
+    /// The installer is still running.
+    ///
+    /// Reported every `InstallOptions::heartbeat_interval`, if set, while the
+    /// installer runs, so a spinner keeps moving even when the installer
+    /// prints nothing for a long time.
+    Heartbeat {
+        /// The agent being installed.
+        agent: AgentKind,
+        /// Time since the installer started.
+        elapsed: Duration,
+    },
+
+    /// The installer failed with a network error and is being run again.
+    ///
+    /// Reported before each retry when `InstallOptions::max_retries` is
+    /// above 0, after waiting `retry_backoff * attempt`.
+    Retrying {
+        /// The agent being installed.
+        agent: AgentKind,
+        /// The retry number, starting at 1.
+        attempt: u32,
+        /// The maximum number of retries (`InstallOptions::max_retries`).
+        max: u32,
+    },
+
     /// Verifying the installation.
     Verifying {
         /// The agent being verified.
         agent: AgentKind,
     },
 
+    /// A verification attempt is starting.
+    ///
+    /// Reported once per attempt after `Verifying`, so UIs can show
+    /// feedback while waiting for PATH changes to settle.
+    VerifyAttempt {
+        /// The attempt number, starting at 1.
+        attempt: u32,
+        /// The maximum number of attempts (`InstallOptions::verify_attempts`).
+        max: u32,
+    },
+
     /// Installation completed successfully.
     Completed {
         /// The agent that was installed.
         agent: AgentKind,
     },
+
+    /// An update finished, with the versions before and after.
+    ///
+    /// Reported by `update()` after `Completed`. Either version is `None`
+    /// if it couldn't be read.
+    Updated {
+        /// The agent that was updated.
+        agent: AgentKind,
+        /// Version installed before the update.
+        from: Option<Version>,
+        /// Version installed after the update.
+        to: Option<Version>,
+    },
 }
 
 impl InstallProgress {
@@ -98,10 +201,16 @@ impl InstallProgress {
         match self {
             Self::Started { .. } => "Starting installation",
             Self::CheckingPrerequisites => "Checking prerequisites",
+            Self::UsingMethod { .. } => "Choosing install method",
+            Self::Resolved { .. } => "Resolved package version",
             Self::Downloading { .. } => "Downloading",
             Self::Installing { .. } => "Installing",
+            Self::Heartbeat { .. } => "Installing",
+            Self::Retrying { .. } => "Retrying installation",
             Self::Verifying { .. } => "Verifying installation",
+            Self::VerifyAttempt { .. } => "Verifying installation",
             Self::Completed { .. } => "Installation complete",
+            Self::Updated { .. } => "Update complete",
         }
     }
 
@@ -123,6 +232,82 @@ impl InstallProgress {
     }
 }
 
+/// Progress stages during agent uninstallation.
+///
+/// The uninstall counterpart of [`InstallProgress`], reported by
+/// `uninstall()`.
+///
+/// # Example
+///
+/// ```rust
+/// use rig_acp_discovery::UninstallProgress;
+///
+/// fn on_progress(progress: UninstallProgress) {
+///     match &progress {
+///         UninstallProgress::Uninstalling { agent } => {
+///             println!("Removing {}...", agent.display_name());
+///         }
+///         UninstallProgress::Completed { agent } => {
+///             println!("{} removed", agent.display_name());
+///         }
+///         _ => println!("{}", progress.description()),
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum UninstallProgress {
+    /// Uninstallation has started.
+    Started {
+        /// The agent being uninstalled.
+        agent: AgentKind,
+    },
+
+    /// Running the uninstall command.
+    Uninstalling {
+        /// The agent being uninstalled.
+        agent: AgentKind,
+    },
+
+    /// Checking that the agent is no longer detected.
+    Verifying {
+        /// The agent being verified.
+        agent: AgentKind,
+    },
+
+    /// Uninstallation completed successfully.
+    Completed {
+        /// The agent that was uninstalled.
+        agent: AgentKind,
+    },
+}
+
+impl UninstallProgress {
+    /// Get a human-readable description of the current progress stage.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rig_acp_discovery::{AgentKind, UninstallProgress};
+    ///
+    /// let progress = UninstallProgress::Uninstalling { agent: AgentKind::Codex };
+    /// assert_eq!(progress.description(), "Uninstalling");
+    /// ```
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Started { .. } => "Starting uninstallation",
+            Self::Uninstalling { .. } => "Uninstalling",
+            Self::Verifying { .. } => "Verifying removal",
+            Self::Completed { .. } => "Uninstallation complete",
+        }
+    }
+
+    /// Check if this progress stage indicates completion.
+    pub fn is_complete(&self) -> bool {
+        matches!(self, Self::Completed { .. })
+    }
+}
+
 /// Options for controlling installation behavior.
 ///
 /// This struct allows customizing installation parameters such as timeout.
@@ -141,6 +326,13 @@ impl InstallProgress {
 /// // Custom timeout
 /// let options = InstallOptions {
 ///     timeout: Duration::from_secs(600),
+///     ..Default::default()
+/// };
+///
+/// // Keep the UI responsive during large installs
+/// let options = InstallOptions {
+///     low_priority: true,
+///     ..Default::default()
 /// };
 /// ```
 #[derive(Debug, Clone)]
@@ -149,13 +341,412 @@ pub struct InstallOptions {
     ///
     /// Default: 5 minutes (300 seconds).
     pub timeout: Duration,
+
+    /// Run the installer at reduced CPU priority.
+    ///
+    /// On Unix the installer is spawned via `nice`; on Windows it runs
+    /// with the below-normal priority class. This keeps the calling
+    /// application responsive while large installs run.
+    ///
+    /// Default: `false`
+    pub low_priority: bool,
+
+    /// Global prefix for npm-based installs.
+    ///
+    /// When set, npm install methods run with `NPM_CONFIG_PREFIX` pointing
+    /// at this directory, so packages install under it instead of npm's
+    /// configured global prefix. The prefix's `bin` directory must be on
+    /// PATH for the installed agent to be detected.
+    ///
+    /// Default: `None` (use npm's configured prefix)
+    pub npm_prefix: Option<PathBuf>,
+
+    /// Command to run instead of the agent's primary install method.
+    ///
+    /// When set, this replaces the resolved install command entirely.
+    /// Prerequisites for the agent are still checked before it runs.
+    ///
+    /// Default: `None` (use the agent's primary install method)
+    pub command_override: Option<StructuredCommand>,
+
+    /// Capture the installer's output and return it on success.
+    ///
+    /// When set to `true`, `InstallOutcome::log` contains the installer's
+    /// complete stdout and stderr. Output is always attached to the error
+    /// when installation fails, regardless of this setting.
+    ///
+    /// Default: `false`
+    pub capture_log: bool,
+
+    /// Number of times to check that the agent is detectable after install.
+    ///
+    /// PATH changes from an installer can take a moment to become visible,
+    /// so verification waits briefly before each attempt and retries until
+    /// the agent is detected or the attempts run out. Values below 1 are
+    /// treated as 1.
+    ///
+    /// Default: 3
+    pub verify_attempts: u32,
+
+    /// Record the install in a marker file while it runs.
+    ///
+    /// When set to `true`, a marker is written to the user's state
+    /// directory before the installer runs and removed once the install
+    /// finishes, so `had_interrupted_install()` can tell a later run that
+    /// this install was killed or cancelled partway.
+    ///
+    /// Default: `false`
+    pub write_marker: bool,
+
+    /// Skip post-install verification.
+    ///
+    /// When set to `true`, `install()` returns as soon as the installer
+    /// exits successfully, without waiting for PATH changes or running
+    /// detection. `InstallOutcome::version` is then `None`. Useful for
+    /// batch installs that verify later.
+    ///
+    /// Default: `false`
+    pub skip_verification: bool,
+
+    /// Exact version to install with npm-based methods.
+    ///
+    /// When set, the package argument of an npm install command is pinned
+    /// to this version (e.g., `@openai/codex@0.87.0`). Other install
+    /// methods and `command_override` are not changed. Cannot be combined
+    /// with `dist_tag`.
+    ///
+    /// Default: `None` (install the method's default version)
+    pub version: Option<Version>,
+
+    /// npm dist-tag to install with npm-based methods (e.g., `"beta"`).
+    ///
+    /// When set, the package argument of an npm install command is
+    /// installed from this tag (e.g., `@openai/codex@beta`). Other install
+    /// methods and `command_override` are not changed. Cannot be combined
+    /// with `version`.
+    ///
+    /// Default: `None` (install the method's default version)
+    pub dist_tag: Option<String>,
+
+    /// Maximum bytes of installer output kept in memory, per stream.
+    ///
+    /// stdout and stderr are read as the installer runs and only the last
+    /// this many bytes of each are kept (with a note marking the cut), so
+    /// a runaway installer can't exhaust memory while the errors printed
+    /// at the end survive. The exit status is unaffected.
+    ///
+    /// Default: 1 MiB
+    pub max_captured_output: usize,
+
+    /// How often to report `InstallProgress::Heartbeat` while the
+    /// installer runs.
+    ///
+    /// `None` (or a zero interval) disables heartbeats.
+    ///
+    /// Default: None (no heartbeats)
+    pub heartbeat_interval: Option<Duration>,
+
+    /// Number of times to rerun the installer after a network error.
+    ///
+    /// An installer failure classified as `InstallError::Network` (such as
+    /// npm's `ETIMEDOUT` or `ENOTFOUND`) is retried up to this many times,
+    /// reporting `InstallProgress::Retrying` before each retry. Other
+    /// failures are never retried.
+    ///
+    /// Default: 0 (no retries)
+    pub max_retries: u32,
+
+    /// Base delay between retries after a network error.
+    ///
+    /// Retry `n` waits `retry_backoff * n` (saturating at `Duration::MAX`),
+    /// so later retries wait longer.
+    ///
+    /// Default: 2 seconds
+    pub retry_backoff: Duration,
+
+    /// Most installations `install_many()` runs at once.
+    ///
+    /// `None` uses the number of CPUs available to the process, as
+    /// reported by [`std::thread::available_parallelism`]. Installs that
+    /// share a package manager are serialized regardless. See
+    /// [`effective_max_parallel`](Self::effective_max_parallel).
+    ///
+    /// Default: `None` (one per available CPU)
+    pub max_parallel: Option<usize>,
+}
+
+impl InstallOptions {
+    /// The number of installations `install_many()` runs at once.
+    ///
+    /// `max_parallel` if set, otherwise the available parallelism (1 if
+    /// it can't be determined). Never less than 1.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rig_acp_discovery::InstallOptions;
+    ///
+    /// let sequential = InstallOptions {
+    ///     max_parallel: Some(1),
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(sequential.effective_max_parallel(), 1);
+    /// assert!(InstallOptions::default().effective_max_parallel() >= 1);
+    /// ```
+    pub fn effective_max_parallel(&self) -> usize {
+        self.max_parallel
+            .unwrap_or_else(|| {
+                std::thread::available_parallelism().map_or(1, std::num::NonZeroUsize::get)
+            })
+            .max(1)
+    }
 }
 
 impl Default for InstallOptions {
     fn default() -> Self {
         Self {
             timeout: Duration::from_secs(300), // 5 minutes
+            low_priority: false,
+            npm_prefix: None,
+            command_override: None,
+            capture_log: false,
+            verify_attempts: 3,
+            write_marker: false,
+            skip_verification: false,
+            version: None,
+            dist_tag: None,
+            max_captured_output: 1024 * 1024,
+            heartbeat_interval: None,
+            max_retries: 0,
+            retry_backoff: Duration::from_secs(2),
+            max_parallel: None,
+        }
+    }
+}
+
+/// The result of a successful installation.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use rig_acp_discovery::{AgentKind, InstallOptions, install};
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() {
+///     let options = InstallOptions {
+///         capture_log: true,
+///         ..Default::default()
+///     };
+///     let outcome = install(AgentKind::Codex, options, |_| {}).await.unwrap();
+///     println!("Installed {:?}", outcome.version);
+///     if let Some(log) = &outcome.log {
+///         println!("Installer output:\n{}", log);
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct InstallOutcome {
+    /// The agent that was installed.
+    pub agent: AgentKind,
+
+    /// Version reported by the installed agent, if it could be parsed.
+    pub version: Option<Version>,
+
+    /// Complete installer output (stdout followed by stderr).
+    ///
+    /// `Some` only when `InstallOptions::capture_log` is enabled.
+    pub log: Option<String>,
+
+    /// The agent was installed but can't be found by name until a new
+    /// shell (or a restart of this application) picks up the updated PATH.
+    ///
+    /// Set on Windows when verification can't resolve the agent by name but
+    /// finds it in a known install location. `version` is `None` in this
+    /// case.
+    pub needs_restart: bool,
+
+    /// npm's summary of what the install changed, such as
+    /// `added 1 package in 3s` or `up to date, audited 1 package in 1s`.
+    ///
+    /// `None` if the installer didn't print one, as for installs that
+    /// don't use npm.
+    pub npm_summary: Option<NpmSummary>,
+}
+
+/// The package counts from npm's summary line after `npm install`.
+///
+/// An "up to date" summary, or one with no packages added or changed,
+/// means npm left the existing installation as it was.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use rig_acp_discovery::{install, AgentKind, InstallOptions};
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() {
+///     let outcome = install(AgentKind::Codex, InstallOptions::default(), |_| {})
+///         .await
+///         .unwrap();
+///     if outcome.npm_summary.is_some_and(|summary| summary.is_noop()) {
+///         println!("Codex was already installed; nothing changed");
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct NpmSummary {
+    /// Number of packages added.
+    pub added: usize,
+    /// Number of packages removed.
+    pub removed: usize,
+    /// Number of packages changed (updated in place).
+    pub changed: usize,
+    /// npm reported that everything was already up to date.
+    pub up_to_date: bool,
+}
+
+impl NpmSummary {
+    /// Whether npm installed nothing new.
+    pub fn is_noop(&self) -> bool {
+        self.up_to_date || (self.added == 0 && self.changed == 0)
+    }
+
+    /// Find npm's summary in installer output, using the last one printed.
+    ///
+    /// Summary lines start with `added`, `removed`, `changed` or
+    /// `up to date`, followed by comma-separated counts such as
+    /// `added 2 packages, changed 1 package, and audited 4 packages in 3s`.
+    pub(crate) fn parse(output: &str) -> Option<Self> {
+        output.lines().rev().find_map(|line| {
+            let line = line.trim();
+            let up_to_date = line.starts_with("up to date");
+            if !up_to_date
+                && !["added ", "removed ", "changed "]
+                    .iter()
+                    .any(|p| line.starts_with(p))
+            {
+                return None;
+            }
+
+            let mut summary = NpmSummary {
+                up_to_date,
+                ..Default::default()
+            };
+            for part in line.split(", ") {
+                let mut words = part.trim_start_matches("and ").split_whitespace();
+                let (Some(verb), Some(count), Some(noun)) =
+                    (words.next(), words.next(), words.next())
+                else {
+                    continue;
+                };
+                let Ok(count) = count.parse() else { continue };
+                if !noun.starts_with("package") {
+                    continue;
+                }
+                match verb {
+                    "added" => summary.added = count,
+                    "removed" => summary.removed = count,
+                    "changed" => summary.changed = count,
+                    _ => {}
+                }
+            }
+            Some(summary)
+        })
+    }
+}
+
+/// Combines progress events from several installations into one fraction.
+///
+/// Each agent contributes between 0.0 and 1.0 according to its latest
+/// stage, so the overall fraction is (agents completed + the current
+/// stage fraction of agents in progress) / total. An agent's contribution
+/// never moves backwards, which keeps a global progress bar monotonic.
+///
+/// `InstallProgress` events such as `CheckingPrerequisites` don't name
+/// their agent, so the caller passes the agent alongside each event.
+///
+/// # Example
+///
+/// ```rust
+/// use rig_acp_discovery::{AgentKind, InstallProgress, ProgressAggregator};
+///
+/// let mut overall = ProgressAggregator::new(2);
+/// overall.update(AgentKind::Codex, &InstallProgress::Completed { agent: AgentKind::Codex });
+/// let fraction = overall.update(
+///     AgentKind::Gemini,
+///     &InstallProgress::Started { agent: AgentKind::Gemini },
+/// );
+/// assert_eq!(fraction, 0.5);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ProgressAggregator {
+    total: usize,
+    stages: HashMap<AgentKind, f64>,
+}
+
+impl ProgressAggregator {
+    /// Create an aggregator for `total` installations.
+    pub fn new(total: usize) -> Self {
+        Self {
+            total,
+            stages: HashMap::new(),
+        }
+    }
+
+    /// Record a progress event for `agent` and return the overall fraction.
+    pub fn update(&mut self, agent: AgentKind, progress: &InstallProgress) -> f64 {
+        self.advance(agent, stage_fraction(progress));
+        self.fraction()
+    }
+
+    /// Mark `agent` as finished and return the overall fraction.
+    ///
+    /// Use this when an installation fails, since no `Completed` event is
+    /// reported for it but it no longer has work remaining.
+    pub fn finish(&mut self, agent: AgentKind) -> f64 {
+        self.advance(agent, 1.0);
+        self.fraction()
+    }
+
+    /// The overall fraction complete, from 0.0 to 1.0.
+    ///
+    /// An aggregator with no installations is complete.
+    pub fn fraction(&self) -> f64 {
+        if self.total == 0 {
+            return 1.0;
+        }
+        let done: f64 = self.stages.values().sum();
+        (done / self.total as f64).min(1.0)
+    }
+
+    fn advance(&mut self, agent: AgentKind, fraction: f64) {
+        let stage = self.stages.entry(agent).or_insert(0.0);
+        *stage = stage.max(fraction);
+    }
+}
+
+/// How far through a single installation a progress stage is.
+///
+/// Downloads are reported while the installer runs, so they fill the span
+/// between `Installing` and `Verifying` by their percentage.
+fn stage_fraction(progress: &InstallProgress) -> f64 {
+    match progress {
+        InstallProgress::Started { .. } => 0.0,
+        InstallProgress::CheckingPrerequisites => 0.1,
+        InstallProgress::UsingMethod { .. } => 0.12,
+        InstallProgress::Resolved { .. } => 0.15,
+        InstallProgress::Installing { .. } => 0.4,
+        InstallProgress::Downloading { percent, .. } => {
+            0.4 + 0.3 * percent.unwrap_or(0.0).clamp(0.0, 100.0) / 100.0
         }
+        InstallProgress::Heartbeat { .. } => 0.4,
+        InstallProgress::Retrying { .. } => 0.4,
+        InstallProgress::Verifying { .. } => 0.8,
+        InstallProgress::VerifyAttempt { attempt, max } => {
+            0.8 + 0.2 * f64::from(attempt.saturating_sub(1)) / f64::from((*max).max(1))
+        }
+        InstallProgress::Completed { .. } => 1.0,
+        InstallProgress::Updated { .. } => 1.0,
     }
 }
 
@@ -176,9 +767,25 @@ mod tests {
             InstallProgress::CheckingPrerequisites.description(),
             "Checking prerequisites"
         );
+        assert_eq!(
+            InstallProgress::UsingMethod {
+                agent: AgentKind::Codex,
+                description: "Install via npm".to_string()
+            }
+            .description(),
+            "Choosing install method"
+        );
+        assert_eq!(
+            InstallProgress::Resolved {
+                version: Version::new(0, 90, 0)
+            }
+            .description(),
+            "Resolved package version"
+        );
         assert_eq!(
             InstallProgress::Downloading {
                 agent: AgentKind::Codex,
+                percent: None,
                 estimated_remaining: None
             }
             .description(),
@@ -221,6 +828,7 @@ mod tests {
         assert!(!InstallProgress::CheckingPrerequisites.is_complete());
         assert!(!InstallProgress::Downloading {
             agent: AgentKind::Codex,
+            percent: Some(50.0),
             estimated_remaining: Some(Duration::from_secs(30))
         }
         .is_complete());
@@ -238,20 +846,74 @@ mod tests {
     fn test_install_options_default() {
         let opts = InstallOptions::default();
         assert_eq!(opts.timeout, Duration::from_secs(300));
+        assert!(!opts.low_priority);
+        assert_eq!(opts.heartbeat_interval, None);
+        assert!(!opts.write_marker);
+        assert_eq!(opts.max_parallel, None);
+        assert_eq!(opts.max_retries, 0);
+        assert_eq!(opts.retry_backoff, Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_effective_max_parallel() {
+        let opts = InstallOptions {
+            max_parallel: Some(0),
+            ..Default::default()
+        };
+        assert_eq!(opts.effective_max_parallel(), 1);
+
+        let opts = InstallOptions {
+            max_parallel: Some(3),
+            ..Default::default()
+        };
+        assert_eq!(opts.effective_max_parallel(), 3);
     }
 
     #[test]
     fn test_install_options_custom() {
         let opts = InstallOptions {
             timeout: Duration::from_secs(600),
+            ..Default::default()
         };
         assert_eq!(opts.timeout, Duration::from_secs(600));
     }
 
+    #[test]
+    fn test_npm_summary_parse() {
+        let output = "npm warn deprecated inflight@1.0.6: not supported\n\
+                      \n\
+                      added 2 packages, removed 1 package, changed 3 packages, and audited 10 packages in 4s\n\
+                      \n\
+                      1 package is looking for funding\n\
+                      \x20 run `npm fund` for details\n\
+                      found 0 vulnerabilities\n";
+        assert_eq!(
+            NpmSummary::parse(output),
+            Some(NpmSummary {
+                added: 2,
+                removed: 1,
+                changed: 3,
+                up_to_date: false,
+            })
+        );
+
+        let added = NpmSummary::parse("added 1 package in 3s").unwrap();
+        assert_eq!(added.added, 1);
+        assert!(!added.is_noop());
+
+        let up_to_date = NpmSummary::parse("up to date, audited 1 package in 512ms").unwrap();
+        assert!(up_to_date.up_to_date);
+        assert!(up_to_date.is_noop());
+
+        assert_eq!(NpmSummary::parse("Installing Claude Code...\ndone"), None);
+        assert_eq!(NpmSummary::parse(""), None);
+    }
+
     #[test]
     fn test_install_progress_clone() {
         let progress = InstallProgress::Downloading {
             agent: AgentKind::ClaudeCode,
+            percent: Some(50.0),
             estimated_remaining: Some(Duration::from_secs(30)),
         };
         let cloned = progress.clone();
@@ -262,8 +924,72 @@ mod tests {
     fn test_install_options_clone() {
         let opts = InstallOptions {
             timeout: Duration::from_secs(120),
+            ..Default::default()
         };
         let cloned = opts.clone();
         assert_eq!(opts.timeout, cloned.timeout);
     }
+
+    #[test]
+    fn test_progress_aggregator_two_agents() {
+        let mut overall = ProgressAggregator::new(2);
+        assert_eq!(overall.fraction(), 0.0);
+
+        let mut fractions = Vec::new();
+        for agent in [AgentKind::Codex, AgentKind::Gemini] {
+            for progress in [
+                InstallProgress::Started { agent },
+                InstallProgress::CheckingPrerequisites,
+                InstallProgress::Installing { agent },
+                InstallProgress::Verifying { agent },
+                InstallProgress::VerifyAttempt { attempt: 1, max: 3 },
+                InstallProgress::VerifyAttempt { attempt: 2, max: 3 },
+                InstallProgress::Completed { agent },
+            ] {
+                fractions.push(overall.update(agent, &progress));
+            }
+        }
+
+        assert!(fractions.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(fractions[6], 0.5);
+        assert_eq!(overall.fraction(), 1.0);
+    }
+
+    #[test]
+    fn test_progress_aggregator_failed_agent() {
+        let mut overall = ProgressAggregator::new(2);
+        let agent = AgentKind::Codex;
+        overall.update(agent, &InstallProgress::Installing { agent });
+        assert_eq!(overall.finish(agent), 0.5);
+
+        // A late event does not move the agent backwards
+        assert_eq!(
+            overall.update(agent, &InstallProgress::Started { agent }),
+            0.5
+        );
+        assert_eq!(ProgressAggregator::new(0).fraction(), 1.0);
+    }
+
+    #[test]
+    fn test_progress_aggregator_download_percent() {
+        let mut overall = ProgressAggregator::new(1);
+        let agent = AgentKind::ClaudeCode;
+        let downloading = |percent| InstallProgress::Downloading {
+            agent,
+            percent,
+            estimated_remaining: None,
+        };
+
+        assert_eq!(
+            overall.update(agent, &InstallProgress::Installing { agent }),
+            0.4
+        );
+        assert_eq!(overall.update(agent, &downloading(None)), 0.4);
+        assert!((overall.update(agent, &downloading(Some(50.0))) - 0.55).abs() < 1e-9);
+        assert!((overall.update(agent, &downloading(Some(100.0))) - 0.7).abs() < 1e-9);
+        assert_eq!(
+            overall.update(agent, &InstallProgress::Verifying { agent }),
+            0.8
+        );
+    }
 }
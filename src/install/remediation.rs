@@ -0,0 +1,139 @@
+//! Executable remediations for missing prerequisites.
+//!
+//! This module builds [`StructuredCommand`]s that install a missing
+//! prerequisite (currently Node.js) using the package manager available on
+//! the system. See [`InstallError::remediation`](crate::InstallError::remediation).
+
+use crate::install::StructuredCommand;
+use regex::Regex;
+use std::path::PathBuf;
+
+/// A package manager that can install Node.js.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum NodeManager {
+    /// Node Version Manager, with the directory containing `nvm.sh`.
+    Nvm(PathBuf),
+    /// Homebrew.
+    Brew,
+    /// Windows Package Manager.
+    Winget,
+    /// Debian/Ubuntu apt.
+    Apt,
+}
+
+/// Find the preferred Node.js package manager on this system.
+///
+/// nvm is preferred because it installs per-user without sudo and can
+/// install a specific major version.
+pub(crate) fn detect_node_manager() -> Option<NodeManager> {
+    let nvm_dir = std::env::var_os("NVM_DIR")
+        .map(PathBuf::from)
+        .or_else(|| crate::detection::home_dir().map(|home| home.join(".nvm")));
+    if let Some(dir) = nvm_dir.filter(|dir| dir.join("nvm.sh").is_file()) {
+        return Some(NodeManager::Nvm(dir));
+    }
+
+    [
+        ("brew", NodeManager::Brew),
+        ("winget", NodeManager::Winget),
+        ("apt-get", NodeManager::Apt),
+    ]
+    .into_iter()
+    .find(|(program, _)| which::which(program).is_ok())
+    .map(|(_, manager)| manager)
+}
+
+/// Extract the required major version from text like "18+" or "Node.js 20+".
+pub(crate) fn required_major(text: &str) -> Option<u32> {
+    let re = Regex::new(r"(\d+)\+").expect("Invalid min version regex");
+    re.captures(text)?.get(1)?.as_str().parse().ok()
+}
+
+/// Build the command that installs Node.js with `manager`.
+///
+/// `major` is the minimum required major version, if known. Only nvm can
+/// target it directly; the other managers install their current release.
+pub(crate) fn node_install_command(manager: &NodeManager, major: Option<u32>) -> StructuredCommand {
+    let (program, args): (&str, Vec<String>) = match manager {
+        NodeManager::Nvm(dir) => {
+            let version = major.map_or_else(|| "--lts".to_string(), |m| m.to_string());
+            (
+                "bash",
+                vec![
+                    "-c".to_string(),
+                    format!(
+                        ". \"{}\" && nvm install {}",
+                        dir.join("nvm.sh").display(),
+                        version
+                    ),
+                ],
+            )
+        }
+        NodeManager::Brew => ("brew", vec!["install".to_string(), "node".to_string()]),
+        NodeManager::Winget => (
+            "winget",
+            vec![
+                "install".to_string(),
+                "--id".to_string(),
+                "OpenJS.NodeJS.LTS".to_string(),
+                "--exact".to_string(),
+            ],
+        ),
+        NodeManager::Apt => (
+            "sudo",
+            vec![
+                "apt-get".to_string(),
+                "install".to_string(),
+                "-y".to_string(),
+                "nodejs".to_string(),
+                "npm".to_string(),
+            ],
+        ),
+    };
+
+    StructuredCommand {
+        program: program.to_string(),
+        args,
+        env_vars: vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_required_major() {
+        assert_eq!(required_major("18+"), Some(18));
+        assert_eq!(required_major("Node.js 20+"), Some(20));
+        assert_eq!(required_major("Node.js"), None);
+    }
+
+    #[test]
+    fn test_nvm_installs_required_major() {
+        let manager = NodeManager::Nvm(PathBuf::from("/home/u/.nvm"));
+
+        let cmd = node_install_command(&manager, Some(20));
+        assert_eq!(cmd.program, "bash");
+        assert!(cmd.args[1].contains("/home/u/.nvm/nvm.sh"));
+        assert!(cmd.args[1].ends_with("nvm install 20"));
+
+        let cmd = node_install_command(&manager, None);
+        assert!(cmd.args[1].ends_with("nvm install --lts"));
+    }
+
+    #[test]
+    fn test_package_manager_commands() {
+        let cmd = node_install_command(&NodeManager::Brew, Some(18));
+        assert_eq!(cmd.program, "brew");
+        assert_eq!(cmd.args, ["install", "node"]);
+
+        let cmd = node_install_command(&NodeManager::Winget, Some(18));
+        assert_eq!(cmd.program, "winget");
+        assert!(cmd.args.contains(&"OpenJS.NodeJS.LTS".to_string()));
+
+        let cmd = node_install_command(&NodeManager::Apt, None);
+        assert_eq!(cmd.program, "sudo");
+        assert_eq!(cmd.args[..2], ["apt-get", "install"]);
+    }
+}
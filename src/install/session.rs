@@ -0,0 +1,89 @@
+//! Interactive session detection for installers.
+//!
+//! Native installers (such as `curl | bash` scripts) and package managers
+//! may prompt for confirmation. `install()` pipes their output, so nobody
+//! could see or answer a prompt even from a terminal: it always closes the
+//! installer's stdin and asks known installers to skip their prompts.
+
+use crate::install::StructuredCommand;
+use std::io::IsTerminal;
+
+/// Check whether the current session can answer interactive prompts.
+///
+/// Returns `true` only if both stdin and stdout are terminals, for
+/// callers deciding whether to ask the user something themselves.
+/// [`install`](crate::install) never lets installers prompt: it runs them
+/// with stdin closed and their non-interactive settings enabled (for
+/// example `NONINTERACTIVE=1` for shell installers and Homebrew, and
+/// `npm_config_yes=true` for npm) whether or not this returns `true`.
+///
+/// # Example
+///
+/// ```rust
+/// use rig_acp_discovery::is_interactive_session;
+///
+/// if !is_interactive_session() {
+///     println!("Running without a terminal; installers will not prompt");
+/// }
+/// ```
+pub fn is_interactive_session() -> bool {
+    is_interactive_with(&std::io::stdin(), &std::io::stdout())
+}
+
+/// [`is_interactive_session`] for explicit stdin and stdout handles.
+fn is_interactive_with(stdin: &impl IsTerminal, stdout: &impl IsTerminal) -> bool {
+    stdin.is_terminal() && stdout.is_terminal()
+}
+
+/// Environment variables that make `cmd` skip interactive prompts.
+pub(crate) fn non_interactive_env(cmd: &StructuredCommand) -> Vec<(&'static str, &'static str)> {
+    match cmd.program.as_str() {
+        "npm" => vec![("npm_config_yes", "true")],
+        "brew" | "bash" | "sh" => vec![("NONINTERACTIVE", "1")],
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_piped_session_is_not_interactive() {
+        use std::os::fd::OwnedFd;
+        use std::process::{Command, Stdio};
+
+        let mut child = Command::new("true")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let stdin = OwnedFd::from(child.stdin.take().unwrap());
+        let stdout = OwnedFd::from(child.stdout.take().unwrap());
+
+        assert!(!is_interactive_with(&stdin, &stdout));
+        child.wait().unwrap();
+    }
+
+    #[test]
+    fn test_file_is_not_a_terminal() {
+        let file = tempfile::tempfile().unwrap();
+        assert!(!is_interactive_with(&file, &file));
+    }
+
+    #[test]
+    fn test_non_interactive_env() {
+        let cmd = |program: &str| StructuredCommand {
+            program: program.to_string(),
+            args: vec![],
+            env_vars: vec![],
+        };
+        assert_eq!(
+            non_interactive_env(&cmd("npm")),
+            [("npm_config_yes", "true")]
+        );
+        assert_eq!(non_interactive_env(&cmd("bash")), [("NONINTERACTIVE", "1")]);
+        assert!(non_interactive_env(&cmd("scoop")).is_empty());
+    }
+}
@@ -37,6 +37,38 @@ pub enum InstallLocation {
     System,
 }
 
+/// Where an install method gets the agent from.
+///
+/// This normalizes the package source of an [`InstallMethod`] for display
+/// and filtering. See [`InstallMethod::registry`].
+///
+/// # Example
+///
+/// ```rust
+/// use rig_acp_discovery::{AgentKind, Registry};
+///
+/// let info = AgentKind::Codex.install_info();
+/// assert_eq!(info.primary.registry(), Some(Registry::Npm));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum Registry {
+    /// The npm registry (`npm install -g`).
+    Npm,
+    /// crates.io (`cargo install`).
+    Cargo,
+    /// A Homebrew formula or cask (`brew install`).
+    Brew,
+    /// A Scoop manifest (`scoop install`).
+    Scoop,
+    /// The Windows Package Manager (`winget install`).
+    Winget,
+    /// A shell install script fetched with curl.
+    Curl,
+    /// A PowerShell install script.
+    PowerShell,
+}
+
 /// A structured command for programmatic execution.
 ///
 /// This provides all the information needed to execute an install command
@@ -85,6 +117,7 @@ pub struct StructuredCommand {
 ///     raw_command: "npm install -g @openai/codex".to_string(),
 ///     description: "Install via npm (Node.js package manager)".to_string(),
 ///     location: InstallLocation::UserLocal,
+///     prerequisites: vec![],
 /// };
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -100,6 +133,53 @@ pub struct InstallMethod {
 
     /// Where this method installs to.
     pub location: InstallLocation,
+
+    /// Prerequisites specific to this method (e.g., Node.js for npm).
+    ///
+    /// Unlike `InstallInfo::prerequisites`, which apply to the primary
+    /// method used by `install()`, these let callers tell which
+    /// alternatives are ready to run.
+    #[serde(default)]
+    pub prerequisites: Vec<Prerequisite>,
+}
+
+/// A method for uninstalling an agent.
+///
+/// Returned by `AgentKind::uninstall_method()` for agents whose install
+/// can be removed with a single command.
+///
+/// # Example
+///
+/// ```rust
+/// use rig_acp_discovery::AgentKind;
+///
+/// if let Some(method) = AgentKind::Codex.uninstall_method() {
+///     println!("To uninstall Codex, run: {}", method.raw_command);
+/// }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UninstallMethod {
+    /// Structured command for programmatic execution.
+    pub command: StructuredCommand,
+
+    /// Raw command string for display/copy-paste.
+    pub raw_command: String,
+
+    /// Human-readable description (e.g., "Uninstall via npm").
+    pub description: String,
+}
+
+/// An install method together with its position in [`InstallInfo`].
+///
+/// Index 0 is the primary method; indices 1..N are the alternatives in
+/// order. See [`InstallInfo::methods`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MethodDescriptor {
+    /// Position of the method: 0 for primary, 1..N for alternatives.
+    pub index: usize,
+
+    /// The install method.
+    pub method: InstallMethod,
 }
 
 /// A prerequisite for installation.
@@ -194,6 +274,8 @@ pub struct InstallInfo {
 
     /// Whether this agent is supported on the current platform.
     ///
+    /// Same as [`AgentKind::is_supported_here`](crate::AgentKind::is_supported_here).
+    ///
     /// If `false`, the install commands are provided for informational
     /// purposes but may not work correctly.
     pub is_supported: bool,
@@ -201,3 +283,97 @@ pub struct InstallInfo {
     /// URL to official documentation for this agent.
     pub docs_url: String,
 }
+
+impl InstallMethod {
+    /// The package source this method installs from.
+    ///
+    /// Derived from the method's command. Returns `None` for commands that
+    /// don't match a known registry (e.g., a custom installer).
+    pub fn registry(&self) -> Option<Registry> {
+        let program = self.command.program.as_str();
+        match program {
+            "npm" => Some(Registry::Npm),
+            "cargo" => Some(Registry::Cargo),
+            "brew" => Some(Registry::Brew),
+            "scoop" => Some(Registry::Scoop),
+            "winget" => Some(Registry::Winget),
+            "powershell" | "pwsh" => Some(Registry::PowerShell),
+            "bash" | "sh" if self.command.args.iter().any(|a| a.contains("curl")) => {
+                Some(Registry::Curl)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl InstallInfo {
+    /// All install methods, primary first, with their indices.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rig_acp_discovery::AgentKind;
+    ///
+    /// let info = AgentKind::ClaudeCode.install_info();
+    /// let methods = info.methods();
+    /// assert_eq!(methods[0].index, 0);
+    /// assert_eq!(methods.len(), 1 + info.alternatives.len());
+    /// ```
+    pub fn methods(&self) -> Vec<MethodDescriptor> {
+        std::iter::once(&self.primary)
+            .chain(&self.alternatives)
+            .cloned()
+            .enumerate()
+            .map(|(index, method)| MethodDescriptor { index, method })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AgentKind;
+
+    #[test]
+    fn test_primary_method_registries() {
+        let primary = |kind: AgentKind| kind.install_info().primary.registry();
+
+        #[cfg(not(windows))]
+        {
+            assert_eq!(primary(AgentKind::ClaudeCode), Some(Registry::Curl));
+            assert_eq!(primary(AgentKind::OpenCode), Some(Registry::Curl));
+        }
+        #[cfg(windows)]
+        {
+            assert_eq!(primary(AgentKind::ClaudeCode), Some(Registry::PowerShell));
+            assert_eq!(primary(AgentKind::OpenCode), Some(Registry::Scoop));
+        }
+        assert_eq!(primary(AgentKind::Codex), Some(Registry::Npm));
+        assert_eq!(primary(AgentKind::Gemini), Some(Registry::Npm));
+    }
+
+    #[test]
+    fn test_alternative_registries() {
+        let info = AgentKind::ClaudeCode.install_info();
+        let registries: Vec<_> = info.alternatives.iter().map(|m| m.registry()).collect();
+        assert!(registries.contains(&Some(Registry::Npm)));
+        #[cfg(windows)]
+        assert!(registries.contains(&Some(Registry::Winget)));
+    }
+
+    #[test]
+    fn test_unknown_program_has_no_registry() {
+        let method = InstallMethod {
+            command: StructuredCommand {
+                program: "my-installer".to_string(),
+                args: vec![],
+                env_vars: vec![],
+            },
+            raw_command: "my-installer".to_string(),
+            description: "Custom installer".to_string(),
+            location: InstallLocation::UserLocal,
+            prerequisites: vec![],
+        };
+        assert_eq!(method.registry(), None);
+    }
+}
@@ -0,0 +1,371 @@
+//! Uninstallation of AI coding agents.
+//!
+//! This module provides [`uninstall`], the counterpart of `install()`: it
+//! removes the agent with the package manager that installed it, or its
+//! [`UninstallMethod`], and checks that the agent is gone.
+
+use crate::detect::detect_install_method;
+use crate::fixes;
+use crate::install::executor::{
+    installed_registries, npm_package_index, npm_package_name, run_installer,
+};
+use crate::install::lock::InstallLocks;
+use crate::install::{
+    InstallError, InstallInfo, InstallMethod, InstallOptions, UninstallMethod, UninstallProgress,
+};
+use crate::{detect, AgentKind, AgentStatus, InstallMethodKind};
+use std::future::Future;
+use std::path::Path;
+
+/// Uninstall an agent programmatically.
+///
+/// This function:
+/// 1. Detects the installed agent and chooses its uninstall command
+/// 2. Runs the uninstall command with the configured timeout
+/// 3. Verifies removal via detect() (unless `skip_verification` is set)
+///
+/// The agent is removed the way it was installed, judged from the
+/// detected executable's location, as `update()` does: a copy under a
+/// global `node_modules` is removed with `npm uninstall -g`, a Scoop copy
+/// with `scoop uninstall`, and so on. A native install, or one whose
+/// origin can't be told, uses [`AgentKind::uninstall_method`].
+///
+/// The command runs like an installer: `timeout`, `low_priority`,
+/// `npm_prefix` and `max_captured_output` apply, and it takes turns with
+/// installs that use the same package manager. `command_override`,
+/// `version` and `dist_tag` only affect installs and are ignored.
+///
+/// # Returns
+///
+/// - `Ok(())` if the command succeeded and the agent is no longer detected
+/// - `Err(InstallError::UnsupportedPlatform)` if the agent has no uninstall
+///   command, with a fix pointing at its documentation
+/// - `Err(InstallError::InstallerFailed)` if it was installed by a package
+///   manager none of its install methods use (such as mise)
+/// - `Err(InstallError::UninstallFailed)` if the agent is still detected
+///   afterward
+/// - Other `InstallError`s if the command itself failed
+///
+/// # Consent Model
+///
+/// As with `install()`, calling this function IS consent to uninstall.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use rig_acp_discovery::{uninstall, AgentKind, InstallOptions};
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() {
+///     let result = uninstall(
+///         AgentKind::Codex,
+///         InstallOptions::default(),
+///         |progress| println!("{}", progress.description()),
+///     )
+///     .await;
+///
+///     if let Err(e) = result {
+///         println!("Failed: {}. Fix: {}", e, e.fix_suggestion());
+///     }
+/// }
+/// ```
+pub async fn uninstall<F>(
+    kind: AgentKind,
+    options: InstallOptions,
+    on_progress: F,
+) -> Result<(), InstallError>
+where
+    F: Fn(UninstallProgress) + Send + Sync,
+{
+    let installed = detect(kind).await;
+    let method = uninstall_route(kind, installed.path(), &kind.install_info());
+    uninstall_with(kind, method, &options, &on_progress, || detect(kind)).await
+}
+
+/// Choose the uninstall command for `kind` installed at `path`.
+///
+/// The install method is judged from `path`. The install method whose
+/// registry matches the one that installed the agent is turned into an
+/// uninstall command by [`uninstall_command`]. A native install, one whose
+/// origin is unknown, or no install at all uses
+/// [`AgentKind::uninstall_method`].
+fn uninstall_route(
+    kind: AgentKind,
+    path: Option<&Path>,
+    info: &InstallInfo,
+) -> Result<Option<UninstallMethod>, InstallError> {
+    let installed_by = match path.and_then(detect_install_method) {
+        None | Some(InstallMethodKind::Native) => return Ok(kind.uninstall_method()),
+        Some(installed_by) => installed_by,
+    };
+
+    let registries = installed_registries(&installed_by);
+    std::iter::once(&info.primary)
+        .chain(&info.alternatives)
+        .filter(|method| method.registry().is_some_and(|r| registries.contains(&r)))
+        .find_map(uninstall_command)
+        .map(Some)
+        .ok_or_else(|| InstallError::InstallerFailed {
+            message: format!(
+                "{} was installed with {}, which none of its install methods use",
+                kind.display_name(),
+                installed_by
+            ),
+            exit_code: None,
+            stdout: None,
+            stderr: None,
+            fix: fixes::render(
+                fixes::UNINSTALL_UNSUPPORTED_METHOD,
+                &[("agent", &kind.display_name()), ("method", &installed_by)],
+            ),
+        })
+}
+
+/// The command that removes what a package-manager install method installs.
+///
+/// The method's `install` subcommand becomes `uninstall`, so
+/// `winget install --id X --exact` becomes `winget uninstall --id X --exact`,
+/// and an npm package loses its version or tag. `None` if the command has
+/// no `install` subcommand, as with install scripts.
+fn uninstall_command(method: &InstallMethod) -> Option<UninstallMethod> {
+    let mut command = method.command.clone();
+    let install = command.args.iter().position(|arg| arg == "install")?;
+    command.args[install] = "uninstall".to_string();
+    if command.program == "npm" {
+        if let Some(index) = npm_package_index(&command.args) {
+            command.args[index] = npm_package_name(&command.args[index]).to_string();
+        }
+    }
+
+    let raw_command = std::iter::once(&command.program)
+        .chain(&command.args)
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join(" ");
+    let description = format!("Uninstall via {}", command.program);
+    Some(UninstallMethod {
+        command,
+        raw_command,
+        description,
+    })
+}
+
+/// [`uninstall`] with an injectable method and verification.
+async fn uninstall_with<F, V, Fut>(
+    kind: AgentKind,
+    method: Result<Option<UninstallMethod>, InstallError>,
+    options: &InstallOptions,
+    on_progress: &F,
+    verify: V,
+) -> Result<(), InstallError>
+where
+    F: Fn(UninstallProgress),
+    V: FnOnce() -> Fut,
+    Fut: Future<Output = AgentStatus>,
+{
+    on_progress(UninstallProgress::Started { agent: kind });
+
+    let Some(method) = method? else {
+        let docs_url = kind.install_info().docs_url;
+        return Err(InstallError::UnsupportedPlatform {
+            agent: kind,
+            fix: fixes::render(
+                fixes::UNINSTALL_UNSUPPORTED,
+                &[("agent", &kind.display_name()), ("docs_url", &docs_url)],
+            ),
+        });
+    };
+
+    on_progress(UninstallProgress::Uninstalling { agent: kind });
+    let cmd = &method.command;
+    InstallLocks::global()
+        .run(cmd, run_installer(kind, cmd, options, &|_| {}))
+        .await?;
+
+    if !options.skip_verification {
+        on_progress(UninstallProgress::Verifying { agent: kind });
+        if let Some(path) = verify().await.path() {
+            return Err(InstallError::UninstallFailed {
+                agent: kind,
+                path: path.to_path_buf(),
+                fix: fixes::render(
+                    fixes::UNINSTALL_FAILED,
+                    &[("agent", &kind.display_name()), ("path", &path.display())],
+                ),
+            });
+        }
+    }
+
+    on_progress(UninstallProgress::Completed { agent: kind });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::install::StructuredCommand;
+
+    use std::path::PathBuf;
+    use std::sync::Mutex;
+
+    /// An uninstall method running `program` with no arguments.
+    fn synthetic(program: &str) -> Result<Option<UninstallMethod>, InstallError> {
+        Ok(Some(UninstallMethod {
+            command: StructuredCommand {
+                program: program.to_string(),
+                args: vec![],
+                env_vars: vec![],
+            },
+            raw_command: program.to_string(),
+            description: "Synthetic uninstall".to_string(),
+        }))
+    }
+
+    fn installed(path: &str) -> AgentStatus {
+        AgentStatus::Installed(crate::test_util::installed_meta(path, None))
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_uninstall_verifies_removal() {
+        let events = Mutex::new(Vec::new());
+        let on_progress = |p: UninstallProgress| events.lock().unwrap().push(p.description());
+
+        let result = uninstall_with(
+            AgentKind::Codex,
+            synthetic("true"),
+            &InstallOptions::default(),
+            &on_progress,
+            || async { AgentStatus::NotInstalled },
+        )
+        .await;
+
+        assert!(result.is_ok(), "{:?}", result);
+        assert_eq!(
+            *events.lock().unwrap(),
+            [
+                "Starting uninstallation",
+                "Uninstalling",
+                "Verifying removal",
+                "Uninstallation complete"
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_uninstall_fails_when_still_present() {
+        let result = uninstall_with(
+            AgentKind::Gemini,
+            synthetic("true"),
+            &InstallOptions::default(),
+            &|_| {},
+            || async { installed("/usr/local/bin/gemini") },
+        )
+        .await;
+
+        match result {
+            Err(InstallError::UninstallFailed { agent, path, fix }) => {
+                assert_eq!(agent, AgentKind::Gemini);
+                assert_eq!(path, PathBuf::from("/usr/local/bin/gemini"));
+                assert!(fix.contains("/usr/local/bin/gemini"), "{}", fix);
+            }
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_uninstall_command_failure_skips_verification() {
+        let result = uninstall_with(
+            AgentKind::Codex,
+            synthetic("false"),
+            &InstallOptions::default(),
+            &|_| {},
+            || async { panic!("verification should not run") },
+        )
+        .await;
+
+        assert!(matches!(result, Err(InstallError::InstallerFailed { .. })));
+    }
+
+    #[test]
+    fn test_uninstall_route_follows_install_method() {
+        // OpenCode installed through its npm alternative
+        let path = Path::new("/usr/lib/node_modules/opencode-ai/bin/opencode");
+        let info = AgentKind::OpenCode.install_info();
+        let method = uninstall_route(AgentKind::OpenCode, Some(path), &info)
+            .unwrap()
+            .unwrap();
+        assert_eq!(method.command.program, "npm");
+        assert_eq!(method.command.args, ["uninstall", "-g", "opencode-ai"]);
+        assert_eq!(method.raw_command, "npm uninstall -g opencode-ai");
+
+        // Claude Code installed with npm, though its native install has no
+        // uninstall command
+        let path = Path::new("/usr/lib/node_modules/@anthropic-ai/claude-code/cli.js");
+        let info = AgentKind::ClaudeCode.install_info();
+        let method = uninstall_route(AgentKind::ClaudeCode, Some(path), &info)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            method.raw_command,
+            "npm uninstall -g @anthropic-ai/claude-code"
+        );
+    }
+
+    #[test]
+    fn test_uninstall_route_falls_back_for_unknown_origin() {
+        let info = AgentKind::Codex.install_info();
+        let method = uninstall_route(AgentKind::Codex, Some(Path::new("/opt/codex")), &info)
+            .unwrap()
+            .unwrap();
+        assert_eq!(method.raw_command, "npm uninstall -g @openai/codex");
+
+        let info = AgentKind::ClaudeCode.install_info();
+        assert!(uninstall_route(AgentKind::ClaudeCode, None, &info)
+            .unwrap()
+            .is_none());
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn test_uninstall_route_rejects_unused_package_manager() {
+        // Codex has no Homebrew install method
+        let path = Path::new("/opt/homebrew/bin/codex");
+        let info = AgentKind::Codex.install_info();
+        match uninstall_route(AgentKind::Codex, Some(path), &info) {
+            Err(InstallError::InstallerFailed { message, fix, .. }) => {
+                assert!(message.contains("brew"), "{}", message);
+                assert!(fix.contains("uninstall it with brew"), "{}", fix);
+            }
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_uninstall_without_method_points_at_docs() {
+        // A native Claude Code install has no uninstall command
+        let info = AgentKind::ClaudeCode.install_info();
+        let result = uninstall_with(
+            AgentKind::ClaudeCode,
+            uninstall_route(AgentKind::ClaudeCode, None, &info),
+            &InstallOptions::default(),
+            &|_| {},
+            || async { panic!("verification should not run") },
+        )
+        .await;
+
+        match result {
+            Err(InstallError::UnsupportedPlatform { agent, fix }) => {
+                assert_eq!(agent, AgentKind::ClaudeCode);
+                assert!(
+                    fix.contains(&AgentKind::ClaudeCode.install_info().docs_url),
+                    "{}",
+                    fix
+                );
+            }
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,184 @@
+//! Standalone execution of an agent's verification step.
+//!
+//! This module provides [`run_verification`], which runs a
+//! [`VerificationStep`], for example to confirm a manual installation.
+//! `install()` runs the same step after detecting the new install.
+
+use crate::detection::find_executable;
+use crate::install::VerificationStep;
+use crate::{detect, AgentKind, AgentStatus, DetectOptions, DetectionError};
+use regex::Regex;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::process::Command;
+
+/// Run a verification step and check its output.
+///
+/// The step's `command` is split on whitespace into a program and its
+/// arguments and run without a shell, with stdin closed. The program is
+/// looked up like a detected agent (on PATH, including Windows `.cmd`
+/// shims, then in known install locations). Its stdout and stderr are
+/// then matched against `expected_pattern`.
+///
+/// # Returns
+///
+/// - `Ok(true)` if the command succeeded and its output matches
+/// - `Ok(false)` if the command was not found, exited with an error, or
+///   printed something that doesn't match
+/// - `Err(DetectionError::Timeout)` if the command ran longer than `timeout`
+/// - `Err(DetectionError::PermissionDenied)` if the command cannot be run
+///   due to permissions
+/// - `Err(DetectionError::IoError)` if the command is empty or could not
+///   be started for another reason
+/// - `Err(DetectionError::VersionParseFailed)` if `expected_pattern` is not
+///   a valid regex
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use rig_acp_discovery::{run_verification, AgentKind};
+/// use std::time::Duration;
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() {
+///     let step = AgentKind::Codex.install_info().verification;
+///     match run_verification(&step, Duration::from_secs(5)).await {
+///         Ok(true) => println!("{}", step.success_message),
+///         Ok(false) => println!("Codex is not installed correctly"),
+///         Err(e) => println!("Verification failed to run: {}", e.description()),
+///     }
+/// }
+/// ```
+pub async fn run_verification(
+    step: &VerificationStep,
+    timeout: Duration,
+) -> Result<bool, DetectionError> {
+    let pattern =
+        Regex::new(&step.expected_pattern).map_err(|_| DetectionError::VersionParseFailed)?;
+
+    let mut words = step.command.split_whitespace();
+    let program = words.next().ok_or(DetectionError::IoError)?;
+    let Some(program) = find_executable(program) else {
+        return Ok(false);
+    };
+    let mut cmd = Command::new(program);
+    cmd.args(words).stdin(Stdio::null()).kill_on_drop(true);
+
+    let output = match tokio::time::timeout(timeout, cmd.output()).await {
+        Err(_) => return Err(DetectionError::Timeout),
+        Ok(Err(e)) => {
+            return match e.kind() {
+                std::io::ErrorKind::NotFound => Ok(false),
+                std::io::ErrorKind::PermissionDenied => Err(DetectionError::PermissionDenied),
+                _ => Err(DetectionError::IoError),
+            }
+        }
+        Ok(Ok(output)) => output,
+    };
+
+    if !output.status.success() {
+        return Ok(false);
+    }
+    let text = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    Ok(pattern.is_match(&text))
+}
+
+/// Detect `kind` and run its verification step, as `install()` verifies
+/// an install.
+///
+/// Returns detection's status, which carries the version. A usable status
+/// whose verification step doesn't pass is reported as `Unknown`.
+pub(crate) async fn verify_install(kind: AgentKind) -> AgentStatus {
+    let status = detect(kind).await;
+    if !status.is_usable() {
+        return status;
+    }
+
+    let step = kind.install_info().verification;
+    let timeout = DetectOptions::default().effective_timeout(kind);
+    match run_verification(&step, timeout).await {
+        Ok(true) => status,
+        Ok(false) => AgentStatus::Unknown {
+            error: DetectionError::VersionParseFailed,
+            message: format!(
+                "`{}` didn't print output matching {}",
+                step.command, step.expected_pattern
+            ),
+        },
+        Err(error) => AgentStatus::Unknown {
+            message: format!("`{}` failed: {}", step.command, error.description()),
+            error,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    fn step(command: String) -> VerificationStep {
+        VerificationStep {
+            command,
+            expected_pattern: r"\d+\.\d+\.\d+".to_string(),
+            success_message: "Installed".to_string(),
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_verification_passes_and_fails() {
+        use crate::test_util::fake_executable;
+
+        let dir = tempfile::tempdir().unwrap();
+        let good = fake_executable(dir.path(), "good", "echo \"good $1 2.1.0\"");
+        let garbled = fake_executable(dir.path(), "garbled", "echo 'no version here'");
+        let failing = fake_executable(dir.path(), "failing", "echo 2.1.0\nexit 1");
+
+        let passing = step(format!("{} --version", good.display()));
+        assert_eq!(run_verification(&passing, TIMEOUT).await, Ok(true));
+
+        for path in [&garbled, &failing] {
+            let failed = step(format!("{} --version", path.display()));
+            assert_eq!(run_verification(&failed, TIMEOUT).await, Ok(false));
+        }
+
+        let missing = step(format!(
+            "{} --version",
+            dir.path().join("missing").display()
+        ));
+        assert_eq!(run_verification(&missing, TIMEOUT).await, Ok(false));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_verification_times_out() {
+        let slow = step("sleep 10".to_string());
+        assert_eq!(
+            run_verification(&slow, Duration::from_millis(50)).await,
+            Err(DetectionError::Timeout)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_verification_rejects_bad_input() {
+        assert_eq!(
+            run_verification(&step("   ".to_string()), TIMEOUT).await,
+            Err(DetectionError::IoError)
+        );
+
+        let bad_pattern = VerificationStep {
+            expected_pattern: "(".to_string(),
+            ..step("true".to_string())
+        };
+        assert_eq!(
+            run_verification(&bad_pattern, TIMEOUT).await,
+            Err(DetectionError::VersionParseFailed)
+        );
+    }
+}
@@ -8,13 +8,60 @@
 //!
 //! ## Features
 //!
-//! - `AgentKind` enum identifying supported agents
+//! - `AgentKind` enum identifying supported agents, parseable from names like `"claude"`
+//! - `validate_executable_names()` for checking that no two agents share an executable name or alias
 //! - `AgentStatus` enum representing detection results with rich metadata
 //! - `DetectOptions` struct for configuring detection timeout
+//! - `InstallMethodKind` enum for how an installed agent was installed (npm, cargo, brew, ...)
+//! - `BinaryType` enum telling native executables apart from shell, batch and Node.js wrappers
+//! - `Capabilities` struct for what an agent reports about itself (streaming, tools, context size)
+//! - `Resolver` trait for replacing the PATH-based executable lookup
 //! - `detect()` async function for detecting a single agent
+//! - `detect_with_progress()` for reporting detection stages to a UI
+//! - `detect_with_cancel()` and `install_with_cancel()` for stopping detection or installation early
+//! - `detect_default()` async function picking the preferred installed agent
+//! - `detect_best()` async function picking the best of several installs of an agent for ACP
 //! - `detect_all()` async function for detecting all agents in parallel
+//! - `detect_installed()` async function listing only the agents usable right now
+//! - `detect_all_with_hook()` for observing each agent's result as it completes
+//! - `detect_all_paths()` for listing every installed copy of an agent, to warn about shadowed installs
+//! - `detect_where()` for detecting only the agents passing a predicate
+//! - `detect_all_timed()` for finding which agent's detection is slow
+//! - `DetectionCache` for reusing recent detection results across repeated calls
+//! - `Detector` struct for reusing the same options across detection and install calls
+//! - `revalidate()` for rechecking cached metadata without a PATH search
+//! - `detect_from_lockfile()` for checking agents against pinned paths and versions
+//! - `scan_all_locations()` for auditing every installed copy of every agent
+//! - `detect_blocking()` for detecting from synchronous code without a runtime
+//! - `presence_sync()` for a spawn-free check of which agent executables exist
 //! - `can_install()` async function for prerequisite checking
+//! - `ready_methods()` async function listing install methods whose prerequisites are met
 //! - `install()` async function for programmatic installation with progress
+//! - `install_many()` for installing several agents with bounded parallelism
+//! - `NpmSummary` in `InstallOutcome` for telling whether npm actually changed anything
+//! - `install_with_method()` for installing with a method the user picked
+//! - `update()` async function for updating an installed agent to its latest version
+//! - `uninstall()` async function for removing an agent and verifying it is gone
+//! - `run_verification()` for confirming a manual install with an agent's verification step
+//! - `had_interrupted_install()` for warning that an earlier install may not have completed
+//! - `is_interactive_session()` for telling whether installers can prompt the user
+//! - `ProgressAggregator` for one overall progress fraction across several installs
+//! - `detect_or_plan_upgrade()` for detecting an agent and, if it is too old, the command that upgrades it
+//! - `UpdateStatus` for flagging available updates that cross a major version
+//! - `environment_report()` async function summarizing installed and installable agents
+//! - `EnvironmentDiff` for the agents added, removed, upgraded or downgraded between two reports
+//! - `detect_runtimes()` async function reporting the Node.js version npm-based agents need
+//! - `DetectionReport` for serializing `detect_all()` results
+//! - `doctor()` async function combining all diagnostics into one report
+//! - `health_check()` async function confirming an agent's `--help` lists its ACP mode
+//! - `check_environment()` async function checking required executables, agents and PATH at once
+//! - `all_fix_templates()` listing every fix suggestion for documentation and localization
+//!
+//! ## Cargo Features
+//!
+//! - `network`: resolve the package version with `npm view` before an npm
+//!   install and report it as `InstallProgress::Resolved`, and enable
+//!   `check_for_update()` to compare installed agents with their latest release
 //!
 //! ## Detection Example
 //!
@@ -82,16 +129,66 @@
 
 mod agent_kind;
 mod agent_status;
+mod blocking;
+mod budget;
+mod cache;
 mod detect;
+mod detect_progress;
 mod detection;
+mod detector;
+mod doctor;
+mod environment;
+mod fixes;
+mod health;
 mod install;
+mod lockfile;
 mod options;
+mod report;
+mod resolver;
+mod runtime;
+#[cfg(test)]
+mod test_util;
+mod update;
 
-pub use agent_kind::AgentKind;
-pub use agent_status::{AgentStatus, DetectionError, InstalledMetadata};
-pub use detect::{detect, detect_all, detect_all_with_options, detect_with_options};
+pub use agent_kind::{validate_executable_names, AgentKind, ParseAgentKindError};
+pub use agent_status::{AgentStatus, DetectionError, InstallMethodKind, InstalledMetadata};
+pub use blocking::{
+    detect_blocking, detect_blocking_with_options, presence_sync, runtime_available,
+};
+pub use cache::DetectionCache;
+pub use detect::{
+    detect, detect_all, detect_all_paths, detect_all_paths_with_options, detect_all_timed,
+    detect_all_with_hook, detect_all_with_options, detect_best, detect_default,
+    detect_default_with_order, detect_installed, detect_installed_with_options, detect_where,
+    detect_with_cancel, detect_with_options, detect_with_progress, detection_cost, revalidate,
+    scan_all_locations, DetectionCost,
+};
+pub use detect_progress::DetectProgress;
+pub use detection::{BinaryType, Capabilities};
+pub use detector::Detector;
+pub use doctor::{doctor, AgentDiagnosis, DoctorReport};
+pub use environment::{
+    check_environment, EnvCheckItem, EnvCheckResult, EnvItem, EnvRequirements,
+    ExecutableRequirement,
+};
+pub use fixes::all_fix_templates;
+pub use health::{health_check, HealthReport};
 pub use install::{
-    can_install, install, InstallError, InstallInfo, InstallLocation, InstallMethod,
-    InstallOptions, InstallProgress, Prerequisite, StructuredCommand, VerificationStep,
+    can_install, can_install_all, can_install_method, had_interrupted_install, install,
+    install_environment, install_many, install_with_cancel, install_with_method,
+    is_interactive_session, ready_methods, run_verification, uninstall, update, InstallError,
+    InstallInfo, InstallLocation, InstallMethod, InstallOptions, InstallOutcome, InstallProgress,
+    MethodDescriptor, NpmSummary, Prerequisite, ProgressAggregator, Registry, StructuredCommand,
+    UninstallMethod, UninstallProgress, VerificationStep,
 };
+pub use lockfile::{detect_from_lockfile, LockedAgent};
 pub use options::DetectOptions;
+pub use report::{
+    environment_report, AgentReport, DetectionReport, EnvironmentDiff, EnvironmentReport,
+    VersionChange,
+};
+pub use resolver::{PathResolver, Resolver};
+pub use runtime::{detect_runtimes, Runtime, RuntimeRequirement, RuntimeStatus};
+#[cfg(feature = "network")]
+pub use update::check_for_update;
+pub use update::{detect_or_plan_upgrade, DetectOrPlan, UpdateStatus, UpgradePlan};
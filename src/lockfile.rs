@@ -0,0 +1,169 @@
+//! Detection pinned to a lockfile of exact agent paths and versions.
+//!
+//! A lockfile records, for each agent, the absolute path of the executable
+//! and the version it is expected to report. [`detect_from_lockfile`]
+//! checks the environment against it, so drift from a pinned toolchain
+//! shows up as [`AgentStatus::VersionMismatch`].
+
+use crate::detect::inspect_executable_with_progress;
+use crate::{AgentKind, AgentStatus, DetectOptions};
+use futures::future::join_all;
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// A single agent entry in a lockfile.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockedAgent {
+    /// Absolute path to the agent executable.
+    pub path: PathBuf,
+
+    /// The exact version the executable must report.
+    pub version: Version,
+}
+
+/// Detect agents at the exact paths and versions recorded in a lockfile.
+///
+/// The lockfile is a JSON object mapping agent kinds to [`LockedAgent`]
+/// entries:
+///
+/// ```json
+/// {
+///   "Codex": { "path": "/opt/tools/bin/codex", "version": "0.87.0" },
+///   "ClaudeCode": { "path": "/opt/tools/bin/claude", "version": "2.1.3" }
+/// }
+/// ```
+///
+/// Each locked path is inspected directly, without a PATH search. A path
+/// that doesn't exist yields `NotInstalled`, and a version other than the
+/// locked one yields `VersionMismatch` with the locked version as
+/// `required`. Agents missing from the lockfile are left out of the result.
+///
+/// Returns an error if the lockfile can't be read or parsed.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use rig_acp_discovery::detect_from_lockfile;
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() -> std::io::Result<()> {
+///     for (kind, status) in detect_from_lockfile("agents.lock.json").await? {
+///         if !status.is_usable() {
+///             eprintln!("{} drifted from the lockfile: {:?}", kind.display_name(), status);
+///         }
+///     }
+///     Ok(())
+/// }
+/// ```
+pub async fn detect_from_lockfile(
+    path: impl AsRef<Path>,
+) -> io::Result<HashMap<AgentKind, AgentStatus>> {
+    let contents = std::fs::read_to_string(path)?;
+    let locked: HashMap<AgentKind, LockedAgent> = serde_json::from_str(&contents)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+    Ok(detect_locked(locked, &DetectOptions::default()).await)
+}
+
+/// Check each locked agent's path and version.
+async fn detect_locked(
+    locked: HashMap<AgentKind, LockedAgent>,
+    options: &DetectOptions,
+) -> HashMap<AgentKind, AgentStatus> {
+    let futures = locked.into_iter().map(|(kind, lock)| async move {
+        if !lock.path.is_file() {
+            return (kind, AgentStatus::NotInstalled);
+        }
+        let status = inspect_executable_with_progress(kind, lock.path, options, &|_| {}).await;
+        (kind, check_locked_version(status, lock.version))
+    });
+
+    join_all(futures).await.into_iter().collect()
+}
+
+/// Turn an `Installed` status into `VersionMismatch` if it drifted from `locked`.
+fn check_locked_version(status: AgentStatus, locked: Version) -> AgentStatus {
+    match status {
+        AgentStatus::Installed(meta) => match meta.version {
+            Some(found) if found != locked => AgentStatus::VersionMismatch {
+                found,
+                required: locked,
+                path: meta.path,
+            },
+            _ => AgentStatus::Installed(meta),
+        },
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_detect_from_lockfile_reports_drift() {
+        let dir = tempfile::tempdir().unwrap();
+        let claude =
+            crate::test_util::fake_executable(dir.path(), "claude", "echo '2.1.3 (Claude Code)'");
+        let codex =
+            crate::test_util::fake_executable(dir.path(), "codex", "echo 'codex-cli 0.88.0'");
+
+        let lockfile = dir.path().join("agents.lock.json");
+        let locked = HashMap::from([
+            (
+                AgentKind::ClaudeCode,
+                LockedAgent {
+                    path: claude,
+                    version: Version::new(2, 1, 3),
+                },
+            ),
+            (
+                AgentKind::Codex,
+                LockedAgent {
+                    path: codex.clone(),
+                    version: Version::new(0, 87, 0),
+                },
+            ),
+            (
+                AgentKind::Gemini,
+                LockedAgent {
+                    path: dir.path().join("gemini"),
+                    version: Version::new(0, 20, 0),
+                },
+            ),
+        ]);
+        std::fs::write(&lockfile, serde_json::to_string(&locked).unwrap()).unwrap();
+
+        let results = detect_from_lockfile(&lockfile).await.unwrap();
+        assert_eq!(results.len(), 3);
+        assert!(results[&AgentKind::ClaudeCode].is_usable());
+        assert!(matches!(
+            &results[&AgentKind::Codex],
+            AgentStatus::VersionMismatch { found, required, path }
+                if *found == Version::new(0, 88, 0)
+                    && *required == Version::new(0, 87, 0)
+                    && *path == codex
+        ));
+        assert!(matches!(
+            results[&AgentKind::Gemini],
+            AgentStatus::NotInstalled
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_detect_from_lockfile_invalid() {
+        let dir = tempfile::tempdir().unwrap();
+        let lockfile = dir.path().join("agents.lock.json");
+        std::fs::write(&lockfile, "not json").unwrap();
+
+        let error = detect_from_lockfile(&lockfile).await.unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(detect_from_lockfile(dir.path().join("missing.json"))
+            .await
+            .is_err());
+    }
+}
@@ -3,6 +3,11 @@
 //! This module provides the [`DetectOptions`] struct for configuring
 //! agent detection behavior, including timeouts and version parsing options.
 
+use crate::{AgentKind, PathResolver, Resolver};
+use semver::Version;
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::sync::Arc;
 use std::time::Duration;
 
 /// Configuration options for agent detection.
@@ -51,6 +56,15 @@ pub struct DetectOptions {
     /// Default: 5 seconds
     pub timeout: Duration,
 
+    /// Per-agent overrides of `timeout`.
+    ///
+    /// Agents listed here use their own version check timeout, for example
+    /// a longer one for an agent known to start slowly. Other agents use
+    /// `timeout`. See [`effective_timeout`](Self::effective_timeout).
+    ///
+    /// Default: empty (every agent uses `timeout`)
+    pub agent_timeouts: HashMap<AgentKind, Duration>,
+
     /// Skip version parsing for fast-path detection.
     ///
     /// When set to `true`, detection will skip running `--version` and
@@ -62,13 +76,236 @@ pub struct DetectOptions {
     ///
     /// Default: `false` (version parsing enabled)
     pub skip_version: bool,
+
+    /// Probe the agent's configured default model.
+    ///
+    /// When set to `true`, detection reads the agent's configuration file
+    /// (e.g., `~/.claude/settings.json`, `~/.codex/config.toml`) and fills
+    /// `InstalledMetadata::default_model` with the configured model.
+    ///
+    /// Default: `false`
+    pub probe_default_model: bool,
+
+    /// Probe the schema version of the agent's configuration file.
+    ///
+    /// When set to `true`, detection reads the agent's configuration file
+    /// and fills `InstalledMetadata::config_schema_version` from its
+    /// top-level `version` key (or, for JSON configs without one, its
+    /// `$schema` URL).
+    ///
+    /// Default: `false`
+    pub probe_config_schema: bool,
+
+    /// Probe the agent's edition (e.g., "Pro" or "Enterprise").
+    ///
+    /// When set to `true`, detection looks for an edition marker in the
+    /// `--version` output, then for an `edition` setting in the agent's
+    /// config file, and fills `InstalledMetadata::edition`.
+    ///
+    /// Default: `false`
+    pub probe_edition: bool,
+
+    /// Probe the architecture of the agent executable.
+    ///
+    /// When set to `true`, detection parses the architecture from the
+    /// executable header (ELF, Mach-O or PE) and fills
+    /// `InstalledMetadata::binary_arch`. This surfaces mismatches such as
+    /// an x86_64 binary running under Rosetta on Apple Silicon.
+    /// `InstalledMetadata::binary_type` is filled regardless of this option.
+    ///
+    /// Default: `false`
+    pub probe_arch: bool,
+
+    /// Accept a bare major version (e.g., `3`) from `--version` output.
+    ///
+    /// Some minimal tools print only a major version number. When set to
+    /// `true` and no `X.Y.Z` or `X.Y` version is found, a single number is
+    /// parsed as `X.0.0`. Off by default since a bare number is ambiguous.
+    ///
+    /// Default: `false`
+    pub allow_major_only: bool,
+
+    /// Probe the ACP capabilities the agent advertises.
+    ///
+    /// When set to `true`, detection starts the agent in ACP mode, sends
+    /// an `initialize` request, and fills `InstalledMetadata::acp_capabilities`
+    /// from the response. This spawns an extra process per agent and is
+    /// bounded by `timeout`.
+    ///
+    /// Default: `false`
+    pub probe_acp_capabilities: bool,
+
+    /// Probe the capabilities the agent reports about itself.
+    ///
+    /// When set to `true`, detection runs `<agent> capabilities --json` and
+    /// fills `InstalledMetadata::capabilities` from its output. This spawns
+    /// an extra process per agent and is bounded by `timeout`.
+    ///
+    /// Default: `false`
+    pub probe_capabilities: bool,
+
+    /// Run the version check with a minimal environment.
+    ///
+    /// When set to `true`, the `--version` process is started with a
+    /// cleared environment that keeps only `PATH` and a few essentials
+    /// (home, temp and locale variables). Agents that load configuration
+    /// from environment variables at startup then start faster and
+    /// without side effects.
+    ///
+    /// Default: `false`
+    pub minimal_env: bool,
+
+    /// Number of times to retry a version check that timed out or failed
+    /// with an I/O error.
+    ///
+    /// Cold starts (e.g., the first Node.js launch after boot) can make a
+    /// version check fail once and then succeed. If every attempt fails,
+    /// detection reports `DetectionError::Retried` with the attempt count
+    /// and the last error, and each failed attempt is logged. An agent
+    /// that times out on every attempt is still reported as not installed,
+    /// as it is without retries.
+    ///
+    /// Default: `0` (no retries)
+    pub retries: u8,
+
+    /// Read the version from a file next to the executable when possible.
+    ///
+    /// When set to `true`, detection first looks for a `VERSION` file or a
+    /// `package.json` with a `"version"` field beside the executable (or,
+    /// for npm's symlinked shims, in the package it points into). If one
+    /// is found, its version is used and `--version` is not run; otherwise
+    /// detection falls back to running `--version`.
+    ///
+    /// Default: `false`
+    pub prefer_version_file: bool,
+
+    /// Total time budget shared by all agents in `detect_all`.
+    ///
+    /// With per-agent timeouts, detecting every agent can take up to
+    /// `timeout` for each slow agent. When set, the functions that detect
+    /// several agents at once ([`detect_all_with_options`],
+    /// [`detect_where`], [`detect_all_with_hook`], [`detect_all_timed`] and
+    /// the functions built on them) instead share one budget: the time left
+    /// is split equally between the agents still running, and agents that
+    /// finish early hand their unused share to the rest. The per-agent
+    /// `timeout` no longer applies to the version check. Agents that run
+    /// out of budget report `DetectionError::Timeout`.
+    ///
+    /// Single-agent detection ignores this setting.
+    ///
+    /// Default: `None` (per-agent `timeout` only)
+    ///
+    /// [`detect_all_with_options`]: crate::detect_all_with_options
+    /// [`detect_where`]: crate::detect_where
+    /// [`detect_all_with_hook`]: crate::detect_all_with_hook
+    /// [`detect_all_timed`]: crate::detect_all_timed
+    pub shared_budget: Option<Duration>,
+
+    /// Minimum acceptable agent version.
+    ///
+    /// An agent whose parsed version is lower is reported as
+    /// [`AgentStatus::VersionMismatch`](crate::AgentStatus::VersionMismatch)
+    /// instead of `Installed`. When `None`, the agent's own floor from
+    /// [`AgentKind::min_supported_version`](crate::AgentKind::min_supported_version)
+    /// applies; set `Some(Version::new(0, 0, 0))` to accept any version.
+    /// If the version can't be determined (it failed to parse, or
+    /// `skip_version` is set), the agent stays `Installed` and a warning
+    /// is logged.
+    ///
+    /// Default: `None` (the agent's minimum supported version)
+    pub min_version: Option<Version>,
+
+    /// Report version mismatches as errors from `detect_all` and its variants.
+    ///
+    /// By default an agent below the minimum version is an `Ok` result
+    /// holding [`AgentStatus::VersionMismatch`](crate::AgentStatus::VersionMismatch).
+    /// When set to `true`, it is reported as
+    /// `Err(DetectionError::VersionUnsupported)` instead, for callers that
+    /// only want usable agents in the `Ok` results. Single-agent detection
+    /// functions are unaffected.
+    ///
+    /// Default: `false`
+    pub mismatch_is_error: bool,
+
+    /// PATH used to locate agent executables, instead of the process PATH.
+    ///
+    /// A PATH-style list of directories passed to
+    /// [`Resolver::resolve_in`](crate::Resolver::resolve_in). When `None`,
+    /// each lookup reads the process PATH, so a PATH changed while agents
+    /// are being detected (for example by a parallel test) can give
+    /// inconsistent results. The functions that detect several agents at
+    /// once ([`detect_all_with_options`], [`detect_where`],
+    /// [`detect_all_with_hook`], [`detect_all_timed`] and the functions
+    /// built on them) therefore snapshot the process PATH once into this
+    /// field and detect every agent against that snapshot.
+    ///
+    /// Default: `None` (read the process PATH)
+    ///
+    /// [`detect_all_with_options`]: crate::detect_all_with_options
+    /// [`detect_where`]: crate::detect_where
+    /// [`detect_all_with_hook`]: crate::detect_all_with_hook
+    /// [`detect_all_timed`]: crate::detect_all_timed
+    pub path_env: Option<OsString>,
+
+    /// Resolver used to locate agent executables.
+    ///
+    /// The agent's override variables (e.g., `RIG_ACP_CLAUDE_BIN` and
+    /// `CLAUDE_PATH`) are still checked first; otherwise the executable
+    /// name is passed to this resolver. Replace it to detect from a custom
+    /// tool directory or to run detection without touching the real PATH.
+    ///
+    /// Default: [`PathResolver`] (PATH, then common install locations)
+    pub resolver: Arc<dyn Resolver>,
+}
+
+impl DetectOptions {
+    /// The version check timeout that applies to `kind`.
+    ///
+    /// This is the agent's entry in `agent_timeouts` if it has one, and
+    /// `timeout` otherwise. When `shared_budget` is set, `detect_all` and
+    /// its variants bound every agent by the budget instead.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rig_acp_discovery::{AgentKind, DetectOptions};
+    /// use std::time::Duration;
+    ///
+    /// let mut options = DetectOptions::default();
+    /// options.agent_timeouts.insert(AgentKind::Gemini, Duration::from_secs(15));
+    ///
+    /// assert_eq!(options.effective_timeout(AgentKind::Gemini), Duration::from_secs(15));
+    /// assert_eq!(options.effective_timeout(AgentKind::Codex), Duration::from_secs(5));
+    /// ```
+    pub fn effective_timeout(&self, kind: AgentKind) -> Duration {
+        self.agent_timeouts
+            .get(&kind)
+            .copied()
+            .unwrap_or(self.timeout)
+    }
 }
 
 impl Default for DetectOptions {
     fn default() -> Self {
         Self {
             timeout: Duration::from_secs(5),
+            agent_timeouts: HashMap::new(),
             skip_version: false,
+            probe_default_model: false,
+            probe_config_schema: false,
+            probe_edition: false,
+            probe_arch: false,
+            allow_major_only: false,
+            probe_acp_capabilities: false,
+            probe_capabilities: false,
+            minimal_env: false,
+            retries: 0,
+            prefer_version_file: false,
+            shared_budget: None,
+            min_version: None,
+            mismatch_is_error: false,
+            path_env: None,
+            resolver: Arc::new(PathResolver),
         }
     }
 }
@@ -83,12 +320,52 @@ mod tests {
         assert_eq!(opts.timeout, Duration::from_secs(5));
     }
 
+    #[test]
+    fn test_effective_timeout() {
+        let opts = DetectOptions {
+            timeout: Duration::from_secs(3),
+            agent_timeouts: HashMap::from([(AgentKind::ClaudeCode, Duration::from_secs(20))]),
+            ..Default::default()
+        };
+        // The override wins when present
+        assert_eq!(
+            opts.effective_timeout(AgentKind::ClaudeCode),
+            Duration::from_secs(20)
+        );
+        // Otherwise the global timeout applies
+        assert_eq!(
+            opts.effective_timeout(AgentKind::OpenCode),
+            Duration::from_secs(3)
+        );
+    }
+
     #[test]
     fn test_default_skip_version() {
         let opts = DetectOptions::default();
         assert!(!opts.skip_version);
     }
 
+    #[test]
+    fn test_default_options() {
+        let opts = DetectOptions::default();
+        assert!(opts.agent_timeouts.is_empty());
+        assert!(!opts.probe_default_model);
+        assert!(!opts.probe_config_schema);
+        assert!(!opts.probe_edition);
+        assert!(!opts.probe_arch);
+        assert!(!opts.allow_major_only);
+        assert!(!opts.probe_acp_capabilities);
+        assert!(!opts.probe_capabilities);
+        assert!(!opts.minimal_env);
+        assert_eq!(opts.retries, 0);
+        assert!(!opts.prefer_version_file);
+        assert!(opts.shared_budget.is_none());
+        assert!(opts.min_version.is_none());
+        assert!(!opts.mismatch_is_error);
+        assert!(opts.path_env.is_none());
+        assert_eq!(format!("{:?}", opts.resolver), "PathResolver");
+    }
+
     #[test]
     fn test_custom_timeout() {
         let opts = DetectOptions {
@@ -114,6 +391,7 @@ mod tests {
         let opts = DetectOptions {
             timeout: Duration::from_secs(10),
             skip_version: true,
+            ..Default::default()
         };
         let cloned = opts.clone();
         assert_eq!(opts.timeout, cloned.timeout);
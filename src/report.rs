@@ -0,0 +1,460 @@
+//! Environment reports combining detection and installability.
+//!
+//! This module provides [`environment_report`], which answers "what do I
+//! have and what could I add" in a single call by combining agent detection
+//! with installation pre-flight checks, and [`DetectionReport`], a
+//! serializable form of [`detect_all`] results.
+
+use crate::install::can_install_all;
+use crate::runtime::detect_runtimes;
+use crate::{
+    detect_all, AgentKind, AgentStatus, DetectionError, InstallError, InstalledMetadata,
+    RuntimeStatus,
+};
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Summary of the agents available in the current environment.
+///
+/// Every known agent appears in exactly one of the three lists:
+/// - `installed`: detected and usable right now
+/// - `installable`: not usable, but prerequisites for installing it are met
+/// - `blocked`: not usable, and installation pre-flight checks failed
+///
+/// Agents appear in `AgentKind::all()` order within each list.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use rig_acp_discovery::environment_report;
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() {
+///     let report = environment_report().await;
+///     for (kind, meta) in &report.installed {
+///         println!("Installed: {} at {:?}", kind.display_name(), meta.path);
+///     }
+///     for kind in &report.installable {
+///         println!("Can install: {}", kind.display_name());
+///     }
+///     for (kind, error) in &report.blocked {
+///         println!("Blocked: {} ({})", kind.display_name(), error.fix_suggestion());
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct EnvironmentReport {
+    /// Agents that are installed and usable, with their metadata.
+    pub installed: Vec<(AgentKind, InstalledMetadata)>,
+
+    /// Agents that are not usable but can be installed.
+    pub installable: Vec<AgentKind>,
+
+    /// Agents that are not usable and cannot be installed, with the reason.
+    pub blocked: Vec<(AgentKind, InstallError)>,
+
+    /// Agents found on the system but older than the oldest supported
+    /// version, with the version found. These agents also appear in
+    /// `installable` or `blocked`, since installing upgrades them.
+    pub outdated: Vec<(AgentKind, Version)>,
+
+    /// Runtime requirements of agents that need one, with the runtime
+    /// version found on the system. Empty when built with
+    /// [`from_results`](Self::from_results).
+    pub runtimes: Vec<(AgentKind, RuntimeStatus)>,
+}
+
+impl EnvironmentReport {
+    /// Build a report from detection and pre-flight check results.
+    ///
+    /// `detections` is typically the output of [`detect_all`], and
+    /// `install_checks` the output of [`can_install_all`]. Agents
+    /// that are usable are reported as installed regardless of their
+    /// install check. Agents missing from `install_checks` are treated
+    /// as installable.
+    pub fn from_results(
+        mut detections: HashMap<AgentKind, Result<AgentStatus, DetectionError>>,
+        mut install_checks: HashMap<AgentKind, Result<(), InstallError>>,
+    ) -> Self {
+        let mut report = Self {
+            installed: Vec::new(),
+            installable: Vec::new(),
+            blocked: Vec::new(),
+            outdated: Vec::new(),
+            runtimes: Vec::new(),
+        };
+
+        for kind in AgentKind::all() {
+            match detections.remove(&kind) {
+                Some(Ok(AgentStatus::Installed(meta))) => {
+                    report.installed.push((kind, meta));
+                    continue;
+                }
+                Some(Ok(AgentStatus::VersionMismatch { found, .. })) => {
+                    report.outdated.push((kind, found));
+                }
+                _ => {}
+            }
+
+            match install_checks.remove(&kind) {
+                Some(Err(error)) => report.blocked.push((kind, error)),
+                Some(Ok(())) | None => report.installable.push(kind),
+            }
+        }
+
+        report
+    }
+
+    /// Compare this report with an earlier one.
+    ///
+    /// An agent is present in a report when it is in `installed` or
+    /// `outdated`. It is newly installed or removed when it becomes present
+    /// or stops being present, and upgraded or downgraded when its parsed
+    /// version changed, including an agent that became outdated. Agents
+    /// whose version is unknown in either report are not reported as
+    /// version changes.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rig_acp_discovery::environment_report;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn main() {
+    ///     let before = environment_report().await;
+    ///     // ... later ...
+    ///     let after = environment_report().await;
+    ///     for change in &after.diff(&before).upgraded {
+    ///         println!("{} was upgraded to {}", change.kind.display_name(), change.to);
+    ///     }
+    /// }
+    /// ```
+    pub fn diff(&self, previous: &Self) -> EnvironmentDiff {
+        let find = |report: &Self, kind: AgentKind| {
+            let installed = report
+                .installed
+                .iter()
+                .find(|(installed, _)| *installed == kind)
+                .map(|(_, meta)| meta.version.clone());
+            installed.or_else(|| {
+                report
+                    .outdated
+                    .iter()
+                    .find(|(outdated, _)| *outdated == kind)
+                    .map(|(_, found)| Some(found.clone()))
+            })
+        };
+
+        let mut diff = EnvironmentDiff::default();
+        for kind in AgentKind::all() {
+            match (find(previous, kind), find(self, kind)) {
+                (None, Some(_)) => diff.newly_installed.push(kind),
+                (Some(_), None) => diff.removed.push(kind),
+                (Some(Some(from)), Some(Some(to))) if from != to => {
+                    let upgraded = to > from;
+                    let change = VersionChange { kind, from, to };
+                    if upgraded {
+                        diff.upgraded.push(change);
+                    } else {
+                        diff.downgraded.push(change);
+                    }
+                }
+                _ => {}
+            }
+        }
+        diff
+    }
+}
+
+/// Changes between two [`EnvironmentReport`]s, from [`EnvironmentReport::diff`].
+///
+/// Agents appear in `AgentKind::all()` order within each list.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EnvironmentDiff {
+    /// Agents installed now that weren't before.
+    pub newly_installed: Vec<AgentKind>,
+
+    /// Agents installed before that aren't anymore.
+    pub removed: Vec<AgentKind>,
+
+    /// Agents whose version went up.
+    pub upgraded: Vec<VersionChange>,
+
+    /// Agents whose version went down.
+    pub downgraded: Vec<VersionChange>,
+}
+
+impl EnvironmentDiff {
+    /// Check whether nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.newly_installed.is_empty()
+            && self.removed.is_empty()
+            && self.upgraded.is_empty()
+            && self.downgraded.is_empty()
+    }
+}
+
+/// A change in an installed agent's version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionChange {
+    /// The agent whose version changed.
+    pub kind: AgentKind,
+
+    /// The version in the earlier report.
+    pub from: Version,
+
+    /// The version in the later report.
+    pub to: Version,
+}
+
+/// Detection result for a single agent in a [`DetectionReport`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentReport {
+    /// The agent that was detected.
+    pub kind: AgentKind,
+
+    /// The detection status, or `None` if detection failed.
+    pub status: Option<AgentStatus>,
+
+    /// Why detection failed, or `None` if it succeeded.
+    pub error: Option<DetectionError>,
+}
+
+/// Serializable summary of [`detect_all`] results.
+///
+/// The raw `HashMap<AgentKind, Result<AgentStatus, DetectionError>>` maps
+/// awkwardly to formats like JSON, so this flattens each `Result` into an
+/// optional status and an optional error. Agents are listed in
+/// `AgentKind::all()` order.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use rig_acp_discovery::{detect_all, DetectionReport};
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() {
+///     let report = DetectionReport::from_results(detect_all().await);
+///     for agent in &report.agents {
+///         println!("{}: {:?}", agent.kind.display_name(), agent.status);
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DetectionReport {
+    /// One entry per detected agent.
+    pub agents: Vec<AgentReport>,
+}
+
+impl DetectionReport {
+    /// Build a report from [`detect_all`] output.
+    ///
+    /// Agents missing from `results` are left out of the report.
+    pub fn from_results(
+        mut results: HashMap<AgentKind, Result<AgentStatus, DetectionError>>,
+    ) -> Self {
+        let agents = AgentKind::all()
+            .filter_map(|kind| {
+                let (status, error) = match results.remove(&kind)? {
+                    Ok(status) => (Some(status), None),
+                    Err(error) => (None, Some(error)),
+                };
+                Some(AgentReport {
+                    kind,
+                    status,
+                    error,
+                })
+            })
+            .collect();
+
+        Self { agents }
+    }
+}
+
+/// Detect all agents and check which missing ones can be installed.
+///
+/// Runs [`detect_all`] and [`can_install_all`] concurrently and partitions
+/// the agents into installed, installable, and blocked. This is the single
+/// call behind an onboarding screen. The detected runtime versions from
+/// [`detect_runtimes`] are included as well.
+pub async fn environment_report() -> EnvironmentReport {
+    let (detections, install_checks, runtimes) =
+        futures::join!(detect_all(), can_install_all(), detect_runtimes());
+    let mut report = EnvironmentReport::from_results(detections, install_checks);
+    report.runtimes = runtimes;
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn installed(path: &str) -> AgentStatus {
+        installed_version(path, None)
+    }
+
+    fn installed_version(path: &str, version: Option<Version>) -> AgentStatus {
+        AgentStatus::Installed(crate::test_util::installed_meta(path, version))
+    }
+
+    fn node_missing() -> InstallError {
+        InstallError::PrerequisiteMissing {
+            name: "Node.js 18+".to_string(),
+            install_url: Some("https://nodejs.org".to_string()),
+            fix: "Install Node.js 18+".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_from_results_partitions_agents() {
+        let detections = HashMap::from([
+            (AgentKind::ClaudeCode, Ok(installed("/usr/bin/claude"))),
+            (AgentKind::Codex, Ok(AgentStatus::NotInstalled)),
+            (AgentKind::OpenCode, Ok(AgentStatus::NotInstalled)),
+            (AgentKind::Gemini, Err(DetectionError::IoError)),
+        ]);
+        let install_checks = HashMap::from([
+            (AgentKind::ClaudeCode, Ok(())),
+            (AgentKind::Codex, Err(node_missing())),
+            (AgentKind::OpenCode, Ok(())),
+            (AgentKind::Gemini, Err(node_missing())),
+        ]);
+
+        let report = EnvironmentReport::from_results(detections, install_checks);
+
+        assert_eq!(report.installed.len(), 1);
+        assert_eq!(report.installed[0].0, AgentKind::ClaudeCode);
+        assert_eq!(report.installable, vec![AgentKind::OpenCode]);
+        let blocked: Vec<_> = report.blocked.iter().map(|(kind, _)| *kind).collect();
+        assert_eq!(blocked, vec![AgentKind::Codex, AgentKind::Gemini]);
+    }
+
+    #[test]
+    fn test_diff_reports_new_agents_and_version_changes() {
+        let report = |detections: Vec<(AgentKind, AgentStatus)>| {
+            let detections = detections
+                .into_iter()
+                .map(|(kind, status)| (kind, Ok(status)))
+                .collect();
+            EnvironmentReport::from_results(detections, HashMap::new())
+        };
+        let previous = report(vec![
+            (
+                AgentKind::ClaudeCode,
+                installed_version("/usr/bin/claude", Some(Version::new(2, 1, 0))),
+            ),
+            (
+                AgentKind::Codex,
+                installed_version("/usr/bin/codex", Some(Version::new(0, 87, 0))),
+            ),
+        ]);
+        let current = report(vec![
+            (
+                AgentKind::ClaudeCode,
+                installed_version("/usr/bin/claude", Some(Version::new(2, 1, 0))),
+            ),
+            (
+                AgentKind::Codex,
+                installed_version("/usr/bin/codex", Some(Version::new(0, 90, 0))),
+            ),
+            (AgentKind::Gemini, installed("/usr/bin/gemini")),
+        ]);
+
+        let diff = current.diff(&previous);
+        assert_eq!(diff.newly_installed, [AgentKind::Gemini]);
+        assert!(diff.removed.is_empty());
+        assert_eq!(
+            diff.upgraded,
+            [VersionChange {
+                kind: AgentKind::Codex,
+                from: Version::new(0, 87, 0),
+                to: Version::new(0, 90, 0),
+            }]
+        );
+        assert!(diff.downgraded.is_empty());
+
+        // Going back reverses every change
+        let diff = previous.diff(&current);
+        assert_eq!(diff.removed, [AgentKind::Gemini]);
+        assert_eq!(diff.downgraded.len(), 1);
+        assert!(current.diff(&current).is_empty());
+    }
+
+    #[test]
+    fn test_diff_outdated_agent_is_downgraded_not_removed() {
+        let report = |status: AgentStatus| {
+            EnvironmentReport::from_results(
+                HashMap::from([(AgentKind::Codex, Ok(status))]),
+                HashMap::new(),
+            )
+        };
+        let previous = report(installed_version(
+            "/usr/bin/codex",
+            Some(Version::new(0, 87, 0)),
+        ));
+        let current = report(AgentStatus::VersionMismatch {
+            found: Version::new(0, 40, 0),
+            required: Version::new(0, 50, 0),
+            path: PathBuf::from("/usr/bin/codex"),
+        });
+        assert_eq!(
+            current.outdated,
+            [(AgentKind::Codex, Version::new(0, 40, 0))]
+        );
+        assert!(current.installable.contains(&AgentKind::Codex));
+
+        let diff = current.diff(&previous);
+        assert!(diff.removed.is_empty());
+        assert_eq!(
+            diff.downgraded,
+            [VersionChange {
+                kind: AgentKind::Codex,
+                from: Version::new(0, 87, 0),
+                to: Version::new(0, 40, 0),
+            }]
+        );
+        let diff = previous.diff(&current);
+        assert!(diff.newly_installed.is_empty());
+        assert_eq!(diff.upgraded.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_environment_report_covers_all_agents() {
+        let report = environment_report().await;
+        let total = report.installed.len() + report.installable.len() + report.blocked.len();
+        assert_eq!(total, AgentKind::all().count());
+    }
+
+    #[test]
+    fn test_detection_report_round_trip() {
+        let results = HashMap::from([
+            (AgentKind::ClaudeCode, Ok(installed("/usr/bin/claude"))),
+            (AgentKind::Codex, Ok(AgentStatus::NotInstalled)),
+            (AgentKind::Gemini, Err(DetectionError::Timeout)),
+        ]);
+
+        let report = DetectionReport::from_results(results);
+        let kinds: Vec<_> = report.agents.iter().map(|a| a.kind).collect();
+        assert_eq!(
+            kinds,
+            [AgentKind::ClaudeCode, AgentKind::Codex, AgentKind::Gemini]
+        );
+        assert!(report.agents[2].status.is_none());
+        assert_eq!(report.agents[2].error, Some(DetectionError::Timeout));
+
+        let json = serde_json::to_string(&report).expect("Should serialize");
+        let parsed: DetectionReport = serde_json::from_str(&json).expect("Should deserialize");
+        assert_eq!(parsed.agents.len(), 3);
+        assert_eq!(
+            parsed.agents[0].status.as_ref().and_then(|s| s.path()),
+            Some(std::path::Path::new("/usr/bin/claude"))
+        );
+        assert!(matches!(
+            parsed.agents[1].status,
+            Some(AgentStatus::NotInstalled)
+        ));
+        assert_eq!(serde_json::to_string(&parsed).unwrap(), json);
+    }
+}
@@ -0,0 +1,126 @@
+//! Pluggable executable lookup.
+//!
+//! Detection locates agent executables through a [`Resolver`]. The default
+//! [`PathResolver`] searches PATH and the usual install locations; tests
+//! and unusual environments can supply their own through
+//! [`DetectOptions::resolver`](crate::DetectOptions::resolver).
+
+use crate::detection::{find_executable, find_executable_in};
+use std::ffi::OsStr;
+use std::fmt::Debug;
+use std::path::PathBuf;
+
+/// Resolves an executable name to a path, like `which`.
+///
+/// # Example
+///
+/// ```rust
+/// use rig_acp_discovery::{DetectOptions, Resolver};
+/// use std::path::PathBuf;
+/// use std::sync::Arc;
+///
+/// /// Finds every executable in a single tools directory.
+/// #[derive(Debug)]
+/// struct ToolsDir(PathBuf);
+///
+/// impl Resolver for ToolsDir {
+///     fn resolve(&self, name: &str) -> Option<PathBuf> {
+///         let path = self.0.join(name);
+///         path.is_file().then_some(path)
+///     }
+/// }
+///
+/// let options = DetectOptions {
+///     resolver: Arc::new(ToolsDir(PathBuf::from("/opt/tools/bin"))),
+///     ..Default::default()
+/// };
+/// ```
+pub trait Resolver: Debug + Send + Sync {
+    /// Find the executable named `name`, or `None` if it isn't available.
+    fn resolve(&self, name: &str) -> Option<PathBuf>;
+
+    /// Find the executable named `name`, searching `path_env` (a PATH-style
+    /// list of directories) instead of the process PATH.
+    ///
+    /// Called instead of [`resolve`](Self::resolve) when
+    /// [`DetectOptions::path_env`](crate::DetectOptions::path_env) is set.
+    /// The default implementation ignores `path_env` and calls `resolve`.
+    fn resolve_in(&self, name: &str, path_env: &OsStr) -> Option<PathBuf> {
+        let _ = path_env;
+        self.resolve(name)
+    }
+}
+
+/// The default resolver: PATH, then common install locations.
+///
+/// Searches PATH, or the snapshot in `DetectOptions::path_env` if set
+/// (honoring `PATHEXT` on Windows), then system locations
+/// such as `/usr/local/bin`, then user-level locations such as
+/// `~/.local/bin` and `~/.npm-global/bin`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PathResolver;
+
+impl Resolver for PathResolver {
+    fn resolve(&self, name: &str) -> Option<PathBuf> {
+        find_executable(name)
+    }
+
+    fn resolve_in(&self, name: &str, path_env: &OsStr) -> Option<PathBuf> {
+        find_executable_in(name, Some(path_env))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{detect_with_options, AgentKind, AgentStatus, DetectOptions};
+    use std::sync::Arc;
+
+    /// Resolves every name to a fixed path.
+    #[derive(Debug)]
+    struct FixedResolver(PathBuf);
+
+    impl Resolver for FixedResolver {
+        fn resolve(&self, _name: &str) -> Option<PathBuf> {
+            Some(self.0.clone())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_detect_with_mock_resolver() {
+        let dir = tempfile::tempdir().unwrap();
+        let synthetic = dir.path().join("synthetic-codex");
+        std::fs::write(&synthetic, "fake").unwrap();
+
+        let options = DetectOptions {
+            resolver: Arc::new(FixedResolver(synthetic.clone())),
+            skip_version: true,
+            ..Default::default()
+        };
+        let status = detect_with_options(AgentKind::Codex, options).await;
+
+        match status {
+            AgentStatus::Installed(meta) => assert_eq!(meta.path, synthetic),
+            other => panic!("expected Installed, got {:?}", other),
+        }
+    }
+
+    #[derive(Debug)]
+    struct EmptyResolver;
+
+    impl Resolver for EmptyResolver {
+        fn resolve(&self, _name: &str) -> Option<PathBuf> {
+            None
+        }
+    }
+
+    #[tokio::test]
+    async fn test_detect_with_empty_resolver() {
+        let options = DetectOptions {
+            resolver: Arc::new(EmptyResolver),
+            ..Default::default()
+        };
+        let status = detect_with_options(AgentKind::Gemini, options).await;
+        assert!(matches!(status, AgentStatus::NotInstalled));
+    }
+}
@@ -0,0 +1,229 @@
+//! Language runtimes that some agents need in order to run.
+//!
+//! Agents distributed as npm packages (Codex, Gemini CLI) run on Node.js,
+//! so a working install also needs a recent enough Node.js on the system.
+//! This module describes those requirements and detects the installed
+//! runtime versions.
+
+use crate::detection::{find_executable, parse_version};
+use crate::AgentKind;
+use futures::future::join_all;
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::process::Command;
+use tokio::time::timeout;
+
+/// Timeout for running a runtime's version command.
+const RUNTIME_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A language runtime an agent runs on.
+///
+/// This enum is marked `#[non_exhaustive]` so runtimes for future
+/// Python- or Go-based agents can be added.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum Runtime {
+    /// Node.js (`node`)
+    Node,
+}
+
+impl Runtime {
+    /// Human-readable runtime name.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rig_acp_discovery::Runtime;
+    ///
+    /// assert_eq!(Runtime::Node.display_name(), "Node.js");
+    /// ```
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Node => "Node.js",
+        }
+    }
+
+    /// The runtime's executable name.
+    pub fn executable_name(&self) -> &'static str {
+        match self {
+            Self::Node => "node",
+        }
+    }
+}
+
+/// A runtime an agent needs, with the oldest supported version.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuntimeRequirement {
+    /// The runtime the agent runs on.
+    pub runtime: Runtime,
+
+    /// Oldest runtime version the agent supports.
+    pub min_version: Version,
+}
+
+/// A runtime requirement together with the runtime version found on the system.
+///
+/// Lets a UI show, for example, "Codex (needs Node.js 18+, have Node.js 20.11.0)".
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuntimeStatus {
+    /// What the agent requires.
+    pub requirement: RuntimeRequirement,
+
+    /// Installed runtime version, or `None` if the runtime wasn't found
+    /// or its version couldn't be read.
+    pub detected: Option<Version>,
+}
+
+impl RuntimeStatus {
+    /// Whether the detected runtime meets the requirement.
+    pub fn is_satisfied(&self) -> bool {
+        self.detected
+            .as_ref()
+            .is_some_and(|version| *version >= self.requirement.min_version)
+    }
+}
+
+/// Detect the runtime versions needed by every agent that has a requirement.
+///
+/// Each distinct runtime is checked once, concurrently. Agents without a
+/// [`runtime_requirement`](AgentKind::runtime_requirement) are left out.
+/// Results are in `AgentKind::all()` order.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use rig_acp_discovery::detect_runtimes;
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() {
+///     for (kind, status) in detect_runtimes().await {
+///         println!(
+///             "{} needs {} {}+, have {:?}",
+///             kind.display_name(),
+///             status.requirement.runtime.display_name(),
+///             status.requirement.min_version.major,
+///             status.detected
+///         );
+///     }
+/// }
+/// ```
+pub async fn detect_runtimes() -> Vec<(AgentKind, RuntimeStatus)> {
+    detect_runtimes_with(runtime_version).await
+}
+
+/// [`detect_runtimes`] with an injectable runtime version check.
+pub(crate) async fn detect_runtimes_with<F, Fut>(check: F) -> Vec<(AgentKind, RuntimeStatus)>
+where
+    F: Fn(Runtime) -> Fut,
+    Fut: Future<Output = Option<Version>>,
+{
+    let requirements: Vec<_> = AgentKind::all()
+        .filter_map(|kind| kind.runtime_requirement().map(|req| (kind, req)))
+        .collect();
+
+    let mut runtimes: Vec<Runtime> = Vec::new();
+    for (_, requirement) in &requirements {
+        if !runtimes.contains(&requirement.runtime) {
+            runtimes.push(requirement.runtime);
+        }
+    }
+    let versions = join_all(runtimes.iter().map(|&runtime| check(runtime))).await;
+    let detected: HashMap<Runtime, Option<Version>> = runtimes.into_iter().zip(versions).collect();
+
+    requirements
+        .into_iter()
+        .map(|(kind, requirement)| {
+            let detected = detected.get(&requirement.runtime).cloned().flatten();
+            (
+                kind,
+                RuntimeStatus {
+                    requirement,
+                    detected,
+                },
+            )
+        })
+        .collect()
+}
+
+/// Run the runtime's `--version` and parse the result.
+async fn runtime_version(runtime: Runtime) -> Option<Version> {
+    let output = version_output(runtime.executable_name()).await?;
+    parse_version(&output).map(|(version, _)| version)
+}
+
+/// Run `<program> --version`, returning stdout (or stderr if stdout is empty).
+///
+/// The program is looked up like an agent executable, including Windows
+/// `.cmd` shims, and run with stdin closed. Returns `None` if it isn't
+/// found, fails to start, exits with an error, or runs longer than five
+/// seconds.
+pub(crate) async fn version_output(program: &str) -> Option<String> {
+    let mut command = Command::new(find_executable(program)?);
+    command
+        .arg("--version")
+        .stdin(Stdio::null())
+        .kill_on_drop(true);
+
+    let output = timeout(RUNTIME_CHECK_TIMEOUT, command.output())
+        .await
+        .ok()?
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stream = if output.stdout.is_empty() {
+        &output.stderr
+    } else {
+        &output.stdout
+    };
+    Some(String::from_utf8_lossy(stream).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn test_detect_runtimes_reports_detected_version() {
+        let calls = AtomicUsize::new(0);
+        let runtimes = detect_runtimes_with(|runtime| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            async move {
+                assert_eq!(runtime, Runtime::Node);
+                Some(Version::new(20, 11, 0))
+            }
+        })
+        .await;
+
+        // Node.js is checked once for both npm-based agents
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        let kinds: Vec<_> = runtimes.iter().map(|(kind, _)| *kind).collect();
+        assert_eq!(kinds, [AgentKind::Codex, AgentKind::Gemini]);
+
+        let (_, codex) = &runtimes[0];
+        assert_eq!(codex.requirement.min_version, Version::new(18, 0, 0));
+        assert_eq!(codex.detected, Some(Version::new(20, 11, 0)));
+        assert!(codex.is_satisfied());
+    }
+
+    #[tokio::test]
+    async fn test_detect_runtimes_missing_runtime() {
+        let runtimes = detect_runtimes_with(|_| async { None }).await;
+        assert!(runtimes.iter().all(|(_, status)| status.detected.is_none()));
+        assert!(runtimes.iter().all(|(_, status)| !status.is_satisfied()));
+    }
+
+    #[test]
+    fn test_is_satisfied_below_minimum() {
+        let status = RuntimeStatus {
+            requirement: AgentKind::Gemini.runtime_requirement().unwrap(),
+            detected: Some(Version::new(18, 19, 0)),
+        };
+        assert!(!status.is_satisfied());
+    }
+}
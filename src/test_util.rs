@@ -0,0 +1,90 @@
+//! Shared helpers for unit tests.
+
+use crate::InstalledMetadata;
+use semver::Version;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Metadata for an agent at `path` with `version`, verified now.
+///
+/// `raw_version` is the version's string form; every probed field is
+/// unset. Override fields with struct update syntax.
+pub(crate) fn installed_meta(
+    path: impl Into<PathBuf>,
+    version: Option<Version>,
+) -> InstalledMetadata {
+    InstalledMetadata {
+        raw_version: version.as_ref().map(ToString::to_string),
+        path: path.into(),
+        version,
+        install_method: None,
+        last_verified: SystemTime::now(),
+        reasoning_level: None,
+        default_model: None,
+        version_skipped: false,
+        binary_arch: None,
+        binary_type: None,
+        acp_capabilities: vec![],
+        config_schema_version: None,
+        capabilities: None,
+        edition: None,
+    }
+}
+
+/// Write an executable shell script named `name` into `dir`.
+///
+/// The script body is prefixed with a `#!/bin/sh` shebang.
+#[cfg(unix)]
+pub(crate) fn fake_executable(dir: &Path, name: &str, body: &str) -> PathBuf {
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = dir.join(name);
+    std::fs::write(&path, format!("#!/bin/sh\n{}\n", body)).unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    path
+}
+
+/// Script body that writes its PID to `pid_file`, then sleeps for a minute.
+///
+/// `exec` keeps the PID, so the recorded process is the one that was spawned.
+#[cfg(target_os = "linux")]
+pub(crate) fn record_pid_and_sleep(pid_file: &Path) -> String {
+    format!("echo $$ > '{}'\nexec sleep 60", pid_file.display())
+}
+
+/// Wait up to 5 seconds for `pid_file` to hold a PID.
+#[cfg(target_os = "linux")]
+pub(crate) async fn wait_for_pid(pid_file: &Path) -> Option<u32> {
+    for _ in 0..250 {
+        if let Some(pid) = std::fs::read_to_string(pid_file)
+            .ok()
+            .and_then(|pid| pid.trim().parse().ok())
+        {
+            return Some(pid);
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    None
+}
+
+/// Wait up to 5 seconds for process `pid` to exit and be reaped.
+///
+/// A zombie still has a `/proc` entry, so this fails for processes that
+/// were killed but never waited on.
+#[cfg(target_os = "linux")]
+pub(crate) async fn is_reaped(pid: u32) -> bool {
+    let proc_dir = PathBuf::from(format!("/proc/{}", pid));
+    for _ in 0..250 {
+        if !proc_dir.exists() {
+            return true;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    false
+}
+
+/// Whether the environment overrides where `kind` is found.
+pub(crate) fn has_path_override(kind: crate::AgentKind) -> bool {
+    std::env::var_os(kind.bin_env_var()).is_some()
+        || std::env::var_os(kind.path_env_var()).is_some()
+}
@@ -0,0 +1,463 @@
+//! Checking installed agents for newer releases.
+//!
+//! [`UpdateStatus`] compares an installed version with the latest release
+//! and flags updates that cross a major version. With the `network`
+//! feature, [`check_for_update`] looks up the latest release in the npm
+//! registry. [`detect_or_plan_upgrade`] pairs a minimum-version check with
+//! the command that would bring an old install up to date.
+
+use crate::detection::find_executable;
+use crate::install::update_plan_command;
+use crate::{
+    detect_with_options, AgentKind, AgentStatus, DetectOptions, DetectionError, InstalledMetadata,
+    StructuredCommand,
+};
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::path::PathBuf;
+
+#[cfg(feature = "network")]
+use crate::install::npm_view_version;
+#[cfg(feature = "network")]
+use crate::{detect, Registry};
+
+/// Whether a newer release of an agent is available.
+///
+/// This enum is marked `#[non_exhaustive]` to allow adding new states in
+/// future versions.
+///
+/// # Example
+///
+/// ```rust
+/// use rig_acp_discovery::UpdateStatus;
+/// use semver::Version;
+///
+/// let status = UpdateStatus::compare(Version::new(0, 87, 0), Version::new(1, 0, 0));
+/// if let UpdateStatus::Available { latest, breaking, .. } = status {
+///     if breaking {
+///         println!("{} is a major update; review the changelog first", latest);
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum UpdateStatus {
+    /// The installed version is the latest release (or newer).
+    UpToDate {
+        /// The installed version.
+        current: Version,
+    },
+
+    /// A newer release is available.
+    Available {
+        /// The installed version.
+        current: Version,
+        /// The latest release.
+        latest: Version,
+        /// Whether the update crosses a major version
+        /// (`latest.major > current.major`) and may break compatibility.
+        breaking: bool,
+    },
+
+    /// The installed or latest version could not be determined.
+    Unknown,
+}
+
+impl UpdateStatus {
+    /// Compare an installed version with the latest release.
+    pub fn compare(current: Version, latest: Version) -> Self {
+        if latest > current {
+            let breaking = latest.major > current.major;
+            Self::Available {
+                current,
+                latest,
+                breaking,
+            }
+        } else {
+            Self::UpToDate { current }
+        }
+    }
+
+    /// Whether a newer release is available.
+    pub fn is_available(&self) -> bool {
+        matches!(self, Self::Available { .. })
+    }
+}
+
+/// Check whether a newer release of an agent is available.
+///
+/// Detects the installed version and looks up the latest release of the
+/// agent's npm package with `npm view`. Returns [`UpdateStatus::Unknown`]
+/// if the agent isn't installed, its version can't be parsed, it has no
+/// npm package, or the registry lookup fails.
+///
+/// Requires the `network` feature.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use rig_acp_discovery::{AgentKind, UpdateStatus, check_for_update};
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() {
+///     match check_for_update(AgentKind::Codex).await {
+///         UpdateStatus::Available { latest, breaking: true, .. } => {
+///             println!("Codex {} is available (major update)", latest);
+///         }
+///         UpdateStatus::Available { latest, .. } => println!("Codex {} is available", latest),
+///         _ => println!("No update found"),
+///     }
+/// }
+/// ```
+#[cfg(feature = "network")]
+pub async fn check_for_update(kind: AgentKind) -> UpdateStatus {
+    let Some(package) = npm_package(kind) else {
+        return UpdateStatus::Unknown;
+    };
+    let Some(current) = detect(kind).await.version().cloned() else {
+        return UpdateStatus::Unknown;
+    };
+
+    match npm_view_version(package).await {
+        Some(latest) => UpdateStatus::compare(current, latest),
+        None => UpdateStatus::Unknown,
+    }
+}
+
+/// The npm package an agent is published as, from its npm install method.
+#[cfg(feature = "network")]
+fn npm_package(kind: AgentKind) -> Option<String> {
+    kind.install_info()
+        .methods()
+        .into_iter()
+        .find(|descriptor| descriptor.method.registry() == Some(Registry::Npm))
+        .and_then(|descriptor| {
+            let args = descriptor.method.command.args;
+            args.into_iter().rev().find(|arg| !arg.starts_with('-'))
+        })
+}
+
+/// How to bring an agent that is too old up to a required version.
+///
+/// Returned in [`DetectOrPlan::UpgradeNeeded`]. The command is the one
+/// `update()` would run, chosen from how the agent was installed; show it
+/// to the user, or call `update()` to run it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpgradePlan {
+    /// The agent to upgrade.
+    pub agent: AgentKind,
+    /// The version that was found.
+    pub found: Version,
+    /// The required minimum version.
+    pub required: Version,
+    /// Path where the agent was found.
+    pub path: PathBuf,
+    /// The command that upgrades the agent.
+    pub command: StructuredCommand,
+    /// The command as a single shell line for display, with arguments
+    /// quoted where needed (e.g., "npm install -g @openai/codex@latest").
+    pub raw_command: String,
+}
+
+/// Result of [`detect_or_plan_upgrade`].
+///
+/// This enum is marked `#[non_exhaustive]` to allow adding new outcomes in
+/// future versions.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use rig_acp_discovery::{detect_or_plan_upgrade, AgentKind, DetectOrPlan};
+/// use semver::Version;
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() {
+///     match detect_or_plan_upgrade(AgentKind::Codex, Version::new(0, 50, 0)).await {
+///         DetectOrPlan::Usable(meta) => println!("Ready at {:?}", meta.path),
+///         DetectOrPlan::UpgradeNeeded(plan) => {
+///             println!("Codex {} is too old; run: {}", plan.found, plan.raw_command)
+///         }
+///         DetectOrPlan::NotInstalled => println!("Codex is not installed"),
+///         other => println!("Could not check Codex: {:?}", other),
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum DetectOrPlan {
+    /// The agent is installed and meets the minimum version.
+    Usable(InstalledMetadata),
+
+    /// The agent is installed but too old; upgrading resolves it.
+    UpgradeNeeded(UpgradePlan),
+
+    /// The agent is installed but too old, and `update()` can't upgrade it,
+    /// for example because a package manager none of its install methods
+    /// use installed it.
+    UpgradeUnavailable {
+        /// The version that was found.
+        found: Version,
+        /// The required minimum version.
+        required: Version,
+        /// Path where the agent was found.
+        path: PathBuf,
+        /// Actionable suggestion for upgrading the agent by hand.
+        fix: String,
+    },
+
+    /// The agent is not installed.
+    NotInstalled,
+
+    /// Detection failed, so the version couldn't be checked.
+    Unknown {
+        /// The type of error that occurred.
+        error: DetectionError,
+        /// Human-readable error message.
+        message: String,
+    },
+}
+
+/// Detect an agent and, if it is older than `min`, plan its upgrade.
+///
+/// Detects `kind` with `min` as `DetectOptions::min_version`. A version
+/// mismatch becomes [`DetectOrPlan::UpgradeNeeded`] with the command
+/// `update()` would run, so callers can handle "installed but too old"
+/// without assembling the fix themselves. If `update()` couldn't upgrade
+/// the install, it becomes [`DetectOrPlan::UpgradeUnavailable`] instead.
+pub async fn detect_or_plan_upgrade(kind: AgentKind, min: Version) -> DetectOrPlan {
+    let options = DetectOptions {
+        min_version: Some(min),
+        ..Default::default()
+    };
+    detect_or_plan_upgrade_with(kind, detect_with_options(kind, options), |program| {
+        find_executable(program).is_some()
+    })
+    .await
+}
+
+/// [`detect_or_plan_upgrade`] with an injectable detection and check for
+/// which install programs are available.
+async fn detect_or_plan_upgrade_with(
+    kind: AgentKind,
+    detect: impl Future<Output = AgentStatus>,
+    available: impl Fn(&str) -> bool,
+) -> DetectOrPlan {
+    match detect.await {
+        AgentStatus::Installed(meta) => DetectOrPlan::Usable(meta),
+        AgentStatus::NotInstalled => DetectOrPlan::NotInstalled,
+        AgentStatus::VersionMismatch {
+            found,
+            required,
+            path,
+        } => match update_plan_command(kind, &path, available) {
+            Ok(command) => {
+                let raw_command = std::iter::once(&command.program)
+                    .chain(&command.args)
+                    .map(|word| shell_quote(word))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                DetectOrPlan::UpgradeNeeded(UpgradePlan {
+                    agent: kind,
+                    found,
+                    required,
+                    path,
+                    command,
+                    raw_command,
+                })
+            }
+            Err(error) => DetectOrPlan::UpgradeUnavailable {
+                found,
+                required,
+                path,
+                fix: error.fix_suggestion().to_string(),
+            },
+        },
+        AgentStatus::Unknown { error, message } => DetectOrPlan::Unknown { error, message },
+    }
+}
+
+/// Quote `word` for a POSIX shell if it contains anything but plain
+/// command-line characters.
+fn shell_quote(word: &str) -> String {
+    let plain = |c: char| c.is_ascii_alphanumeric() || "@%+=:,./_-".contains(c);
+    if !word.is_empty() && word.chars().all(plain) {
+        word.to_string()
+    } else {
+        format!("'{}'", word.replace('\'', "'\\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_patch_update_is_not_breaking() {
+        let status = UpdateStatus::compare(Version::new(0, 87, 0), Version::new(0, 87, 2));
+        assert_eq!(
+            status,
+            UpdateStatus::Available {
+                current: Version::new(0, 87, 0),
+                latest: Version::new(0, 87, 2),
+                breaking: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_major_update_is_breaking() {
+        let status = UpdateStatus::compare(Version::new(1, 4, 0), Version::new(2, 0, 0));
+        assert!(matches!(
+            status,
+            UpdateStatus::Available { breaking: true, .. }
+        ));
+    }
+
+    #[test]
+    fn test_up_to_date() {
+        let current = Version::new(2, 1, 3);
+        let status = UpdateStatus::compare(current.clone(), Version::new(2, 1, 3));
+        assert_eq!(status, UpdateStatus::UpToDate { current });
+        assert!(!status.is_available());
+    }
+
+    #[cfg(feature = "network")]
+    #[test]
+    fn test_npm_package() {
+        assert_eq!(
+            npm_package(AgentKind::Codex).as_deref(),
+            Some("@openai/codex")
+        );
+        assert_eq!(
+            npm_package(AgentKind::ClaudeCode).as_deref(),
+            Some("@anthropic-ai/claude-code")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_detect_or_plan_upgrade_usable() {
+        let meta = crate::test_util::installed_meta("/usr/bin/codex", Some(Version::new(1, 2, 0)));
+        let result = detect_or_plan_upgrade_with(
+            AgentKind::Codex,
+            async { AgentStatus::Installed(meta) },
+            |_| true,
+        )
+        .await;
+        assert!(matches!(result, DetectOrPlan::Usable(meta) if meta.path.ends_with("codex")));
+    }
+
+    #[tokio::test]
+    async fn test_detect_or_plan_upgrade_plans_mismatch() {
+        let mismatch = AgentStatus::VersionMismatch {
+            found: Version::new(0, 1, 0),
+            required: Version::new(1, 0, 0),
+            path: PathBuf::from("/usr/local/bin/codex"),
+        };
+        let DetectOrPlan::UpgradeNeeded(plan) =
+            detect_or_plan_upgrade_with(AgentKind::Codex, async { mismatch }, |_| true).await
+        else {
+            panic!("Expected an upgrade plan");
+        };
+        assert_eq!(plan.agent, AgentKind::Codex);
+        assert_eq!(plan.found, Version::new(0, 1, 0));
+        assert_eq!(plan.required, Version::new(1, 0, 0));
+        assert_eq!(plan.command.program, "npm");
+        assert!(
+            plan.raw_command.ends_with("@openai/codex@latest"),
+            "{}",
+            plan.raw_command
+        );
+
+        // Claude Code of unknown origin updates itself from the detected executable
+        let mismatch = AgentStatus::VersionMismatch {
+            found: Version::new(0, 1, 0),
+            required: Version::new(1, 0, 0),
+            path: PathBuf::from("/opt/claude/claude"),
+        };
+        let DetectOrPlan::UpgradeNeeded(plan) =
+            detect_or_plan_upgrade_with(AgentKind::ClaudeCode, async { mismatch }, |_| true).await
+        else {
+            panic!("Expected an upgrade plan");
+        };
+        assert_eq!(plan.raw_command, "/opt/claude/claude update");
+    }
+
+    #[cfg(not(windows))]
+    #[tokio::test]
+    async fn test_detect_or_plan_upgrade_follows_install_method() {
+        let mismatch = |path: &str| AgentStatus::VersionMismatch {
+            found: Version::new(0, 1, 0),
+            required: Version::new(1, 0, 0),
+            path: PathBuf::from(path),
+        };
+
+        // An npm copy of Claude Code is planned through npm, as update() runs it
+        let npm_claude = mismatch("/usr/lib/node_modules/@anthropic-ai/claude-code/cli.js");
+        let DetectOrPlan::UpgradeNeeded(plan) =
+            detect_or_plan_upgrade_with(AgentKind::ClaudeCode, async { npm_claude }, |_| true)
+                .await
+        else {
+            panic!("Expected an upgrade plan");
+        };
+        assert_eq!(
+            plan.raw_command,
+            "npm install -g @anthropic-ai/claude-code@latest"
+        );
+
+        // Shell commands are quoted so they can be pasted as shown
+        let opencode = mismatch("/home/u/.opencode/bin/opencode");
+        let DetectOrPlan::UpgradeNeeded(plan) =
+            detect_or_plan_upgrade_with(AgentKind::OpenCode, async { opencode }, |_| true).await
+        else {
+            panic!("Expected an upgrade plan");
+        };
+        assert_eq!(
+            plan.raw_command,
+            "bash -c 'curl -fSL --progress-bar https://opencode.ai/install | bash'"
+        );
+
+        // Codex has no brew method to upgrade a brew copy with
+        let brew_codex = mismatch("/opt/homebrew/bin/codex");
+        let result =
+            detect_or_plan_upgrade_with(AgentKind::Codex, async { brew_codex }, |_| true).await;
+        assert!(
+            matches!(&result, DetectOrPlan::UpgradeUnavailable { fix, .. } if fix.contains("brew")),
+            "{:?}",
+            result
+        );
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("@openai/codex@latest"), "@openai/codex@latest");
+        assert_eq!(shell_quote("a b"), "'a b'");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+        assert_eq!(shell_quote(""), "''");
+    }
+
+    #[tokio::test]
+    async fn test_detect_or_plan_upgrade_not_installed_or_unknown() {
+        let result = detect_or_plan_upgrade_with(
+            AgentKind::Gemini,
+            async { AgentStatus::NotInstalled },
+            |_| true,
+        )
+        .await;
+        assert!(matches!(result, DetectOrPlan::NotInstalled));
+
+        let unknown = AgentStatus::Unknown {
+            error: DetectionError::Timeout,
+            message: "timed out".to_string(),
+        };
+        let result =
+            detect_or_plan_upgrade_with(AgentKind::Gemini, async { unknown }, |_| true).await;
+        assert!(matches!(
+            result,
+            DetectOrPlan::Unknown {
+                error: DetectionError::Timeout,
+                ..
+            }
+        ));
+    }
+}
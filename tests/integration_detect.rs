@@ -168,7 +168,7 @@ async fn test_detect_all_with_options_custom_timeout() {
     assert_eq!(results.len(), 4);
 
     // Each result should be valid
-    for (_, result) in &results {
+    for result in results.values() {
         assert!(result.is_ok() || result.is_err());
     }
 }